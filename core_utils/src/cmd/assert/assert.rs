@@ -1,14 +1,21 @@
 use super::error::AssertError;
 use super::reason::AssertReason;
+use super::snapshot::{
+    read_snapshot, snapshot_path, update_snapshots, write_snapshot, Redactions,
+};
 use super::tree::CaseTree;
 use crate::cmd::{
     color::Palette,
     outputs::output_fmt,
     predicate::{IntoCodePredicate, IntoOutputPredicate},
 };
+use crate::predicates::core::{CaseTreeExt as _, Predicate as _};
+use crate::predicates::str::diff;
 use predicates_core;
 use predicates_tree::CaseTreeExt;
 use std::fmt;
+use std::panic::Location;
+use std::path::Path;
 use std::process;
 
 pub type AssertResult = Result<Assert, AssertError>;
@@ -20,6 +27,8 @@ pub struct Assert {
     pub(crate) output: process::Output, // 进程输出
     pub(crate) context:
         Vec<(&'static str, Box<dyn fmt::Display + Send + Sync>)>,
+    pub(crate) redactions: Redactions,
+    pub(crate) update: bool, // 是否写入不一致的快照
 }
 
 impl Assert {
@@ -28,7 +37,12 @@ impl Assert {
     /// [`Output`]: std::process::Output
     pub fn new(output: process::Output) -> Self {
         // 构造函数，上下文默认为空
-        Self { output, context: vec![] }
+        Self {
+            output,
+            context: vec![],
+            redactions: Default::default(),
+            update: update_snapshots(),
+        }
     }
 
     fn into_error(self, reason: AssertReason) -> AssertError {
@@ -196,6 +210,123 @@ impl Assert {
         }
         Ok(self)
     }
+
+    /// Sets the rules applied to the output before comparing it to a
+    /// snapshot, see [`Redactions`].
+    pub fn redactions(mut self, redactions: Redactions) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// Whether missing or different snapshots are written instead of
+    /// failing, defaults to [`update_snapshots`].
+    pub fn update_snapshots(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Ensure the redacted stdout equals the snapshot `name`.
+    ///
+    /// Snapshots are stored in `snapshots/<test file>__<name>.snap` next to
+    /// the calling test, run with `UPDATE_SNAPSHOTS=1` to create or update
+    /// them.
+    #[track_caller]
+    pub fn stdout_snapshot(self, name: &str) -> Self {
+        self.try_stdout_snapshot(name).unwrap_or_else(AssertError::panic)
+    }
+
+    /// Variant of [`Assert::stdout_snapshot`] that returns an [`AssertResult`].
+    #[track_caller]
+    pub fn try_stdout_snapshot(self, name: &str) -> AssertResult {
+        let path = snapshot_path(Location::caller(), name);
+        self.try_stdout_snapshot_at(path)
+    }
+
+    /// Ensure the redacted stdout equals the snapshot stored at `path`.
+    #[track_caller]
+    pub fn stdout_snapshot_at<P: AsRef<Path>>(self, path: P) -> Self {
+        self.try_stdout_snapshot_at(path).unwrap_or_else(AssertError::panic)
+    }
+
+    /// Variant of [`Assert::stdout_snapshot_at`] that returns an
+    /// [`AssertResult`].
+    pub fn try_stdout_snapshot_at<P: AsRef<Path>>(
+        self,
+        path: P,
+    ) -> AssertResult {
+        self.snapshot_impl("stdout", path.as_ref())
+    }
+
+    /// Ensure the redacted stderr equals the snapshot `name`, see
+    /// [`Assert::stdout_snapshot`].
+    #[track_caller]
+    pub fn stderr_snapshot(self, name: &str) -> Self {
+        self.try_stderr_snapshot(name).unwrap_or_else(AssertError::panic)
+    }
+
+    /// Variant of [`Assert::stderr_snapshot`] that returns an [`AssertResult`].
+    #[track_caller]
+    pub fn try_stderr_snapshot(self, name: &str) -> AssertResult {
+        let path = snapshot_path(Location::caller(), name);
+        self.try_stderr_snapshot_at(path)
+    }
+
+    /// Ensure the redacted stderr equals the snapshot stored at `path`.
+    #[track_caller]
+    pub fn stderr_snapshot_at<P: AsRef<Path>>(self, path: P) -> Self {
+        self.try_stderr_snapshot_at(path).unwrap_or_else(AssertError::panic)
+    }
+
+    /// Variant of [`Assert::stderr_snapshot_at`] that returns an
+    /// [`AssertResult`].
+    pub fn try_stderr_snapshot_at<P: AsRef<Path>>(
+        self,
+        path: P,
+    ) -> AssertResult {
+        self.snapshot_impl("stderr", path.as_ref())
+    }
+
+    fn snapshot_impl(self, stream: &'static str, path: &Path) -> AssertResult {
+        let actual = match stream {
+            "stderr" => &self.output.stderr,
+            _ => &self.output.stdout,
+        };
+        let actual = String::from_utf8_lossy(actual);
+        let actual = self.redactions.redact(&actual).into_owned();
+        let path = path.to_path_buf();
+
+        let expected = match read_snapshot(&path) {
+            Ok(expected) => expected,
+            Err(error) => {
+                return Err(self
+                    .into_error(AssertReason::SnapshotError { path, error }))
+            }
+        };
+
+        match expected {
+            Some(expected) if expected == actual => Ok(self),
+            // 接受新的输出
+            _ if self.update => match write_snapshot(&path, &actual) {
+                Ok(()) => Ok(self),
+                Err(error) => Err(self
+                    .into_error(AssertReason::SnapshotError { path, error })),
+            },
+            None => Err(self
+                .into_error(AssertReason::MissingSnapshot { stream, path })),
+            Some(expected) => {
+                let pred = diff(expected);
+                let case_tree = pred
+                    .find_case(false, &actual)
+                    .map(|case| case.tree().to_string())
+                    .unwrap_or_default();
+                Err(self.into_error(AssertReason::UnexpectedSnapshot {
+                    stream,
+                    path,
+                    case_tree,
+                }))
+            }
+        }
+    }
 }
 
 impl fmt::Display for Assert {
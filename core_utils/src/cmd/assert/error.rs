@@ -1,5 +1,6 @@
 use super::assert::Assert;
 use super::reason::AssertReason;
+use super::snapshot::UPDATE_SNAPSHOTS_ENV;
 use crate::str::DebugBytes;
 use std::error::Error;
 use std::fmt;
@@ -58,6 +59,44 @@ impl fmt::Display for AssertError {
             AssertReason::UnexpectedStderr { case_tree } => {
                 writeln!(f, "Unexpected stderr, failed {}", case_tree)
             }
+            AssertReason::MissingSnapshot { stream, path } => writeln!(
+                f,
+                "Missing {} snapshot {}, run with {}=1 to create it",
+                stream,
+                path.display(),
+                UPDATE_SNAPSHOTS_ENV
+            ),
+            AssertReason::UnexpectedSnapshot { stream, path, case_tree } => {
+                writeln!(
+                    f,
+                    "Unexpected {}, snapshot {} failed {}",
+                    stream,
+                    path.display(),
+                    case_tree
+                )
+            }
+            AssertReason::SnapshotError { path, error } => {
+                writeln!(
+                    f,
+                    "Failed to access snapshot {}: {}",
+                    path.display(),
+                    error
+                )
+            }
+            AssertReason::ExpectTimeout { stream, timeout, case_tree } => {
+                writeln!(
+                    f,
+                    "Timed out after {:?} waiting for {}, failed {}",
+                    timeout, stream, case_tree
+                )
+            }
+            AssertReason::ExpectEof { stream, case_tree } => {
+                writeln!(
+                    f,
+                    "Unexpected end of {}, failed {}",
+                    stream, case_tree
+                )
+            }
         }?;
         write!(f, "{}", self.assert)
     }
@@ -2,10 +2,12 @@ mod assert;
 mod error;
 mod output_assert_ext;
 mod reason;
+mod snapshot;
 mod tree;
 
 pub use assert::*;
 pub use error::*;
 pub use output_assert_ext::*;
-pub use reason::*;
-pub use tree::*;
+pub(crate) use reason::*;
+pub use snapshot::{update_snapshots, Redactions, UPDATE_SNAPSHOTS_ENV};
+pub(crate) use tree::*;
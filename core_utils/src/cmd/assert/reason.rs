@@ -1,4 +1,7 @@
 use super::tree::CaseTree;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub(crate) enum AssertReason {
@@ -9,4 +12,17 @@ pub(crate) enum AssertReason {
     UnexpectedReturnCode { case_tree: CaseTree },
     UnexpectedStdout { case_tree: CaseTree },
     UnexpectedStderr { case_tree: CaseTree },
+    MissingSnapshot { stream: &'static str, path: PathBuf },
+    UnexpectedSnapshot {
+        stream: &'static str,
+        path: PathBuf,
+        case_tree: String,
+    },
+    SnapshotError { path: PathBuf, error: io::Error },
+    ExpectTimeout {
+        stream: &'static str,
+        timeout: Duration,
+        case_tree: CaseTree,
+    },
+    ExpectEof { stream: &'static str, case_tree: CaseTree },
 }
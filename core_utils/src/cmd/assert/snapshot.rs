@@ -0,0 +1,122 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::io;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+
+/// 设置为 1 时用实际输出更新快照
+pub const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Rules replacing the parts of an output that change between runs before
+/// it is compared to a snapshot.
+///
+/// The default rules replace timestamps with `[TIMESTAMP]`, paths in the
+/// temporary directory with `[TEMP]` and `pid` values with `[PID]`.
+#[derive(Clone, Debug)]
+pub struct Redactions {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Default for Redactions {
+    fn default() -> Self {
+        let temp_dir = env::temp_dir();
+        let temp_dir = temp_dir.to_string_lossy();
+        let temp_dir = temp_dir.trim_end_matches(['/', '\\']);
+
+        Redactions::none()
+            .add(
+                Regex::new(&format!(r#"{}[^\s"']*"#, regex::escape(temp_dir)))
+                    .unwrap(),
+                "[TEMP]",
+            )
+            .add(
+                Regex::new(
+                    r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:?\d{2})?",
+                )
+                .unwrap(),
+                "[TIMESTAMP]",
+            )
+            .add(Regex::new(r"(?i)(\bpid[=:]?\s*)\d+").unwrap(), "${1}[PID]")
+    }
+}
+
+impl Redactions {
+    /// No redaction rules.
+    pub fn none() -> Self {
+        Redactions { rules: vec![] }
+    }
+
+    /// Replaces the matches of `regex` with `placeholder`, which may refer to
+    /// capture groups as in [`Regex::replace_all`].
+    pub fn add<S: Into<String>>(
+        mut self,
+        regex: Regex,
+        placeholder: S,
+    ) -> Self {
+        self.rules.push((regex, placeholder.into()));
+        self
+    }
+
+    /// Applies the rules in order.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for (regex, placeholder) in &self.rules {
+            if let Cow::Owned(redacted) =
+                regex.replace_all(&text, placeholder.as_str())
+            {
+                text = Cow::Owned(redacted);
+            }
+        }
+        text
+    }
+}
+
+/// Whether snapshots should be updated instead of compared.
+pub fn update_snapshots() -> bool {
+    env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| v == "1")
+}
+
+/// The path of the snapshot `name` of the test file calling the assertion,
+/// `snapshots/<test file>__<name>.snap` next to the test.
+pub(crate) fn snapshot_path(caller: &Location<'_>, name: &str) -> PathBuf {
+    let file = Path::new(caller.file());
+    // file!() 相对于工作区根目录，而测试的当前目录是包目录
+    let file = if file.is_absolute() {
+        file.to_path_buf()
+    } else {
+        env::current_dir()
+            .ok()
+            .and_then(|cwd| {
+                cwd.ancestors()
+                    .map(|dir| dir.join(file))
+                    .find(|path| path.exists())
+            })
+            .unwrap_or_else(|| file.to_path_buf())
+    };
+
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    file.parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("snapshots")
+        .join(format!("{}__{}.snap", stem, name))
+}
+
+pub(crate) fn read_snapshot(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(snapshot) => Ok(Some(snapshot)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn write_snapshot(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
+}
@@ -1,6 +1,8 @@
-mod assert;
+pub mod assert;
 mod cargo;
 mod color;
 mod outputs;
 mod predicate;
-mod process;
+pub mod process;
+#[cfg(feature = "serialize")]
+pub mod testcase;
@@ -156,6 +156,22 @@ impl Command {
     pub fn assert(&mut self) -> Assert {
         OutputAssertExt::assert(self)
     }
+
+    /// Spawn the `Command` under a pseudo-terminal for an interactive
+    /// [`Session`].
+    ///
+    /// The input set by [`Command::write_stdin`] is sent once the child is
+    /// started, and the timeout bounds each expectation.
+    ///
+    /// [`Session`]: crate::cmd::process::Session
+    #[cfg(unix)]
+    pub fn spawn_pty(&mut self) -> io::Result<super::Session> {
+        let mut session = super::Session::spawn(&self.cmd, self.timeout)?;
+        if let Some(stdin) = self.stdin.as_ref() {
+            session.send(stdin)?;
+        }
+        Ok(session)
+    }
 }
 
 /// Mirror [`std::process::Command`]'s API
@@ -371,7 +387,7 @@ impl Command {
     /// assert!(output.status.success());
     /// ```
     pub fn output(&mut self) -> io::Result<process::Output> {
-        let spawn = self.spawn()?;
+        let spawn = self.spawn_piped()?;
         Self::wait_with_input_output(
             spawn,
             self.stdin.as_deref().cloned(),
@@ -379,7 +395,52 @@ impl Command {
         )
     }
 
-    fn spawn(&mut self) -> io::Result<process::Child> {
+    /// Executes the `Command` as a child process without waiting for it,
+    /// returning a [`Running`] handle to read its output while it runs.
+    ///
+    /// The input set by [`Command::write_stdin`] is written once the child
+    /// is started and stdin stays open until [`Running::finish`].
+    ///
+    /// [`Running`]: crate::cmd::process::Running
+    /// [`Running::finish`]: crate::cmd::process::Running::finish
+    pub fn spawn(&mut self) -> io::Result<super::Running> {
+        let child = self.spawn_piped()?;
+        super::Running::new(
+            format!("{:?}", self.cmd),
+            child,
+            self.stdin.clone(),
+            self.timeout,
+        )
+    }
+
+    /// Like [`Command::output`], but stderr is written to the same pipe as
+    /// stdout so `Output::stdout` holds both streams interleaved.
+    #[cfg(feature = "serialize")] // 只有测试用例用到
+    pub(crate) fn output_merged(&mut self) -> io::Result<process::Output> {
+        let (mut reader, writer) = io::pipe()?;
+        self.cmd.stdin(process::Stdio::piped());
+        self.cmd.stdout(writer.try_clone()?);
+        self.cmd.stderr(writer);
+        let child = self.cmd.spawn();
+        // 关闭父进程持有的写端，否则子进程退出后读不到 EOF
+        self.cmd.stdout(process::Stdio::null());
+        self.cmd.stderr(process::Stdio::null());
+        let child = child?;
+
+        let merged = std::thread::spawn(move || {
+            let mut ret = Vec::new();
+            reader.read_to_end(&mut ret).map(|_| ret)
+        });
+        let mut output = Self::wait_with_input_output(
+            child,
+            self.stdin.as_deref().cloned(),
+            self.timeout,
+        )?;
+        output.stdout = merged.join().unwrap()?;
+        Ok(output)
+    }
+
+    fn spawn_piped(&mut self) -> io::Result<process::Child> {
         // stdout/stderr should only be piped for `output` according to `process::Command::new`.
         self.cmd.stdin(process::Stdio::piped());
         self.cmd.stdout(process::Stdio::piped());
@@ -1,3 +1,9 @@
 mod cmd;
+#[cfg(unix)]
+mod pty;
+mod running;
 
 pub use cmd::Command;
+#[cfg(unix)]
+pub use pty::Session;
+pub use running::Running;
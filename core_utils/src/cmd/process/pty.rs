@@ -0,0 +1,322 @@
+//! Interactive sessions driving a child process through a pseudo-terminal.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::unix::process::CommandExt;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nix::pty::{openpty, Winsize};
+
+use crate::cmd::assert::AssertReason;
+use crate::cmd::assert::{Assert, AssertError};
+use crate::cmd::predicate::IntoOutputPredicate;
+use crate::str::DebugBuffer;
+
+use super::running::case_tree;
+
+/// 等待输出的默认超时时间
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 一次交互
+#[derive(Debug)]
+enum Exchange {
+    Send(Vec<u8>),
+    Expect { predicate: String, received: Vec<u8> },
+}
+
+/// A child process running under a pseudo-terminal, driven expect-style.
+///
+/// Create a `Session` with [`Command::spawn_pty`]. The child's stdin, stdout
+/// and stderr are all the terminal, so `isatty` (and `IsTerminal`) is true
+/// inside the child and output from both streams is interleaved.
+///
+/// Every exchange is recorded and shown in the [`Assert`] context when an
+/// expectation fails or when the session is [finished](Session::finish).
+///
+/// [`Command::spawn_pty`]: crate::cmd::process::Command::spawn_pty
+#[derive(Debug)]
+pub struct Session {
+    cmd: String,
+    child: process::Child,
+    master: fs::File,
+    output: mpsc::Receiver<Vec<u8>>,
+    buffer: Vec<u8>,     // 还没有被 expect 消费的输出
+    transcript: Vec<u8>, // 收到的全部输出
+    exchanges: Vec<Exchange>,
+    timeout: Duration,
+}
+
+impl Session {
+    pub(crate) fn spawn(
+        cmd: &process::Command,
+        timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let winsize =
+            Winsize { ws_row: 24, ws_col: 80, ws_xpixel: 0, ws_ypixel: 0 };
+        let pty = openpty(Some(&winsize), None)?;
+
+        // 在副本上设置终端和 pre_exec，调用者的 Command 保持不变
+        let mut pty_cmd = clone_command(cmd);
+        pty_cmd.stdin(pty.slave.try_clone()?);
+        pty_cmd.stdout(pty.slave.try_clone()?);
+        pty_cmd.stderr(pty.slave);
+        // SAFETY: 只调用了 async-signal-safe 的函数
+        unsafe {
+            pty_cmd.pre_exec(|| {
+                // 创建新会话，并将终端设置为控制终端，这样 Ctrl-C 才会发送 SIGINT
+                if libc::setsid() == -1
+                    || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1
+                {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = pty_cmd.spawn();
+        // 关闭父进程持有的终端从设备，否则子进程退出后读不到 EOF
+        drop(pty_cmd);
+        let child = child?;
+
+        let master = fs::File::from(pty.master);
+        let mut reader = master.try_clone()?;
+        let (sender, output) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    // 子进程关闭终端后，Linux 上返回 EIO
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if sender.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            cmd: format!("{:?}", cmd),
+            child,
+            master,
+            output,
+            buffer: Vec::new(),
+            transcript: Vec::new(),
+            exchanges: Vec::new(),
+            timeout: timeout.unwrap_or(DEFAULT_TIMEOUT),
+        })
+    }
+}
+
+/// 复制程序、参数、环境变量和工作目录，`env_clear` 无法从 `Command` 中读出
+fn clone_command(cmd: &process::Command) -> process::Command {
+    let mut clone = process::Command::new(cmd.get_program());
+    clone.args(cmd.get_args());
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => clone.env(key, value),
+            None => clone.env_remove(key),
+        };
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        clone.current_dir(dir);
+    }
+    clone
+}
+
+impl Session {
+    /// How long [`Session::expect`] waits for matching output, defaults to
+    /// the `Command`'s timeout or 10 seconds.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait until the output received since the last expectation matches
+    /// `pred`, returning that output.
+    ///
+    /// The predicate is evaluated on the whole pending output, so prefer
+    /// `predicates::str::contains` or `is_match` over an exact match.
+    #[track_caller]
+    pub fn expect<I, P>(&mut self, pred: I) -> String
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.try_expect(pred).unwrap_or_else(|err| err.panic())
+    }
+
+    /// Variant of [`Session::expect`] that returns an [`AssertError`].
+    ///
+    /// On failure the child is killed.
+    pub fn try_expect<I, P>(
+        &mut self,
+        pred: I,
+    ) -> Result<String, Box<AssertError>>
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.expect_impl(&pred.into_output())
+    }
+
+    fn expect_impl(
+        &mut self,
+        pred: &dyn predicates_core::Predicate<[u8]>,
+    ) -> Result<String, Box<AssertError>> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if pred.eval(&self.buffer) {
+                let received = mem::take(&mut self.buffer);
+                let matched = String::from_utf8_lossy(&received).into_owned();
+                self.exchanges.push(Exchange::Expect {
+                    predicate: pred.to_string(),
+                    received,
+                });
+                return Ok(matched);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let reason = match self.output.recv_timeout(remaining) {
+                Ok(chunk) => {
+                    self.receive(chunk);
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    AssertReason::ExpectTimeout {
+                        stream: "output",
+                        timeout: self.timeout,
+                        case_tree: case_tree(pred, &self.buffer),
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    AssertReason::ExpectEof {
+                        stream: "output",
+                        case_tree: case_tree(pred, &self.buffer),
+                    }
+                }
+            };
+
+            self.exchanges.push(Exchange::Expect {
+                predicate: pred.to_string(),
+                received: mem::take(&mut self.buffer),
+            });
+            let _ = self.child.kill();
+            return Err(Box::new(AssertError {
+                assert: self.take_assert(),
+                reason,
+            }));
+        }
+    }
+
+    /// Write `bytes` to the terminal.
+    pub fn send<B: AsRef<[u8]>>(&mut self, bytes: B) -> io::Result<()> {
+        let bytes = bytes.as_ref();
+        self.exchanges.push(Exchange::Send(bytes.to_vec()));
+        self.master.write_all(bytes)?;
+        self.master.flush()
+    }
+
+    /// Write `line` followed by a newline to the terminal.
+    pub fn send_line(&mut self, line: &str) -> io::Result<()> {
+        self.send(format!("{}\n", line))
+    }
+
+    /// Send the control character `Ctrl-<c>`, e.g. `send_control('c')` to
+    /// interrupt the child or `send_control('d')` for end of file.
+    pub fn send_control(&mut self, c: char) -> io::Result<()> {
+        let byte = match c {
+            'a'..='z' => c as u8 - b'a' + 1,
+            'A'..='Z' | '@' | '[' | '\\' | ']' | '^' | '_' => c as u8 & 0x1f,
+            '?' => 0x7f,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("no control character for {:?}", c),
+                ))
+            }
+        };
+        self.send([byte])
+    }
+
+    /// Wait for the child to exit, killing it once the timeout is reached,
+    /// and make assertions on its status and remaining output.
+    ///
+    /// The whole terminal output is available as stdout.
+    pub fn finish(mut self) -> Assert {
+        let status = wait_timeout::ChildExt::wait_timeout(
+            &mut self.child,
+            self.timeout,
+        )
+        .transpose()
+        .unwrap_or_else(|| {
+            let _ = self.child.kill();
+            self.child.wait()
+        });
+        if let Err(err) = status {
+            panic!("Failed to wait for {}: {}", self.cmd, err);
+        }
+
+        // 读取剩余的输出，后台的孙进程可能一直持有终端，所以也要有超时
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.output.recv_timeout(remaining) {
+                Ok(chunk) => self.receive(chunk),
+                Err(_) => break,
+            }
+        }
+        self.take_assert()
+    }
+
+    fn receive(&mut self, chunk: Vec<u8>) {
+        self.buffer.extend_from_slice(&chunk);
+        self.transcript.extend_from_slice(&chunk);
+    }
+
+    /// 将所有交互记录到断言的上下文中
+    fn take_assert(&mut self) -> Assert {
+        let status = match self.child.wait() {
+            Ok(status) => status,
+            Err(err) => panic!("Failed to wait for {}: {}", self.cmd, err),
+        };
+        let output = process::Output {
+            status,
+            stdout: self.transcript.clone(),
+            stderr: Vec::new(),
+        };
+
+        let mut assert =
+            Assert::new(output).append_context("command", self.cmd.clone());
+        for exchange in mem::take(&mut self.exchanges) {
+            assert = match exchange {
+                Exchange::Send(bytes) => {
+                    assert.append_context("send", DebugBuffer::new(bytes))
+                }
+                Exchange::Expect { predicate, received } => assert
+                    .append_context("expect", predicate)
+                    .append_context("received", DebugBuffer::new(received)),
+            };
+        }
+        if !self.buffer.is_empty() {
+            assert = assert.append_context(
+                "pending",
+                DebugBuffer::new(mem::take(&mut self.buffer)),
+            );
+        }
+        assert
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Handle on a long-running child process with streaming output.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use predicates_tree::CaseTreeExt;
+
+use crate::cmd::assert::{Assert, AssertError, AssertReason, CaseTree};
+use crate::str::DebugBuffer;
+
+/// 输出流
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn name(self) -> &'static str {
+        match self {
+            Stream::Stdout => "stdout",
+            Stream::Stderr => "stderr",
+        }
+    }
+}
+
+/// 单个输出流收到的内容
+#[derive(Debug, Default)]
+struct Received {
+    all: Vec<u8>,             // 收到的全部输出
+    pending: Vec<u8>,         // 上次 wait_for 匹配之后收到的输出
+    lines: VecDeque<Vec<u8>>, // 还没有被读取的行
+    partial: Vec<u8>,         // 还没有换行符的最后一行
+    closed: bool,             // 输出流已关闭
+}
+
+impl Received {
+    fn extend(&mut self, chunk: &[u8]) {
+        self.all.extend_from_slice(chunk);
+        self.pending.extend_from_slice(chunk);
+
+        self.partial.extend_from_slice(chunk);
+        while let Some(end) = self.partial.iter().position(|&b| b == b'\n') {
+            let rest = self.partial.split_off(end + 1);
+            self.lines.push_back(std::mem::replace(&mut self.partial, rest));
+        }
+    }
+
+    fn close(&mut self) {
+        if !self.partial.is_empty() {
+            self.lines.push_back(std::mem::take(&mut self.partial));
+        }
+        self.closed = true;
+    }
+}
+
+/// A child process spawned by [`Command::spawn`], whose output is read while
+/// it runs.
+///
+/// Wait for the output to match a predicate, e.g. until a server is
+/// listening, or read it line by line. Waits match the output as it is
+/// received, so prompts without a trailing newline are seen too. The timeout of the `Command` bounds
+/// the whole run: once it is reached waits fail and the child is killed.
+///
+/// The child is killed when the handle is dropped.
+///
+/// [`Command::spawn`]: crate::cmd::process::Command::spawn
+#[derive(Debug)]
+pub struct Running {
+    cmd: String,
+    stdin: Option<bstr::BString>,
+    // 写入 stdin 的线程在发送端被丢弃后关闭 stdin
+    stdin_writer: Option<mpsc::Sender<()>>,
+    child: process::Child,
+    output: mpsc::Receiver<(Stream, Option<Vec<u8>>)>,
+    stdout: Received,
+    stderr: Received,
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl Running {
+    pub(crate) fn new(
+        cmd: String,
+        mut child: process::Child,
+        stdin: Option<bstr::BString>,
+        timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let (sender, output) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            read_chunks(Stream::Stdout, stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            read_chunks(Stream::Stderr, stderr, sender);
+        }
+
+        // 在线程中写入 stdin，子进程不读取 stdin 时也不会阻塞调用方
+        // stdin 保持打开，直到 finish 或者 drop
+        let stdin_writer = match (stdin.as_ref(), child.stdin.take()) {
+            (Some(input), Some(child_stdin)) => {
+                Some(write_stdin(child_stdin, input.to_vec()))
+            }
+            (_, child_stdin) => {
+                child.stdin = child_stdin;
+                None
+            }
+        };
+
+        Ok(Self {
+            cmd,
+            stdin,
+            stdin_writer,
+            child,
+            output,
+            stdout: Received::default(),
+            stderr: Received::default(),
+            timeout,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        })
+    }
+}
+
+/// 启动一个线程写入 stdin，返回的发送端被丢弃后关闭 stdin
+fn write_stdin(
+    mut stdin: process::ChildStdin,
+    input: Vec<u8>,
+) -> mpsc::Sender<()> {
+    let (close, closed) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        let _ = stdin.write_all(&input);
+        let _ = closed.recv();
+    });
+    close
+}
+
+/// 启动一个线程按块读取输出流，流关闭时发送 None
+fn read_chunks<R>(
+    stream: Stream,
+    mut input: R,
+    sender: mpsc::Sender<(Stream, Option<Vec<u8>>)>,
+) where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buf = [0; 8192];
+        loop {
+            match input.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if sender.send((stream, Some(buf[..n].to_vec()))).is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        let _ = sender.send((stream, None));
+    });
+}
+
+impl Running {
+    /// OS-assigned process identifier of the child.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Wait until the stdout received since the last wait on stdout matches
+    /// `pred`, returning that output.
+    #[track_caller]
+    pub fn wait_for_stdout<P>(&mut self, pred: P) -> String
+    where
+        P: predicates_core::Predicate<str>,
+    {
+        self.try_wait_for_stdout(pred).unwrap_or_else(|err| err.panic())
+    }
+
+    /// Variant of [`Running::wait_for_stdout`] that returns an
+    /// [`AssertError`].
+    ///
+    /// On failure the child is killed.
+    pub fn try_wait_for_stdout<P>(
+        &mut self,
+        pred: P,
+    ) -> Result<String, Box<AssertError>>
+    where
+        P: predicates_core::Predicate<str>,
+    {
+        self.wait_for_impl(Stream::Stdout, &pred)
+    }
+
+    /// Wait until the stderr received since the last wait on stderr matches
+    /// `pred`, returning that output.
+    #[track_caller]
+    pub fn wait_for_stderr<P>(&mut self, pred: P) -> String
+    where
+        P: predicates_core::Predicate<str>,
+    {
+        self.try_wait_for_stderr(pred).unwrap_or_else(|err| err.panic())
+    }
+
+    /// Variant of [`Running::wait_for_stderr`] that returns an
+    /// [`AssertError`].
+    ///
+    /// On failure the child is killed.
+    pub fn try_wait_for_stderr<P>(
+        &mut self,
+        pred: P,
+    ) -> Result<String, Box<AssertError>>
+    where
+        P: predicates_core::Predicate<str>,
+    {
+        self.wait_for_impl(Stream::Stderr, &pred)
+    }
+
+    fn wait_for_impl(
+        &mut self,
+        stream: Stream,
+        pred: &dyn predicates_core::Predicate<str>,
+    ) -> Result<String, Box<AssertError>> {
+        loop {
+            let received = self.received(stream);
+            let pending =
+                String::from_utf8_lossy(&received.pending).into_owned();
+            if pred.eval(&pending) {
+                received.pending.clear();
+                return Ok(pending);
+            }
+
+            let reason = if received.closed {
+                AssertReason::ExpectEof {
+                    stream: stream.name(),
+                    case_tree: case_tree(pred, &pending),
+                }
+            } else if self.receive() {
+                continue;
+            } else {
+                AssertReason::ExpectTimeout {
+                    stream: stream.name(),
+                    timeout: self.timeout.unwrap_or_default(),
+                    case_tree: case_tree(pred, &pending),
+                }
+            };
+            let _ = self.child.kill();
+            return Err(Box::new(AssertError {
+                assert: self.take_assert(),
+                reason,
+            }));
+        }
+    }
+
+    /// Read the next line of stdout, `None` once stdout is closed.
+    ///
+    /// Lines are read independently of [`Running::wait_for_stdout`].
+    pub fn read_stdout_line(&mut self) -> io::Result<Option<String>> {
+        self.read_line(Stream::Stdout)
+    }
+
+    /// Read the next line of stderr, `None` once stderr is closed.
+    ///
+    /// Lines are read independently of [`Running::wait_for_stderr`].
+    pub fn read_stderr_line(&mut self) -> io::Result<Option<String>> {
+        self.read_line(Stream::Stderr)
+    }
+
+    fn read_line(&mut self, stream: Stream) -> io::Result<Option<String>> {
+        loop {
+            let received = self.received(stream);
+            if let Some(line) = received.lines.pop_front() {
+                return Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+            if received.closed {
+                return Ok(None);
+            }
+            if !self.receive() {
+                let _ = self.child.kill();
+                return Err(timed_out());
+            }
+        }
+    }
+
+    /// Send the signal `signal` to the child.
+    #[cfg(unix)]
+    pub fn signal(&self, signal: nix::sys::signal::Signal) -> io::Result<()> {
+        let pid = nix::unistd::Pid::from_raw(self.child.id() as i32);
+        nix::sys::signal::kill(pid, signal)?;
+        Ok(())
+    }
+
+    /// Kill the child.
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Close stdin and wait for the child to exit, killing it once the
+    /// timeout is reached, then make assertions on its status and output.
+    pub fn finish(mut self) -> Assert {
+        drop(self.child.stdin.take());
+        drop(self.stdin_writer.take());
+        let status = match self.deadline {
+            Some(deadline) => {
+                let remaining =
+                    deadline.saturating_duration_since(Instant::now());
+                wait_timeout::ChildExt::wait_timeout(
+                    &mut self.child,
+                    remaining,
+                )
+                .transpose()
+                .unwrap_or_else(|| {
+                    let _ = self.child.kill();
+                    self.child.wait()
+                })
+            }
+            None => self.child.wait(),
+        };
+        if let Err(err) = status {
+            panic!("Failed to wait for {}: {}", self.cmd, err);
+        }
+        self.take_assert()
+    }
+
+    fn received(&mut self, stream: Stream) -> &mut Received {
+        match stream {
+            Stream::Stdout => &mut self.stdout,
+            Stream::Stderr => &mut self.stderr,
+        }
+    }
+
+    /// 在截止时间之前接收一块输出，超时返回 false
+    fn receive(&mut self) -> bool {
+        let timeout = self.deadline.map(|deadline| {
+            deadline.saturating_duration_since(Instant::now())
+        });
+        self.receive_timeout(timeout)
+    }
+
+    fn receive_timeout(&mut self, timeout: Option<Duration>) -> bool {
+        let message = match timeout {
+            Some(timeout) => self.output.recv_timeout(timeout),
+            None => self
+                .output
+                .recv()
+                .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        match message {
+            Ok((stream, Some(chunk))) => self.received(stream).extend(&chunk),
+            Ok((stream, None)) => self.received(stream).close(),
+            Err(mpsc::RecvTimeoutError::Timeout) => return false,
+            // 两个输出流都已关闭
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                self.stdout.close();
+                self.stderr.close();
+            }
+        }
+        true
+    }
+
+    /// 等待子进程退出，读取剩余的输出，生成断言
+    fn take_assert(&mut self) -> Assert {
+        let status = match self.child.wait() {
+            Ok(status) => status,
+            Err(err) => panic!("Failed to wait for {}: {}", self.cmd, err),
+        };
+        // 孙进程可能一直持有输出流，所以也要有超时
+        while !(self.stdout.closed && self.stderr.closed) {
+            if !self.receive_timeout(Some(Duration::from_millis(100))) {
+                break;
+            }
+        }
+
+        let output = process::Output {
+            status,
+            stdout: std::mem::take(&mut self.stdout.all),
+            stderr: std::mem::take(&mut self.stderr.all),
+        };
+        let assert =
+            Assert::new(output).append_context("command", self.cmd.clone());
+        if let Some(stdin) = self.stdin.take() {
+            assert.append_context("stdin", DebugBuffer::new(stdin.into()))
+        } else {
+            assert
+        }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "timed out waiting for output")
+}
+
+/// 生成断言失败时的 case tree
+pub(crate) fn case_tree<T: ?Sized>(
+    pred: &dyn predicates_core::Predicate<T>,
+    actual: &T,
+) -> CaseTree {
+    let case = pred.find_case(false, actual).unwrap_or_else(|| {
+        predicates_core::reflection::Case::new(None, false)
+    });
+    CaseTree(case.tree())
+}
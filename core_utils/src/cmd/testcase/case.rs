@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+
+/// Expected exit status of a [`Step`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Status {
+    /// Exited with code `0`.
+    #[default]
+    Success,
+    /// Exited with any non-zero code.
+    Failed,
+    /// Killed by any signal, including the kill on timeout, or ended
+    /// without an exit code.
+    Interrupted,
+    /// Killed by the given signal, e.g. `signal 15` for `SIGTERM`.
+    Signal(i32),
+    /// Exited with the given code.
+    Code(i32),
+}
+
+impl Status {
+    /// 解析 `success`、`failed`、`interrupted`、`signal <N>` 或者返回码
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "success" => Some(Status::Success),
+            "failed" => Some(Status::Failed),
+            "interrupted" => Some(Status::Interrupted),
+            s => match s.strip_prefix("signal ") {
+                Some(signal) => signal.trim().parse().ok().map(Status::Signal),
+                None => s.parse().ok().map(Status::Code),
+            },
+        }
+    }
+
+    pub(crate) fn matches(self, status: &ExitStatus) -> bool {
+        match (self, status.code()) {
+            (Status::Success, Some(code)) => code == 0,
+            (Status::Failed, Some(code)) => code != 0,
+            (Status::Interrupted, None) => true,
+            (Status::Signal(expected), None) => {
+                signal(status) == Some(expected)
+            }
+            (Status::Code(expected), Some(code)) => code == expected,
+            _ => false,
+        }
+    }
+}
+
+/// 终止子进程的信号，只有 unix 上有
+#[cfg(unix)]
+pub(crate) fn signal(status: &ExitStatus) -> Option<i32> {
+    std::os::unix::process::ExitStatusExt::signal(status)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Success => write!(f, "success"),
+            Status::Failed => write!(f, "failed"),
+            Status::Interrupted => write!(f, "interrupted"),
+            Status::Signal(signal) => write!(f, "signal {}", signal),
+            Status::Code(code) => write!(f, "{}", code),
+        }
+    }
+}
+
+/// One command of a test case file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Step {
+    /// Where the step is defined, `<file>` or `<file>:<line>`.
+    pub location: String,
+    /// The binary, registered with `TestCases::register_bin`, built by cargo
+    /// or looked up in `PATH`.
+    pub bin: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    /// Working directory relative to the sandbox.
+    pub cwd: Option<PathBuf>,
+    pub stdin: Option<String>,
+    pub status: Status,
+    /// Expected stdout, not checked when `None`.
+    pub stdout: Option<String>,
+    /// Expected stderr, not checked when `None`.
+    pub stderr: Option<String>,
+    /// Compare the expected stdout to stdout and stderr interleaved in the
+    /// order they were written.
+    pub merge_stderr: bool,
+}
+
+/// A test case file, its steps run in order in one sandbox directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestCase {
+    pub path: PathBuf,
+    pub steps: Vec<Step>,
+    /// Directory copied into the sandbox before the first step, the
+    /// `<name>.in` directory next to the case file.
+    pub sandbox: Option<PathBuf>,
+}
+
+/// `.toml` 文件的内容
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TomlCase {
+    bin: String,
+    args: Args,
+    env: BTreeMap<String, String>,
+    cwd: Option<PathBuf>,
+    stdin: Option<String>,
+    status: Option<toml::Value>,
+    stdout: Option<String>,
+    stderr: Option<String>,
+}
+
+/// 参数可以是数组，也可以是用空白分隔的字符串
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Args {
+    List(Vec<String>),
+    Line(String),
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args::List(Vec::new())
+    }
+}
+
+impl TestCase {
+    /// Load a `.toml` or `.md` test case file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let steps = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => vec![parse_toml(path, &content)?],
+            Some("md") => parse_markdown(path, &content)?,
+            _ => return Err(invalid(path, "expected a .toml or .md file")),
+        };
+
+        let sandbox = path.with_extension("in");
+        let sandbox = if sandbox.is_dir() { Some(sandbox) } else { None };
+        Ok(Self { path: path.to_path_buf(), steps, sandbox })
+    }
+}
+
+/// 解析 `.toml` 文件，没有 stdout、stderr 时读取同名的 `.stdout`、`.stderr` 文件
+fn parse_toml(path: &Path, content: &str) -> io::Result<Step> {
+    let case: TomlCase = toml::from_str(content)
+        .map_err(|err| invalid(path, &err.to_string()))?;
+    if case.bin.is_empty() {
+        return Err(invalid(path, "missing `bin`"));
+    }
+
+    let status = match case.status {
+        None => Status::Success,
+        Some(toml::Value::Integer(code)) => Status::Code(code as i32),
+        Some(toml::Value::String(s)) => {
+            Status::parse(&s).ok_or_else(|| {
+                invalid(path, &format!("invalid status {:?}", s))
+            })?
+        }
+        Some(value) => {
+            return Err(invalid(path, &format!("invalid status {}", value)))
+        }
+    };
+    let args = match case.args {
+        Args::List(args) => args,
+        Args::Line(line) => split_args(&line)
+            .ok_or_else(|| invalid(path, "unterminated quote in `args`"))?,
+    };
+    let stdout = match case.stdout {
+        Some(stdout) => Some(stdout),
+        None => read_optional(&path.with_extension("stdout"))?,
+    };
+    let stderr = match case.stderr {
+        Some(stderr) => Some(stderr),
+        None => read_optional(&path.with_extension("stderr"))?,
+    };
+
+    Ok(Step {
+        location: path.display().to_string(),
+        bin: case.bin,
+        args,
+        env: case.env,
+        cwd: case.cwd,
+        stdin: case.stdin,
+        status,
+        stdout,
+        stderr,
+        merge_stderr: false,
+    })
+}
+
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// 解析 markdown 中的 ```console 代码块
+///
+/// ```text
+/// $ bin --flag arg
+/// ? failed
+/// expected output
+/// ```
+///
+/// `$` 行是命令，可以用 `NAME=value` 前缀设置环境变量，紧跟的 `?` 行是期望的状态，
+/// 之后直到下一个命令的行是期望的 stdout 和 stderr
+fn parse_markdown(path: &Path, content: &str) -> io::Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    let mut in_block = false;
+    let mut current: Option<Step> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let fence = line.trim_start();
+        if !in_block {
+            if let Some(info) = fence.strip_prefix("```") {
+                let info = info.trim();
+                in_block = info == "console" || info == "trycmd";
+            }
+            continue;
+        }
+        if fence.starts_with("```") {
+            in_block = false;
+            steps.extend(current.take());
+            continue;
+        }
+
+        if let Some(command) = line.strip_prefix("$ ") {
+            steps.extend(current.take());
+            let location = format!("{}:{}", path.display(), index + 1);
+            current = Some(parse_command(&location, command)?);
+        } else if let Some(step) = current.as_mut() {
+            let stdout = step.stdout.get_or_insert_with(String::new);
+            match line.strip_prefix("? ") {
+                Some(status) if stdout.is_empty() => {
+                    step.status = Status::parse(status).ok_or_else(|| {
+                        invalid(path, &format!("invalid status {:?}", status))
+                    })?;
+                }
+                _ => {
+                    stdout.push_str(line);
+                    stdout.push('\n');
+                }
+            }
+        }
+    }
+    steps.extend(current);
+
+    if steps.is_empty() {
+        return Err(invalid(path, "no ```console command found"));
+    }
+    Ok(steps)
+}
+
+fn parse_command(location: &str, command: &str) -> io::Result<Step> {
+    let words = split_args(command).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: unterminated quote", location),
+        )
+    })?;
+
+    let mut env = BTreeMap::new();
+    let mut words = words.into_iter().peekable();
+    while let Some((key, value)) = words
+        .peek()
+        .and_then(|word| word.split_once('='))
+        .filter(|(key, _)| is_env_name(key))
+    {
+        env.insert(key.to_string(), value.to_string());
+        words.next();
+    }
+    let bin = words.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: missing command", location),
+        )
+    })?;
+
+    Ok(Step {
+        location: location.to_string(),
+        bin,
+        args: words.collect(),
+        env,
+        stdout: Some(String::new()),
+        merge_stderr: true,
+        ..Default::default()
+    })
+}
+
+fn is_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 按空白分隔参数，支持单引号和双引号，引号没有闭合时返回 None
+fn split_args(line: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                current.get_or_insert_with(String::new).extend(chars.next())
+            }
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, '\\') => {
+                current.get_or_insert_with(String::new).extend(chars.next())
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    args.extend(current);
+    Some(args)
+}
+
+fn invalid(path: &Path, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), message),
+    )
+}
@@ -0,0 +1,9 @@
+//! Declarative CLI test cases (`.toml` and markdown files), see
+//! [`TestCases`].
+
+mod case;
+mod pattern;
+mod runner;
+
+pub use case::{Status, Step, TestCase};
+pub use runner::{Failure, TestCases, TestCasesError};
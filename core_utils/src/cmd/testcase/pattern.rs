@@ -0,0 +1,91 @@
+//! Expected output with wildcards.
+//!
+//! - a line containing only `...` matches any number of lines.
+//! - `[..]` inside a line matches any text on that line.
+
+/// 匹配任意多行
+const LINES_WILDCARD: &str = "...";
+/// 匹配行内任意文本
+const INLINE_WILDCARD: &str = "[..]";
+
+/// Whether `actual` matches the `expected` output.
+pub(crate) fn matches(expected: &str, actual: &str) -> bool {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    lines_match(&expected, &actual)
+}
+
+fn lines_match(expected: &[&str], actual: &[&str]) -> bool {
+    match expected.split_first() {
+        None => actual.is_empty(),
+        Some((&LINES_WILDCARD, rest)) => {
+            (0..=actual.len()).any(|skip| lines_match(rest, &actual[skip..]))
+        }
+        Some((line, rest)) => match actual.split_first() {
+            Some((actual_line, actual_rest)) => {
+                line_matches(line, actual_line)
+                    && lines_match(rest, actual_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// 匹配一行，`[..]` 匹配任意文本
+fn line_matches(expected: &str, actual: &str) -> bool {
+    let mut parts = expected.split(INLINE_WILDCARD);
+    let first = parts.next().unwrap_or_default();
+    let Some(mut actual) = actual.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return actual.is_empty();
+    };
+    for part in middle {
+        match actual.find(part) {
+            Some(index) => actual = &actual[index + part.len()..],
+            None => return false,
+        }
+    }
+    actual.ends_with(last)
+}
+
+/// Rewrite `actual` so that the parts matched by wildcards read like
+/// `expected`, leaving only the real differences for the diff.
+pub(crate) fn normalize(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut normalized = Vec::new();
+    let mut index = 0;
+    for (position, line) in expected.iter().enumerate() {
+        if *line == LINES_WILDCARD {
+            normalized.push(LINES_WILDCARD);
+            // 跳过下一行期望值之前的所有行
+            let next = expected.get(position + 1);
+            while index < actual.len()
+                && !next.is_some_and(|next| line_matches(next, actual[index]))
+            {
+                index += 1;
+            }
+            continue;
+        }
+        match actual.get(index) {
+            Some(actual_line) if line_matches(line, actual_line) => {
+                normalized.push(line);
+            }
+            Some(actual_line) => normalized.push(actual_line),
+            None => break,
+        }
+        index += 1;
+    }
+    normalized.extend(actual.iter().skip(index));
+
+    let mut normalized = normalized.join("\n");
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    normalized
+}
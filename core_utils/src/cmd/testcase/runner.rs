@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use walkdir::WalkDir;
+
+use super::case::{signal, Step, TestCase};
+use super::pattern;
+use crate::cmd::cargo::cargo_bin;
+use crate::cmd::color::Palette;
+use crate::cmd::outputs::output_fmt;
+use crate::cmd::process::Command;
+use crate::file::TempDir;
+use crate::predicates::core::{CaseTreeExt as _, Predicate as _};
+use crate::predicates::str::diff;
+
+/// Run the declarative test cases of a directory.
+///
+/// Each `.toml` file is one command, each `.md` file holds the commands of
+/// its ```` ```console ```` blocks. The steps of a file run in a fresh
+/// [`TempDir`] sandbox, seeded with the `<name>.in` directory when it exists.
+/// Files inside `.in` directories are never loaded as test cases.
+///
+/// ```toml
+/// bin = "mytool"
+/// args = ["--name", "world"]
+/// env = { LANG = "C" }
+/// stdin = ""
+/// status = "success"   # "failed", "interrupted", "signal 9" or an exit code
+/// stdout = """
+/// hello world
+/// ...
+/// """
+/// ```
+///
+/// ````markdown
+/// ```console
+/// $ mytool --bad
+/// ? 2
+/// error: unexpected argument [..]
+/// ```
+/// ````
+///
+/// The output of a markdown command is stdout and stderr interleaved as the
+/// child wrote them, so both streams should be unbuffered or line-buffered.
+/// In expected output a line `...` matches any number of lines and `[..]`
+/// matches any text on a line. Without `stdout`/`stderr` in a `.toml` file
+/// the `<name>.stdout`/`<name>.stderr` files are used, if any.
+#[derive(Debug, Default)]
+pub struct TestCases {
+    paths: Vec<PathBuf>,
+    bins: HashMap<String, PathBuf>,
+    env: BTreeMap<String, String>,
+    timeout: Option<Duration>,
+}
+
+impl TestCases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a test case file, or all `.toml` and `.md` files of a directory.
+    pub fn case<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Run `bin = name` with the program at `path`.
+    ///
+    /// Other names are looked up in the cargo target directory, then in
+    /// `PATH`.
+    pub fn register_bin<S, P>(&mut self, name: S, path: P) -> &mut Self
+    where
+        S: Into<String>,
+        P: AsRef<Path>,
+    {
+        self.bins.insert(name.into(), path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set an environment variable for every step.
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env.insert(key.into(), val.into());
+        self
+    }
+
+    /// Kill each step once `timeout` is reached.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run every test case, panicking with all mismatches on failure.
+    #[track_caller]
+    pub fn run(&self) {
+        if let Err(err) = self.try_run() {
+            panic!("{}", err);
+        }
+    }
+
+    /// Variant of [`TestCases::run`] that returns a [`TestCasesError`],
+    /// or the number of steps run.
+    pub fn try_run(&self) -> Result<usize, TestCasesError> {
+        let mut failures = Vec::new();
+        let mut count = 0;
+
+        for path in self.files(&mut failures) {
+            let case = match TestCase::load(&path) {
+                Ok(case) => case,
+                Err(err) => {
+                    failures.push(Failure::new(path.display(), err));
+                    continue;
+                }
+            };
+            count += case.steps.len();
+            self.run_case(&case, &mut failures);
+        }
+
+        if failures.is_empty() {
+            Ok(count)
+        } else {
+            Err(TestCasesError { count, failures })
+        }
+    }
+
+    /// 展开目录，按文件名排序
+    fn files(&self, failures: &mut Vec<Failure>) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in &self.paths {
+            if !path.is_dir() {
+                files.push(path.clone());
+                continue;
+            }
+            // `<name>.in` 是沙盒的输入，其中的文件不是测试用例
+            let walk = WalkDir::new(path)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|entry| !is_sandbox_dir(entry.path()));
+            for entry in walk {
+                match entry {
+                    Ok(entry) if is_case_file(entry.path()) => {
+                        files.push(entry.into_path())
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        failures.push(Failure::new(path.display(), err))
+                    }
+                }
+            }
+        }
+        files
+    }
+
+    /// 在同一个沙盒中依次执行所有步骤
+    fn run_case(&self, case: &TestCase, failures: &mut Vec<Failure>) {
+        let sandbox = TempDir::new().and_then(|sandbox| {
+            if let Some(input) = case.sandbox.as_ref() {
+                copy_dir(input, sandbox.path())?;
+            }
+            Ok(sandbox)
+        });
+        let sandbox = match sandbox {
+            Ok(sandbox) => sandbox,
+            Err(err) => {
+                failures.push(Failure::new(case.path.display(), err));
+                return;
+            }
+        };
+
+        for step in &case.steps {
+            failures.extend(self.run_step(step, sandbox.path()));
+        }
+    }
+
+    fn run_step(&self, step: &Step, sandbox: &Path) -> Option<Failure> {
+        let bin = self.resolve_bin(&step.bin);
+        let mut cmd = Command::new(&bin);
+        cmd.args(&step.args).envs(&self.env).envs(&step.env);
+        match step.cwd.as_ref() {
+            Some(cwd) => cmd.current_dir(sandbox.join(cwd)),
+            None => cmd.current_dir(sandbox),
+        };
+        if let Some(stdin) = step.stdin.as_ref() {
+            cmd.write_stdin(stdin.as_bytes());
+        }
+        if let Some(timeout) = self.timeout {
+            cmd.timeout(timeout);
+        }
+
+        let output =
+            if step.merge_stderr { cmd.output_merged() } else { cmd.output() };
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                return Some(Failure::new(
+                    &step.location,
+                    format!("Failed to spawn {:?}: {}", step.bin, err),
+                ))
+            }
+        };
+
+        let mut reasons = Vec::new();
+        if !step.status.matches(&output.status) {
+            reasons.push(format!(
+                "Unexpected status, expected {} but got {}",
+                step.status,
+                actual_status(&output.status)
+            ));
+        }
+
+        if let Some(expected) = step.stdout.as_ref() {
+            reasons.extend(compare("stdout", expected, &output.stdout));
+        }
+        if let Some(expected) = step.stderr.as_ref() {
+            reasons.extend(compare("stderr", expected, &output.stderr));
+        }
+
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(Failure {
+                location: step.location.clone(),
+                reasons,
+                command: Some(
+                    std::iter::once(bin.display().to_string())
+                        .chain(step.args.iter().cloned())
+                        .map(|arg| format!("{:?}", arg))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                output: Some(output),
+            })
+        }
+    }
+
+    fn resolve_bin(&self, name: &str) -> PathBuf {
+        if let Some(path) = self.bins.get(name) {
+            return path.clone();
+        }
+        let path = cargo_bin(name);
+        if path.is_file() {
+            path
+        } else {
+            PathBuf::from(name)
+        }
+    }
+}
+
+fn is_sandbox_dir(path: &Path) -> bool {
+    path.is_dir() && path.extension().is_some_and(|ext| ext == "in")
+}
+
+fn is_case_file(path: &Path) -> bool {
+    path.is_file()
+        && matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("toml" | "md")
+        )
+}
+
+/// 实际的退出状态，格式和 `Status` 相同
+fn actual_status(status: &process::ExitStatus) -> String {
+    match (status.code(), signal(status)) {
+        (Some(code), _) => code.to_string(),
+        (None, Some(signal)) => format!("signal {}", signal),
+        (None, None) => "<interrupted>".to_owned(),
+    }
+}
+
+/// 比较输出，不匹配时返回 diff
+fn compare(stream: &str, expected: &str, actual: &[u8]) -> Option<String> {
+    let actual = String::from_utf8_lossy(actual);
+    if pattern::matches(expected, &actual) {
+        return None;
+    }
+
+    let normalized = pattern::normalize(expected, &actual);
+    let expected = pattern::normalize(expected, expected);
+    let case_tree = diff(expected)
+        .find_case(false, &normalized)
+        .map(|case| case.tree().to_string())
+        .unwrap_or_default();
+    Some(format!("Unexpected {}, failed {}", stream, case_tree))
+}
+
+/// 将测试用例的输入目录复制到沙盒中
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(from) {
+        let entry = entry?;
+        let target =
+            to.join(entry.path().strip_prefix(from).unwrap_or(entry.path()));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// A failed test case step.
+#[derive(Debug)]
+pub struct Failure {
+    /// Where the step is defined, `<file>` or `<file>:<line>`.
+    pub location: String,
+    pub reasons: Vec<String>,
+    command: Option<String>,
+    output: Option<process::Output>,
+}
+
+impl Failure {
+    fn new<L: fmt::Display, R: fmt::Display>(location: L, reason: R) -> Self {
+        Self {
+            location: location.to_string(),
+            reasons: vec![reason.to_string()],
+            command: None,
+            output: None,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.location)?;
+        for reason in &self.reasons {
+            writeln!(f, "{}", reason.trim_end())?;
+        }
+        if let Some(command) = self.command.as_ref() {
+            let palette = Palette::color();
+            writeln!(
+                f,
+                "{:#}=`{:#}`",
+                palette.key("command"),
+                palette.value(command)
+            )?;
+        }
+        if let Some(output) = self.output.as_ref() {
+            output_fmt(output, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`TestCases::try_run`] error, with every failed step.
+#[derive(Debug)]
+pub struct TestCasesError {
+    count: usize,
+    failures: Vec<Failure>,
+}
+
+impl TestCasesError {
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+}
+
+impl Error for TestCasesError {}
+
+impl fmt::Display for TestCasesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} test case steps failed",
+            self.failures.len(),
+            self.count
+        )?;
+        for failure in &self.failures {
+            write!(f, "\n{}", failure)?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use file_hashing::get_hash_file;
 use md5::Md5;
 use sha1::{Digest, Sha1};
-use std::io::Error;
+use std::io::{self, Error, Read};
 use std::path::Path;
 
 pub fn md5<P: AsRef<Path>>(path: P) -> Result<String, Error> {
@@ -13,3 +13,17 @@ pub fn sha1<P: AsRef<Path>>(path: P) -> Result<String, Error> {
     let mut hasher = Sha1::new();
     get_hash_file(path, &mut hasher)
 }
+
+/// Like `md5`, for content that is not a file of the OS.
+pub fn md5_reader<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut hasher = Md5::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Like `sha1`, for content that is not a file of the OS.
+pub fn sha1_reader<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut hasher = Sha1::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
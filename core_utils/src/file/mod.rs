@@ -10,5 +10,9 @@ mod temp_file;
 mod temp_path;
 mod util;
 
+pub use builder::Builder;
+pub use dir::{tempdir, TempDir};
+pub use error::PersistError;
 pub use file_type::FileType;
-pub use hash::{md5, sha1};
+pub use hash::{md5, md5_reader, sha1, sha1_reader};
+pub use temp_file::NamedTempFile;
@@ -0,0 +1,265 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Error, PathAbs, PathFile, Result};
+use crate::file::{Builder, NamedTempFile};
+
+/// Write a file atomically, created with `FileWrite::atomic`.
+///
+/// The content goes to a temporary file next to the target, which replaces
+/// the target only when `commit` is called. Dropping it without committing
+/// leaves the target untouched.
+pub struct AtomicWrite {
+    target: PathAbs,
+    temp: NamedTempFile,
+}
+
+impl AtomicWrite {
+    /// Create the temporary file in the directory of `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<AtomicWrite> {
+        let target = PathAbs::new(path)?;
+        // 写入符号链接指向的文件，而不是替换符号链接
+        let target =
+            match fs::read_link(&target) {
+                Ok(_) => PathAbs::new(fs::canonicalize(&target).map_err(
+                    |err| Error::new(err, "resolving", target.clone().into()),
+                )?)?,
+                Err(_) => target,
+            };
+
+        let dir = target.as_path().parent().unwrap_or(Path::new("/"));
+        let mut prefix = OsString::from(".");
+        prefix.push(target.as_path().file_name().unwrap_or_default());
+        prefix.push(".");
+        // 临时文件和目标在同一个目录，保证 rename 是原子的
+        let temp = Builder::new()
+            .prefix(&prefix)
+            .suffix(".tmp")
+            .make_in(dir, |path| {
+                let mut options = fs::OpenOptions::new();
+                options.read(true).write(true).create_new(true);
+                // 新文件的权限和 File::create 一样受 umask 影响
+                #[cfg(unix)]
+                std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o666);
+                options.open(path)
+            })
+            .map_err(|err| {
+                Error::new(
+                    err,
+                    "creating temporary file for",
+                    target.clone().into(),
+                )
+            })?;
+        Ok(AtomicWrite { target, temp })
+    }
+
+    /// The path that is replaced on `commit`.
+    pub fn path(&self) -> &PathAbs {
+        &self.target
+    }
+
+    /// The path of the temporary file being written.
+    pub fn temp_path(&self) -> &Path {
+        self.temp.path()
+    }
+
+    pub fn write_str(&mut self, s: &str) -> Result<()> {
+        self.temp.write_all(s.as_bytes()).map_err(|err| {
+            Error::new(err, "writing", self.target.clone().into())
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.temp.flush().map_err(|err| {
+            Error::new(err, "flushing", self.target.clone().into())
+        })
+    }
+
+    /// Replace the target with the written content.
+    ///
+    /// The temporary file gets the permissions and, when the process is
+    /// allowed to change it, the ownership of the target, is synced to disk,
+    /// then renamed over the target. The parent directory is synced last so
+    /// that the rename survives a crash.
+    pub fn commit(self) -> Result<PathFile> {
+        self.finish(false)
+    }
+
+    /// Like `commit`, but keep the previous version of the target as
+    /// `<name>.bak`.
+    pub fn commit_with_backup(self) -> Result<PathFile> {
+        self.finish(true)
+    }
+
+    fn finish(mut self, backup: bool) -> Result<PathFile> {
+        self.flush()?;
+        let existing = match fs::metadata(&self.target) {
+            Ok(metadata) => Some(metadata),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                return Err(Error::new(
+                    err,
+                    "getting metadata for",
+                    self.target.clone().into(),
+                ))
+            }
+        };
+
+        if let Some(metadata) = &existing {
+            self.preserve(metadata)?;
+        }
+        self.temp.as_file().sync_all().map_err(|err| {
+            Error::new(err, "syncing", self.target.clone().into())
+        })?;
+
+        if backup && existing.is_some() {
+            backup_file(&self.target)?;
+        }
+
+        let AtomicWrite { target, temp } = self;
+        temp.persist(&target).map_err(|err| {
+            Error::new(err.error, "renaming over", target.clone().into())
+        })?;
+        sync_dir(&target)?;
+        Ok(PathFile::new_unchecked(target))
+    }
+
+    /// 复制目标文件的权限和所有者
+    fn preserve(&self, metadata: &fs::Metadata) -> Result<()> {
+        let file = self.temp.as_file();
+        file.set_permissions(metadata.permissions()).map_err(|err| {
+            Error::new(
+                err,
+                "preserving permissions of",
+                self.target.clone().into(),
+            )
+        })?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let current = file.metadata().map_err(|err| {
+                Error::new(
+                    err,
+                    "getting metadata for",
+                    self.temp.path().to_path_buf().into(),
+                )
+            })?;
+            // 所有者相同时不需要 chown，普通用户也能原子写入
+            if (current.uid(), current.gid())
+                != (metadata.uid(), metadata.gid())
+            {
+                match std::os::unix::fs::fchown(
+                    file,
+                    Some(metadata.uid()),
+                    Some(metadata.gid()),
+                ) {
+                    // chown 会清除 setuid/setgid 位，重新设置权限
+                    Ok(()) => file
+                        .set_permissions(metadata.permissions())
+                        .map_err(|err| {
+                            Error::new(
+                                err,
+                                "preserving permissions of",
+                                self.target.clone().into(),
+                            )
+                        })?,
+                    // 没有权限修改所有者时，新文件属于当前用户，只保留权限
+                    Err(err)
+                        if err.kind() == io::ErrorKind::PermissionDenied => {}
+                    Err(err) => {
+                        return Err(Error::new(
+                            err,
+                            "preserving ownership of",
+                            self.target.clone().into(),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The path of the backup of `path`, `<name>.bak`.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// 用硬链接保留旧版本，目标文件始终存在
+fn backup_file(target: &PathAbs) -> Result<()> {
+    let backup = backup_path(target.as_path());
+    match fs::remove_file(&backup) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(Error::new(err, "removing", backup.into())),
+    }
+    if fs::hard_link(target, &backup).is_err() {
+        // 文件系统不支持硬链接时复制
+        fs::copy(target, &backup).map_err(|err| {
+            Error::new(err, "backing up", target.clone().into())
+        })?;
+    }
+    Ok(())
+}
+
+/// 同步父目录，保证 rename 写入磁盘
+#[cfg(unix)]
+fn sync_dir(target: &PathAbs) -> Result<()> {
+    let dir = target.as_path().parent().unwrap_or(Path::new("/"));
+    File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .map_err(|err| Error::new(err, "syncing", dir.to_path_buf().into()))
+}
+
+/// Windows 不能打开目录同步，rename 由文件系统保证
+#[cfg(not(unix))]
+fn sync_dir(_target: &PathAbs) -> Result<()> {
+    Ok(())
+}
+
+impl fmt::Debug for AtomicWrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AtomicWrite({:?})", self.target)
+    }
+}
+
+impl io::Write for AtomicWrite {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.temp.write(buf).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!(
+                    "{} when writing to {}",
+                    err,
+                    self.target.as_path().display()
+                ),
+            )
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.temp.flush().map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!(
+                    "{} when flushing {}",
+                    err,
+                    self.target.as_path().display()
+                ),
+            )
+        })
+    }
+}
+
+impl AsRef<File> for AtomicWrite {
+    fn as_ref(&self) -> &File {
+        self.temp.as_file()
+    }
+}
@@ -0,0 +1,726 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+
+use super::{
+    FileKind, FileSystem, FsFile, Metadata, OpenOptions, Permissions,
+};
+
+/// 符号链接的最大解析次数，和 Linux 一致
+const MAX_SYMLINKS: usize = 40;
+/// umask 为 022 时新文件和目录的权限
+const FILE_MODE: u32 = 0o644;
+const DIR_MODE: u32 = 0o755;
+
+/// 没有记录的根目录，例如 Windows 的盘符
+static ROOT: Node =
+    Node::Dir(Attrs { mode: DIR_MODE, modified: SystemTime::UNIX_EPOCH });
+
+/// 和操作系统相同的错误，非 unix 系统只保留错误类型
+macro_rules! os_error {
+    ($errno:ident, $kind:ident) => {{
+        #[cfg(unix)]
+        let err = io::Error::from_raw_os_error(libc::$errno);
+        #[cfg(not(unix))]
+        let err = io::Error::from(io::ErrorKind::$kind);
+        err
+    }};
+}
+
+/// An operation of a `MemFs` that can be made to fail with `MemFs::fail`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Opening or creating a file.
+    Open,
+    Read,
+    Write,
+    /// Creating a directory or a symlink.
+    Create,
+    /// Removing a file, a directory or a symlink.
+    Remove,
+    Rename,
+    ReadDir,
+    /// Querying metadata, reading links and canonicalizing.
+    Metadata,
+    /// Syncing a file to the disk.
+    Sync,
+    /// Changing the permissions or the modification time.
+    SetPermissions,
+}
+
+/// An error injected in a `MemFs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// `ENOSPC`, no space left on device.
+    NoSpace,
+    /// `EACCES`, permission denied.
+    PermissionDenied,
+}
+
+impl Fault {
+    fn to_error(self) -> io::Error {
+        match self {
+            Fault::NoSpace => os_error!(ENOSPC, StorageFull),
+            Fault::PermissionDenied => os_error!(EACCES, PermissionDenied),
+        }
+    }
+}
+
+/// A file system in memory, to test code using the path types without
+/// touching the disk.
+///
+/// It supports symlinks and enforces the owner permission bits, as if the
+/// current user owned every file. Errors can be injected with `fail`. Paths
+/// must be absolute, and clones share the same files.
+///
+/// ```ignore
+/// let fs = MemFs::new();
+/// let dir = PathDir::create_in("/etc", fs.clone())?;
+/// fs.fail(Operation::Write, "/etc", Fault::NoSpace);
+/// let err = dir.join("app.toml").write_str("x").unwrap_err();
+/// ```
+#[derive(Clone)]
+pub struct MemFs(Arc<Mutex<State>>);
+
+struct State {
+    nodes: BTreeMap<PathBuf, Node>,
+    faults: Vec<(Operation, PathBuf, Fault)>,
+}
+
+enum Node {
+    // 文件内容共享，打开的文件在重命名和删除后仍然可用
+    File(Arc<Mutex<Inode>>),
+    Dir(Attrs),
+    Symlink(PathBuf),
+}
+
+struct Inode {
+    data: Vec<u8>,
+    attrs: Attrs,
+}
+
+struct Attrs {
+    mode: u32,
+    modified: SystemTime,
+}
+
+impl Attrs {
+    fn new(mode: u32) -> Attrs {
+        Attrs { mode, modified: SystemTime::now() }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl MemFs {
+    /// An empty file system with only the root directory.
+    pub fn new() -> MemFs {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), Node::Dir(Attrs::new(DIR_MODE)));
+        MemFs(Arc::new(Mutex::new(State { nodes, faults: Vec::new() })))
+    }
+
+    /// Make `operation` fail with `fault` on `path` and the paths below it,
+    /// until `clear_faults` is called.
+    pub fn fail<P: AsRef<Path>>(
+        &self,
+        operation: Operation,
+        path: P,
+        fault: Fault,
+    ) {
+        let path = path.as_ref().to_path_buf();
+        self.state().faults.push((operation, path, fault));
+    }
+
+    /// Remove the faults injected with `fail`.
+    pub fn clear_faults(&self) {
+        self.state().faults.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        lock(&self.0)
+    }
+}
+
+impl Default for MemFs {
+    fn default() -> MemFs {
+        MemFs::new()
+    }
+}
+
+impl fmt::Debug for MemFs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_tuple("MemFs").field(&state.nodes.keys()).finish()
+    }
+}
+
+impl State {
+    fn node(&self, path: &Path) -> Option<&Node> {
+        match self.nodes.get(path) {
+            Some(node) => Some(node),
+            None if path.has_root() && path.parent().is_none() => Some(&ROOT),
+            None => None,
+        }
+    }
+
+    /// 检查注入的错误并解析路径，`follow` 为 false 时不解析最后一个符号链接
+    fn enter(
+        &self,
+        operation: Operation,
+        path: &Path,
+        follow: bool,
+    ) -> io::Result<PathBuf> {
+        self.check(operation, path)?;
+        let resolved = self.resolve(path, follow, &mut 0)?;
+        self.check(operation, &resolved)?;
+        Ok(resolved)
+    }
+
+    fn check(&self, operation: Operation, path: &Path) -> io::Result<()> {
+        for (op, prefix, fault) in &self.faults {
+            if *op == operation && path.starts_with(prefix) {
+                return Err(fault.to_error());
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        path: &Path,
+        follow: bool,
+        links: &mut usize,
+    ) -> io::Result<PathBuf> {
+        if !path.has_root() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "MemFs paths must be absolute",
+            ));
+        }
+        let mut resolved = PathBuf::new();
+        let mut components = path.components().peekable();
+        while let Some(component) = components.next() {
+            let name = match component {
+                Component::Prefix(_) | Component::RootDir => {
+                    resolved.push(component);
+                    continue;
+                }
+                Component::CurDir => continue,
+                Component::ParentDir => {
+                    resolved.pop();
+                    continue;
+                }
+                Component::Normal(name) => name,
+            };
+            match self.node(&resolved) {
+                Some(Node::Dir(_)) => {}
+                Some(_) => return Err(os_error!(ENOTDIR, NotADirectory)),
+                None => return Err(os_error!(ENOENT, NotFound)),
+            }
+            let next = resolved.join(name);
+            let last = components.peek().is_none();
+            match self.node(&next) {
+                Some(Node::Symlink(target)) if follow || !last => {
+                    *links += 1;
+                    if *links > MAX_SYMLINKS {
+                        return Err(os_error!(ELOOP, Other));
+                    }
+                    // 相对路径相对于符号链接所在的目录
+                    let target = resolved.join(target);
+                    resolved = self.resolve(&target, true, links)?;
+                }
+                _ => resolved = next,
+            }
+        }
+        Ok(resolved)
+    }
+
+    fn mode(&self, path: &Path) -> u32 {
+        match self.node(path) {
+            Some(Node::File(inode)) => lock(inode).attrs.mode,
+            Some(Node::Dir(attrs)) => attrs.mode,
+            Some(Node::Symlink(_)) => 0o777,
+            None => 0,
+        }
+    }
+
+    /// 检查当前用户（文件所有者）的权限位
+    fn check_access(&self, path: &Path, bits: u32) -> io::Result<()> {
+        if self.mode(path) & bits == bits {
+            Ok(())
+        } else {
+            Err(os_error!(EACCES, PermissionDenied))
+        }
+    }
+
+    /// 创建和删除需要父目录的写权限
+    fn check_parent(&self, path: &Path) -> io::Result<()> {
+        match path.parent() {
+            Some(parent) => self.check_access(parent, 0o200),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the root directory can not be changed",
+            )),
+        }
+    }
+
+    fn has_children(&self, path: &Path) -> bool {
+        self.nodes.keys().any(|key| key.parent() == Some(path))
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let metadata = match self.node(path) {
+            Some(Node::File(inode)) => {
+                let inode = lock(inode);
+                Metadata::new(
+                    FileKind::File,
+                    inode.data.len() as u64,
+                    Permissions::from_mode(inode.attrs.mode),
+                    Some(inode.attrs.modified),
+                )
+            }
+            Some(Node::Dir(attrs)) => Metadata::new(
+                FileKind::Dir,
+                0,
+                Permissions::from_mode(attrs.mode),
+                Some(attrs.modified),
+            ),
+            Some(Node::Symlink(target)) => Metadata::new(
+                FileKind::Symlink,
+                target.as_os_str().len() as u64,
+                Permissions::from_mode(0o777),
+                None,
+            ),
+            None => return Err(os_error!(ENOENT, NotFound)),
+        };
+        Ok(metadata)
+    }
+
+    fn symlink(&mut self, src: &Path, dst: &Path) -> io::Result<()> {
+        let dst = self.enter(Operation::Create, dst, false)?;
+        if self.node(&dst).is_some() {
+            return Err(os_error!(EEXIST, AlreadyExists));
+        }
+        self.check_parent(&dst)?;
+        self.nodes.insert(dst, Node::Symlink(src.to_path_buf()));
+        Ok(())
+    }
+}
+
+impl FileSystem for MemFs {
+    type File = MemFile;
+    type ReadDir = std::vec::IntoIter<io::Result<PathBuf>>;
+
+    fn open(&self, path: &Path, options: &OpenOptions) -> io::Result<MemFile> {
+        let (read, write) = (options.is_read(), options.is_write());
+        let create = options.is_create() || options.is_create_new();
+        // 和 std 一样，创建和截断需要写权限
+        if !write && (!read || create || options.is_truncate()) {
+            return Err(os_error!(EINVAL, InvalidInput));
+        }
+
+        let mut state = self.state();
+        let path = state.enter(Operation::Open, path, true)?;
+        let inode = match state.node(&path) {
+            Some(Node::File(inode)) => {
+                if options.is_create_new() {
+                    return Err(os_error!(EEXIST, AlreadyExists));
+                }
+                let bits = if read { 0o400 } else { 0 }
+                    | if write { 0o200 } else { 0 };
+                state.check_access(&path, bits)?;
+                let inode = inode.clone();
+                if options.is_truncate() {
+                    let mut inode = lock(&inode);
+                    inode.data.clear();
+                    inode.attrs.modified = SystemTime::now();
+                }
+                inode
+            }
+            Some(Node::Dir(_)) => return Err(os_error!(EISDIR, IsADirectory)),
+            Some(Node::Symlink(_)) => return Err(os_error!(ELOOP, Other)),
+            None if create => {
+                state.check_parent(&path)?;
+                let inode = Arc::new(Mutex::new(Inode {
+                    data: Vec::new(),
+                    attrs: Attrs::new(FILE_MODE),
+                }));
+                state.nodes.insert(path.clone(), Node::File(inode.clone()));
+                inode
+            }
+            None => return Err(os_error!(ENOENT, NotFound)),
+        };
+        Ok(MemFile {
+            fs: self.clone(),
+            path,
+            inode,
+            position: 0,
+            read,
+            write,
+            append: options.is_append(),
+        })
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let state = self.state();
+        let path = state.enter(Operation::Metadata, path, true)?;
+        state.metadata(&path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let state = self.state();
+        let path = state.enter(Operation::Metadata, path, false)?;
+        state.metadata(&path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let state = self.state();
+        let path = state.enter(Operation::Metadata, path, false)?;
+        match state.node(&path) {
+            Some(Node::Symlink(target)) => Ok(target.clone()),
+            Some(_) => Err(os_error!(EINVAL, InvalidInput)),
+            None => Err(os_error!(ENOENT, NotFound)),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let state = self.state();
+        let path = state.enter(Operation::Metadata, path, true)?;
+        match state.node(&path) {
+            Some(_) => Ok(path),
+            None => Err(os_error!(ENOENT, NotFound)),
+        }
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        let path = state.enter(Operation::Create, path, false)?;
+        if state.node(&path).is_some() {
+            return Err(os_error!(EEXIST, AlreadyExists));
+        }
+        state.check_parent(&path)?;
+        state.nodes.insert(path, Node::Dir(Attrs::new(DIR_MODE)));
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        // 从根目录开始逐级创建
+        let ancestors: Vec<&Path> = path.ancestors().collect();
+        for dir in ancestors.into_iter().rev() {
+            if dir.parent().is_none() {
+                continue;
+            }
+            match self.metadata(dir) {
+                Ok(metadata) if metadata.is_dir() => {}
+                Ok(_) => return Err(os_error!(EEXIST, AlreadyExists)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    self.create_dir(dir)?
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Self::ReadDir> {
+        let state = self.state();
+        let resolved = state.enter(Operation::ReadDir, path, true)?;
+        match state.node(&resolved) {
+            Some(Node::Dir(_)) => {}
+            Some(_) => return Err(os_error!(ENOTDIR, NotADirectory)),
+            None => return Err(os_error!(ENOENT, NotFound)),
+        }
+        state.check_access(&resolved, 0o400)?;
+        // 和 std::fs::read_dir 一样，返回的路径以参数开头
+        let entries: Vec<_> = state
+            .nodes
+            .keys()
+            .filter(|key| key.parent() == Some(resolved.as_path()))
+            .filter_map(|key| key.file_name())
+            .map(|name| Ok(path.join(name)))
+            .collect();
+        Ok(entries.into_iter())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        let path = state.enter(Operation::Remove, path, false)?;
+        match state.node(&path) {
+            Some(Node::Dir(_)) => return Err(os_error!(EISDIR, IsADirectory)),
+            Some(_) => {}
+            None => return Err(os_error!(ENOENT, NotFound)),
+        }
+        state.check_parent(&path)?;
+        state.nodes.remove(&path);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        let path = state.enter(Operation::Remove, path, false)?;
+        match state.node(&path) {
+            Some(Node::Dir(_)) => {}
+            Some(_) => return Err(os_error!(ENOTDIR, NotADirectory)),
+            None => return Err(os_error!(ENOENT, NotFound)),
+        }
+        if state.has_children(&path) {
+            return Err(os_error!(ENOTEMPTY, DirectoryNotEmpty));
+        }
+        state.check_parent(&path)?;
+        state.nodes.remove(&path);
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        let path = state.enter(Operation::Remove, path, false)?;
+        match state.node(&path) {
+            // 和 std 一样只删除符号链接本身
+            Some(Node::Dir(_)) | Some(Node::Symlink(_)) => {}
+            Some(_) => return Err(os_error!(ENOTDIR, NotADirectory)),
+            None => return Err(os_error!(ENOENT, NotFound)),
+        }
+        state.check_parent(&path)?;
+        state.nodes.retain(|key, _| !key.starts_with(&path));
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        let from = state.enter(Operation::Rename, from, false)?;
+        let to = state.enter(Operation::Rename, to, false)?;
+        let is_dir = match state.node(&from) {
+            Some(node) => matches!(node, Node::Dir(_)),
+            None => return Err(os_error!(ENOENT, NotFound)),
+        };
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(&from) {
+            return Err(os_error!(EINVAL, InvalidInput));
+        }
+        match (is_dir, state.node(&to)) {
+            (true, Some(Node::Dir(_))) if state.has_children(&to) => {
+                return Err(os_error!(ENOTEMPTY, DirectoryNotEmpty))
+            }
+            (true, Some(Node::Dir(_))) | (_, None) => {}
+            (true, Some(_)) => return Err(os_error!(ENOTDIR, NotADirectory)),
+            (false, Some(Node::Dir(_))) => {
+                return Err(os_error!(EISDIR, IsADirectory))
+            }
+            (false, Some(_)) => {}
+        }
+        state.check_parent(&from)?;
+        state.check_parent(&to)?;
+
+        // 移动节点和它的子节点
+        state.nodes.remove(&to);
+        let keys: Vec<PathBuf> = state
+            .nodes
+            .keys()
+            .filter(|key| key.starts_with(&from))
+            .cloned()
+            .collect();
+        for key in keys {
+            let node = state.nodes.remove(&key).expect("key exists");
+            let relative = key.strip_prefix(&from).expect("key is below from");
+            state.nodes.insert(to.join(relative), node);
+        }
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let mut src = self.open(from, OpenOptions::new().read(true))?;
+        let mut data = Vec::new();
+        src.read_to_end(&mut data)?;
+        let permissions = src.metadata()?.permissions();
+
+        let mut dst = self.open(
+            to,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )?;
+        dst.write_all(&data)?;
+        dst.set_permissions(permissions)?;
+        Ok(data.len() as u64)
+    }
+
+    fn symlink_file(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        self.state().symlink(src, dst)
+    }
+
+    fn symlink_dir(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        self.state().symlink(src, dst)
+    }
+
+    fn set_permissions(
+        &self,
+        path: &Path,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        let mut state = self.state();
+        let path = state.enter(Operation::SetPermissions, path, true)?;
+        let mode = permissions.mode();
+        if state.node(&path).is_none() {
+            return Err(os_error!(ENOENT, NotFound));
+        }
+        match state.nodes.get_mut(&path) {
+            Some(Node::File(inode)) => lock(inode).attrs.mode = mode,
+            Some(Node::Dir(attrs)) => attrs.mode = mode,
+            Some(Node::Symlink(_)) => {}
+            // 没有记录的根目录
+            None => {
+                state.nodes.insert(path, Node::Dir(Attrs::new(mode)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A file opened in a `MemFs`.
+///
+/// Unlike `std::fs::File`, clones made by `try_clone` have their own
+/// position.
+pub struct MemFile {
+    fs: MemFs,
+    path: PathBuf,
+    inode: Arc<Mutex<Inode>>,
+    position: u64,
+    read: bool,
+    write: bool,
+    append: bool,
+}
+
+impl MemFile {
+    fn check(&self, operation: Operation) -> io::Result<()> {
+        self.fs.state().check(operation, &self.path)
+    }
+}
+
+impl fmt::Debug for MemFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MemFile({:?})", self.path)
+    }
+}
+
+impl io::Read for MemFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.read {
+            return Err(os_error!(EBADF, Other));
+        }
+        self.check(Operation::Read)?;
+        let inode = lock(&self.inode);
+        let data = &inode.data;
+        let start = (self.position as usize).min(data.len());
+        let len = buf.len().min(data.len() - start);
+        buf[..len].copy_from_slice(&data[start..start + len]);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl io::Write for MemFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.write {
+            return Err(os_error!(EBADF, Other));
+        }
+        self.check(Operation::Write)?;
+        let mut inode = lock(&self.inode);
+        if self.append {
+            self.position = inode.data.len() as u64;
+        }
+        let start = self.position as usize;
+        let end = start + buf.len();
+        if inode.data.len() < end {
+            inode.data.resize(end, 0);
+        }
+        inode.data[start..end].copy_from_slice(buf);
+        inode.attrs.modified = SystemTime::now();
+        self.position = end as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for MemFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let len = lock(&self.inode).data.len() as u64;
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => {
+                self.position.checked_add_signed(offset)
+            }
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(os_error!(EINVAL, InvalidInput)),
+        }
+    }
+}
+
+impl FsFile for MemFile {
+    fn sync_all(&self) -> io::Result<()> {
+        self.check(Operation::Sync)
+    }
+
+    fn sync_data(&self) -> io::Result<()> {
+        self.check(Operation::Sync)
+    }
+
+    fn set_len(&self, size: u64) -> io::Result<()> {
+        if !self.write {
+            return Err(os_error!(EINVAL, InvalidInput));
+        }
+        self.check(Operation::Write)?;
+        let mut inode = lock(&self.inode);
+        inode.data.resize(size as usize, 0);
+        inode.attrs.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn metadata(&self) -> io::Result<Metadata> {
+        let inode = lock(&self.inode);
+        Ok(Metadata::new(
+            FileKind::File,
+            inode.data.len() as u64,
+            Permissions::from_mode(inode.attrs.mode),
+            Some(inode.attrs.modified),
+        ))
+    }
+
+    fn set_permissions(&self, permissions: Permissions) -> io::Result<()> {
+        self.check(Operation::SetPermissions)?;
+        lock(&self.inode).attrs.mode = permissions.mode();
+        Ok(())
+    }
+
+    fn set_modified(&self, time: SystemTime) -> io::Result<()> {
+        self.check(Operation::SetPermissions)?;
+        lock(&self.inode).attrs.modified = time;
+        Ok(())
+    }
+
+    fn try_clone(&self) -> io::Result<MemFile> {
+        Ok(MemFile {
+            fs: self.fs.clone(),
+            path: self.path.clone(),
+            inode: self.inode.clone(),
+            position: self.position,
+            read: self.read,
+            write: self.write,
+            append: self.append,
+        })
+    }
+}
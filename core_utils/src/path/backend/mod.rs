@@ -0,0 +1,248 @@
+//! 文件系统后端，路径类型通过它访问文件系统
+mod mem;
+mod os;
+
+pub use mem::{Fault, MemFile, MemFs, Operation};
+pub use os::OsFs;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A file system used by the path types, `OsFs` by default.
+///
+/// The paths given to the methods are absolute.
+pub trait FileSystem: Clone {
+    /// A file opened with `open`.
+    type File: FsFile;
+    /// The paths of the entries of a directory, returned by `read_dir`.
+    type ReadDir: Iterator<Item = io::Result<PathBuf>>;
+
+    fn open(
+        &self,
+        path: &Path,
+        options: &OpenOptions,
+    ) -> io::Result<Self::File>;
+
+    /// Query the metadata of a path, following symlinks.
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// Query the metadata of a path without following symlinks.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Resolve all the symlinks of an existing path.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    fn create_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn read_dir(&self, path: &Path) -> io::Result<Self::ReadDir>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    /// Copy the content and permissions of a file, returning its size.
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64>;
+
+    /// Create a symlink at `dst` pointing to the file `src`.
+    fn symlink_file(&self, src: &Path, dst: &Path) -> io::Result<()>;
+
+    /// Create a symlink at `dst` pointing to the directory `src`.
+    fn symlink_dir(&self, src: &Path, dst: &Path) -> io::Result<()>;
+
+    fn set_permissions(
+        &self,
+        path: &Path,
+        permissions: Permissions,
+    ) -> io::Result<()>;
+}
+
+/// A file opened by a `FileSystem`.
+pub trait FsFile: io::Read + io::Write + io::Seek + Sized {
+    fn sync_all(&self) -> io::Result<()>;
+
+    fn sync_data(&self) -> io::Result<()>;
+
+    fn set_len(&self, size: u64) -> io::Result<()>;
+
+    fn metadata(&self) -> io::Result<Metadata>;
+
+    fn set_permissions(&self, permissions: Permissions) -> io::Result<()>;
+
+    /// Change the modification time of the file.
+    fn set_modified(&self, time: SystemTime) -> io::Result<()>;
+
+    fn try_clone(&self) -> io::Result<Self>;
+}
+
+/// Options to open a file with `FileSystem::open`, like
+/// `std::fs::OpenOptions`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: bool,
+    create_new: bool,
+}
+
+impl OpenOptions {
+    /// All the options are false.
+    pub fn new() -> OpenOptions {
+        OpenOptions::default()
+    }
+
+    pub fn read(&mut self, read: bool) -> &mut OpenOptions {
+        self.read = read;
+        self
+    }
+
+    pub fn write(&mut self, write: bool) -> &mut OpenOptions {
+        self.write = write;
+        self
+    }
+
+    pub fn append(&mut self, append: bool) -> &mut OpenOptions {
+        self.append = append;
+        self
+    }
+
+    pub fn truncate(&mut self, truncate: bool) -> &mut OpenOptions {
+        self.truncate = truncate;
+        self
+    }
+
+    pub fn create(&mut self, create: bool) -> &mut OpenOptions {
+        self.create = create;
+        self
+    }
+
+    pub fn create_new(&mut self, create_new: bool) -> &mut OpenOptions {
+        self.create_new = create_new;
+        self
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read
+    }
+
+    /// Whether the file is opened for writing, `append` implies it.
+    pub fn is_write(&self) -> bool {
+        self.write || self.append
+    }
+
+    pub fn is_append(&self) -> bool {
+        self.append
+    }
+
+    pub fn is_truncate(&self) -> bool {
+        self.truncate
+    }
+
+    pub fn is_create(&self) -> bool {
+        self.create
+    }
+
+    pub fn is_create_new(&self) -> bool {
+        self.create_new
+    }
+}
+
+/// The kind of an entry of a `FileSystem`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+/// Unix style permission bits, only the write bits are used on Windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Permissions {
+    mode: u32,
+}
+
+impl Permissions {
+    pub fn from_mode(mode: u32) -> Permissions {
+        Permissions { mode: mode & 0o7777 }
+    }
+
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    /// Whether no one can write, like `std::fs::Permissions::readonly`.
+    pub fn readonly(&self) -> bool {
+        self.mode & 0o222 == 0
+    }
+
+    pub fn set_readonly(&mut self, readonly: bool) {
+        if readonly {
+            self.mode &= !0o222;
+        } else {
+            self.mode |= 0o222;
+        }
+    }
+}
+
+/// Metadata of an entry of a `FileSystem`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    kind: FileKind,
+    len: u64,
+    permissions: Permissions,
+    modified: Option<SystemTime>,
+}
+
+impl Metadata {
+    pub fn new(
+        kind: FileKind,
+        len: u64,
+        permissions: Permissions,
+        modified: Option<SystemTime>,
+    ) -> Metadata {
+        Metadata { kind, len, permissions, modified }
+    }
+
+    pub fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == FileKind::File
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileKind::Dir
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.kind == FileKind::Symlink
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    /// The last modification time, if the file system records it.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
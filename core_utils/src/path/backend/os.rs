@@ -0,0 +1,213 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::{
+    FileKind, FileSystem, FsFile, Metadata, OpenOptions, Permissions,
+};
+
+/// The file system of the operating system, through `std::fs`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub struct OsFs;
+
+impl FileSystem for OsFs {
+    type File = fs::File;
+    type ReadDir = ReadDir;
+
+    fn open(
+        &self,
+        path: &Path,
+        options: &OpenOptions,
+    ) -> io::Result<fs::File> {
+        fs::OpenOptions::from(options).open(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::metadata(path).map(Metadata::from)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::symlink_metadata(path).map(Metadata::from)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
+        fs::read_dir(path).map(ReadDir)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        fs::copy(from, to)
+    }
+
+    fn symlink_file(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(src, dst);
+        #[cfg(target_os = "wasi")]
+        return std::os::wasi::fs::symlink_path(src, dst);
+        #[cfg(windows)]
+        return std::os::windows::fs::symlink_file(src, dst);
+    }
+
+    fn symlink_dir(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(src, dst);
+        #[cfg(target_os = "wasi")]
+        return std::os::wasi::fs::symlink_path(src, dst);
+        #[cfg(windows)]
+        return std::os::windows::fs::symlink_dir(src, dst);
+    }
+
+    fn set_permissions(
+        &self,
+        path: &Path,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        let current = fs::metadata(path)?.permissions();
+        fs::set_permissions(path, to_std(current, permissions))
+    }
+}
+
+impl FsFile for fs::File {
+    fn sync_all(&self) -> io::Result<()> {
+        fs::File::sync_all(self)
+    }
+
+    fn sync_data(&self) -> io::Result<()> {
+        fs::File::sync_data(self)
+    }
+
+    fn set_len(&self, size: u64) -> io::Result<()> {
+        fs::File::set_len(self, size)
+    }
+
+    fn metadata(&self) -> io::Result<Metadata> {
+        fs::File::metadata(self).map(Metadata::from)
+    }
+
+    fn set_permissions(&self, permissions: Permissions) -> io::Result<()> {
+        let current = fs::File::metadata(self)?.permissions();
+        fs::File::set_permissions(self, to_std(current, permissions))
+    }
+
+    fn set_modified(&self, time: SystemTime) -> io::Result<()> {
+        fs::File::set_modified(self, time)
+    }
+
+    fn try_clone(&self) -> io::Result<fs::File> {
+        fs::File::try_clone(self)
+    }
+}
+
+/// The paths of the entries of a directory of `OsFs`.
+pub struct ReadDir(fs::ReadDir);
+
+impl Iterator for ReadDir {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<io::Result<PathBuf>> {
+        self.0.next().map(|entry| entry.map(|entry| entry.path()))
+    }
+}
+
+impl From<&OpenOptions> for fs::OpenOptions {
+    fn from(options: &OpenOptions) -> fs::OpenOptions {
+        let mut std_options = fs::OpenOptions::new();
+        std_options
+            .read(options.is_read())
+            .write(options.is_write())
+            .append(options.is_append())
+            .truncate(options.is_truncate())
+            .create(options.is_create())
+            .create_new(options.is_create_new());
+        std_options
+    }
+}
+
+impl From<fs::Metadata> for Metadata {
+    fn from(metadata: fs::Metadata) -> Metadata {
+        let file_type = metadata.file_type();
+        let kind = if file_type.is_symlink() {
+            FileKind::Symlink
+        } else if file_type.is_dir() {
+            FileKind::Dir
+        } else {
+            FileKind::File
+        };
+        Metadata::new(
+            kind,
+            metadata.len(),
+            Permissions::from(metadata.permissions()),
+            metadata.modified().ok(),
+        )
+    }
+}
+
+impl From<fs::Permissions> for Permissions {
+    #[cfg(unix)]
+    fn from(permissions: fs::Permissions) -> Permissions {
+        use std::os::unix::fs::PermissionsExt;
+        Permissions::from_mode(permissions.mode())
+    }
+
+    #[cfg(not(unix))]
+    fn from(permissions: fs::Permissions) -> Permissions {
+        if permissions.readonly() {
+            Permissions::from_mode(0o444)
+        } else {
+            Permissions::from_mode(0o666)
+        }
+    }
+}
+
+/// 转换为 std 的权限，非 unix 系统只能设置只读
+#[cfg(unix)]
+fn to_std(
+    _current: fs::Permissions,
+    permissions: Permissions,
+) -> fs::Permissions {
+    use std::os::unix::fs::PermissionsExt;
+    fs::Permissions::from_mode(permissions.mode())
+}
+
+#[cfg(not(unix))]
+fn to_std(
+    mut current: fs::Permissions,
+    permissions: Permissions,
+) -> fs::Permissions {
+    current.set_readonly(permissions.readonly());
+    current
+}
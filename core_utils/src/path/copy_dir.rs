@@ -0,0 +1,613 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{Error, FileSystem, FsFile, Metadata, OpenOptions, Result};
+use crate::file;
+
+/// How `PathDir::copy_to` and friends handle the symlinks of the source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Symlinks {
+    /// Create a symlink with the same target.
+    #[default]
+    Copy,
+    /// Copy the file or directory the symlink points to.
+    Follow,
+    /// Leave the symlinks out.
+    Skip,
+}
+
+/// What `PathDir::copy_to` and `PathDir::move_to` do when a destination
+/// file already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overwrite {
+    /// Fail with `io::ErrorKind::AlreadyExists`.
+    #[default]
+    Never,
+    /// Keep the existing file.
+    Skip,
+    /// Replace the existing file.
+    Always,
+}
+
+/// How `PathDir::sync_to` decides that a file changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compare {
+    /// The size or the modification time differ.
+    #[default]
+    Metadata,
+    /// The size or the MD5 hash differ, see `file::md5`.
+    Hash,
+}
+
+/// Options of `PathDir::copy_to`, `PathDir::move_to` and `PathDir::sync_to`.
+///
+/// ```ignore
+/// let options = CopyOptions::new()
+///     .overwrite(Overwrite::Always)
+///     .exclude("**/target")
+///     .progress(|p| println!("{}/{}", p.bytes_done(), p.bytes_total()));
+/// src.copy_to("/backup/src", options)?;
+/// ```
+#[derive(Default)]
+pub struct CopyOptions<'a> {
+    symlinks: Symlinks,
+    overwrite: Overwrite,
+    compare: Compare,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    includes: GlobSet,
+    excludes: GlobSet,
+    progress: Option<ProgressFn<'a>>,
+}
+
+/// 进度回调
+type ProgressFn<'a> = Box<dyn FnMut(&CopyProgress<'_>) + 'a>;
+
+/// The progress of a copy, given to the callback of `CopyOptions::progress`
+/// after each file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyProgress<'a> {
+    path: &'a Path,
+    skipped: bool,
+    files_done: usize,
+    files_total: usize,
+    bytes_done: u64,
+    bytes_total: u64,
+}
+
+impl<'a> CopyProgress<'a> {
+    /// The path of the file, relative to the source directory.
+    pub fn path(&self) -> &'a Path {
+        self.path
+    }
+
+    /// Whether the file was left as is, because it exists or is unchanged.
+    pub fn skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// The number of files and symlinks done, including this one.
+    pub fn files_done(&self) -> usize {
+        self.files_done
+    }
+
+    pub fn files_total(&self) -> usize {
+        self.files_total
+    }
+
+    /// The size of the files done, skipped files included.
+    pub fn bytes_done(&self) -> u64 {
+        self.bytes_done
+    }
+
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes_total
+    }
+}
+
+/// 复制还是同步，决定已存在的文件如何处理
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    Copy,
+    Sync,
+}
+
+/// 要复制的一项，路径相对于源目录
+pub(crate) struct Entry {
+    relative: PathBuf,
+    kind: Kind,
+    /// 源路径本身是符号链接
+    symlink: bool,
+    /// 在跟随的符号链接下面
+    linked: bool,
+}
+
+enum Kind {
+    Dir,
+    File(Metadata),
+    Symlink(PathBuf),
+}
+
+impl<'a> CopyOptions<'a> {
+    /// Copy everything, keep the symlinks and never overwrite.
+    pub fn new() -> CopyOptions<'a> {
+        CopyOptions::default()
+    }
+
+    pub fn symlinks(mut self, symlinks: Symlinks) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Ignored by `sync_to`, which replaces the changed files.
+    pub fn overwrite(mut self, overwrite: Overwrite) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Used by `sync_to` only.
+    pub fn compare(mut self, compare: Compare) -> Self {
+        self.compare = compare;
+        self
+    }
+
+    /// Only copy the files and symlinks whose path, relative to the source,
+    /// matches one of the `include` globs. Directories are created only when
+    /// something is copied in them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self.includes = build_globs(&self.include);
+        self
+    }
+
+    /// Skip the files and directories whose path, relative to the source,
+    /// matches `glob`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self.excludes = build_globs(&self.exclude);
+        self
+    }
+
+    /// Call `callback` after each file or symlink.
+    pub fn progress<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(&CopyProgress<'_>) + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Whether every entry of the source is copied.
+    pub(crate) fn is_unfiltered(&self) -> bool {
+        self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.symlinks == Symlinks::Copy
+    }
+
+    /// 复制 `src` 的内容到 `dst`，返回复制了的项
+    pub(crate) fn run<F: FileSystem>(
+        &mut self,
+        fs: &F,
+        src: &Path,
+        dst: &Path,
+        mode: Mode,
+    ) -> Result<Vec<Entry>> {
+        if dst.starts_with(src) {
+            return Err(Error::new(
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "destination is inside the source",
+                ),
+                &format!("copying to {} from", dst.display()),
+                src.to_path_buf().into(),
+            ));
+        }
+        let entries = self.plan(fs, src)?;
+        let files_total = entries
+            .iter()
+            .filter(|entry| !matches!(entry.kind, Kind::Dir))
+            .count();
+        let bytes_total = entries.iter().map(Entry::len).sum();
+
+        fs.create_dir_all(dst).map_err(|err| {
+            Error::new(err, "creating-all", dst.to_path_buf().into())
+        })?;
+        let mut done = Vec::new();
+        let (mut files_done, mut bytes_done) = (0, 0);
+        for entry in entries {
+            let from = src.join(&entry.relative);
+            let to = dst.join(&entry.relative);
+            if let Kind::Dir = entry.kind {
+                create_dir(fs, &to)?;
+                done.push(entry);
+                continue;
+            }
+
+            let skipped = !self.replace(fs, &entry, &from, &to, mode)?;
+            if !skipped {
+                match &entry.kind {
+                    Kind::File(metadata) => {
+                        copy_file(fs, &from, &to, metadata)?
+                    }
+                    Kind::Symlink(target) => {
+                        copy_link(fs, &from, &to, target)?
+                    }
+                    Kind::Dir => unreachable!(),
+                }
+            }
+            files_done += 1;
+            bytes_done += entry.len();
+            if let Some(callback) = &mut self.progress {
+                callback(&CopyProgress {
+                    path: &entry.relative,
+                    skipped,
+                    files_done,
+                    files_total,
+                    bytes_done,
+                    bytes_total,
+                });
+            }
+            if !skipped {
+                done.push(entry);
+            }
+        }
+        Ok(done)
+    }
+
+    /// 遍历源目录，目录排在它的内容之前
+    fn plan<F: FileSystem>(&self, fs: &F, src: &Path) -> Result<Vec<Entry>> {
+        let mut entries = Vec::new();
+        let mut ancestors = Vec::new();
+        if self.symlinks == Symlinks::Follow {
+            ancestors.push(canonicalize(fs, src)?);
+        }
+        self.walk(
+            fs,
+            src,
+            Path::new(""),
+            false,
+            &mut ancestors,
+            &mut entries,
+        )?;
+
+        if !self.include.is_empty() {
+            // 只保留包含了文件的目录
+            let needed: HashSet<PathBuf> = entries
+                .iter()
+                .filter(|entry| !matches!(entry.kind, Kind::Dir))
+                .flat_map(|entry| entry.relative.ancestors().skip(1))
+                .map(Path::to_path_buf)
+                .collect();
+            entries.retain(|entry| {
+                !matches!(entry.kind, Kind::Dir)
+                    || needed.contains(&entry.relative)
+            });
+        }
+        Ok(entries)
+    }
+
+    fn walk<F: FileSystem>(
+        &self,
+        fs: &F,
+        src: &Path,
+        relative: &Path,
+        linked: bool,
+        ancestors: &mut Vec<PathBuf>,
+        entries: &mut Vec<Entry>,
+    ) -> Result<()> {
+        let dir = src.join(relative);
+        let mut paths = fs
+            .read_dir(&dir)
+            .and_then(|read| read.collect::<io::Result<Vec<_>>>())
+            .map_err(|err| Error::new(err, "reading dir", dir.into()))?;
+        // 按名字排序，复制的顺序是确定的
+        paths.sort();
+
+        for path in paths {
+            let relative = match path.file_name() {
+                Some(name) => relative.join(name),
+                None => continue,
+            };
+            if self.excludes.is_match(&relative) {
+                continue;
+            }
+            let mut metadata = fs.symlink_metadata(&path).map_err(|err| {
+                Error::new(err, "getting metadata of", path.clone().into())
+            })?;
+            let symlink = metadata.is_symlink();
+            if symlink {
+                match self.symlinks {
+                    Symlinks::Skip => continue,
+                    Symlinks::Copy => {
+                        let target = fs.read_link(&path).map_err(|err| {
+                            Error::new(err, "reading link", path.into())
+                        })?;
+                        if self.is_included(&relative) {
+                            entries.push(Entry {
+                                relative,
+                                kind: Kind::Symlink(target),
+                                symlink,
+                                linked,
+                            });
+                        }
+                        continue;
+                    }
+                    Symlinks::Follow => {
+                        metadata = fs.metadata(&path).map_err(|err| {
+                            Error::new(
+                                err,
+                                "getting metadata of",
+                                path.clone().into(),
+                            )
+                        })?;
+                    }
+                }
+            }
+
+            if metadata.is_dir() {
+                let real = match self.symlinks {
+                    Symlinks::Follow => Some(canonicalize(fs, &path)?),
+                    _ => None,
+                };
+                if let Some(real) = &real {
+                    // 符号链接指向上级目录时会无限循环
+                    if ancestors.contains(real) {
+                        return Err(Error::new(
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                "symlink to a parent directory",
+                            ),
+                            "copying",
+                            path.into(),
+                        ));
+                    }
+                    ancestors.push(real.clone());
+                }
+                entries.push(Entry {
+                    relative: relative.clone(),
+                    kind: Kind::Dir,
+                    symlink,
+                    linked,
+                });
+                self.walk(
+                    fs,
+                    src,
+                    &relative,
+                    linked || symlink,
+                    ancestors,
+                    entries,
+                )?;
+                if real.is_some() {
+                    ancestors.pop();
+                }
+            } else if self.is_included(&relative) {
+                entries.push(Entry {
+                    relative,
+                    kind: Kind::File(metadata),
+                    symlink,
+                    linked,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn is_included(&self, relative: &Path) -> bool {
+        self.include.is_empty() || self.includes.is_match(relative)
+    }
+
+    /// 目标已存在时决定是否替换，需要时先删除目标的符号链接
+    fn replace<F: FileSystem>(
+        &self,
+        fs: &F,
+        entry: &Entry,
+        from: &Path,
+        to: &Path,
+        mode: Mode,
+    ) -> Result<bool> {
+        let existing = match fs.symlink_metadata(to) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(true)
+            }
+            Err(err) => {
+                return Err(Error::new(
+                    err,
+                    "getting metadata of",
+                    to.to_path_buf().into(),
+                ))
+            }
+        };
+        let replace = match mode {
+            Mode::Copy => match self.overwrite {
+                Overwrite::Never => {
+                    return Err(Error::new(
+                        io::ErrorKind::AlreadyExists.into(),
+                        "overwriting",
+                        to.to_path_buf().into(),
+                    ))
+                }
+                Overwrite::Skip => false,
+                Overwrite::Always => true,
+            },
+            Mode::Sync => self.changed(fs, entry, from, to, &existing)?,
+        };
+        // 不能通过符号链接写入，也不能覆盖符号链接
+        if replace
+            && (existing.is_symlink()
+                || matches!(entry.kind, Kind::Symlink(_)))
+        {
+            fs.remove_file(to).map_err(|err| {
+                Error::new(err, "removing", to.to_path_buf().into())
+            })?;
+        }
+        Ok(replace)
+    }
+
+    fn changed<F: FileSystem>(
+        &self,
+        fs: &F,
+        entry: &Entry,
+        from: &Path,
+        to: &Path,
+        existing: &Metadata,
+    ) -> Result<bool> {
+        match &entry.kind {
+            Kind::File(metadata) => {
+                if !existing.is_file() || existing.len() != metadata.len() {
+                    return Ok(true);
+                }
+                match self.compare {
+                    Compare::Metadata => Ok(metadata.modified().is_none()
+                        || existing.modified() != metadata.modified()),
+                    Compare::Hash => Ok(md5(fs, from)? != md5(fs, to)?),
+                }
+            }
+            Kind::Symlink(target) => {
+                if !existing.is_symlink() {
+                    return Ok(true);
+                }
+                let current = fs.read_link(to).map_err(|err| {
+                    Error::new(err, "reading link", to.to_path_buf().into())
+                })?;
+                Ok(&current != target)
+            }
+            Kind::Dir => Ok(false),
+        }
+    }
+}
+
+impl Entry {
+    pub(crate) fn relative(&self) -> &Path {
+        &self.relative
+    }
+
+    pub(crate) fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir)
+    }
+
+    /// Whether the source is a symlink, copied or followed.
+    pub(crate) fn is_symlink(&self) -> bool {
+        self.symlink
+    }
+
+    /// Whether the source was reached through a followed symlink.
+    pub(crate) fn is_linked(&self) -> bool {
+        self.linked
+    }
+
+    fn len(&self) -> u64 {
+        match &self.kind {
+            Kind::File(metadata) => metadata.len(),
+            _ => 0,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for CopyOptions<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyOptions")
+            .field("symlinks", &self.symlinks)
+            .field("overwrite", &self.overwrite)
+            .field("compare", &self.compare)
+            .field("include", &self.include)
+            .field("exclude", &self.exclude)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+pub(crate) fn build_globs(globs: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).expect("invalid glob"));
+    }
+    builder.build().expect("invalid glob")
+}
+
+fn canonicalize<F: FileSystem>(fs: &F, path: &Path) -> Result<PathBuf> {
+    fs.canonicalize(path).map_err(|err| {
+        Error::new(err, "canonicalizing", path.to_path_buf().into())
+    })
+}
+
+fn create_dir<F: FileSystem>(fs: &F, path: &Path) -> Result<()> {
+    match fs.create_dir(path) {
+        Ok(()) => Ok(()),
+        Err(err)
+            if err.kind() == io::ErrorKind::AlreadyExists
+                && fs.metadata(path).is_ok_and(|m| m.is_dir()) =>
+        {
+            Ok(())
+        }
+        Err(err) => {
+            Err(Error::new(err, "creating", path.to_path_buf().into()))
+        }
+    }
+}
+
+/// 复制文件内容和权限，并保留修改时间，以便下次同步时比较
+fn copy_file<F: FileSystem>(
+    fs: &F,
+    from: &Path,
+    to: &Path,
+    metadata: &Metadata,
+) -> Result<()> {
+    fs.copy(from, to).map_err(|err| {
+        Error::new(
+            err,
+            &format!("copying {} from", to.display()),
+            from.to_path_buf().into(),
+        )
+    })?;
+    if let Some(modified) = metadata.modified() {
+        fs.open(to, OpenOptions::new().read(true))
+            .and_then(|file| file.set_modified(modified))
+            .map_err(|err| {
+                Error::new(
+                    err,
+                    "setting modification time of",
+                    to.to_path_buf().into(),
+                )
+            })?;
+    }
+    Ok(())
+}
+
+fn copy_link<F: FileSystem>(
+    fs: &F,
+    from: &Path,
+    to: &Path,
+    target: &Path,
+) -> Result<()> {
+    // Windows 区分文件和目录的符号链接
+    let result = if fs.metadata(from).is_ok_and(|m| m.is_dir()) {
+        fs.symlink_dir(target, to)
+    } else {
+        fs.symlink_file(target, to)
+    };
+    result.map_err(|err| {
+        Error::new(
+            err,
+            &format!("linking from {} to", target.display()),
+            to.to_path_buf().into(),
+        )
+    })
+}
+
+fn md5<F: FileSystem>(fs: &F, path: &Path) -> Result<String> {
+    fs.open(path, OpenOptions::new().read(true))
+        .and_then(file::md5_reader)
+        .map_err(|err| Error::new(err, "hashing", path.to_path_buf().into()))
+}
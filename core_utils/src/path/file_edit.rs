@@ -1,12 +1,14 @@
 use super::FileOpen;
-use super::{Error, PathAbs, PathInfo, Result};
+use super::{
+    Error, FileSystem, FsFile, OpenOptions, OsFs, PathFile, PathInfo, Result,
+};
 use std::borrow::Borrow;
 use std::fmt;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::Path;
 
-pub struct FileEdit(pub(crate) FileOpen);
+pub struct FileEdit<F: FileSystem = OsFs>(pub(crate) FileOpen<F>);
 
 impl FileEdit {
     /// Open the file with the given `OpenOptions` but always sets `read` and `write` to true.
@@ -19,23 +21,10 @@ impl FileEdit {
         Ok(FileEdit(FileOpen::open(path, options)?))
     }
 
-    /// Shortcut to open the file if the path is already absolute.
-    pub(crate) fn open_abs<P: Into<PathAbs>>(
-        path: P,
-        mut options: fs::OpenOptions,
-    ) -> Result<FileEdit> {
-        options.write(true);
-        options.read(true);
-        Ok(FileEdit(FileOpen::open_abs(path, options)?))
-    }
-
     /// Open the file in editing mode, truncating it first if it exists and creating it
     /// otherwise.
     pub fn create<P: AsRef<Path>>(path: P) -> Result<FileEdit> {
-        let mut options = fs::OpenOptions::new();
-        options.truncate(true);
-        options.create(true);
-        FileEdit::open(path, options)
+        FileEdit::create_in(path, OsFs)
     }
 
     /// Open the file for appending, creating it if it doesn't exist.
@@ -54,6 +43,47 @@ impl FileEdit {
         FileEdit::open(path, options)
     }
 
+    pub fn set_permissions(&mut self, perm: fs::Permissions) -> Result<()> {
+        self.0.file.set_permissions(perm).map_err(|err| {
+            Error::new(
+                err,
+                "setting permisions for",
+                self.0.path.clone().into(),
+            )
+        })
+    }
+}
+
+impl<F: FileSystem> FileEdit<F> {
+    /// Like `open`, in the file system `fs`.
+    pub fn open_in<P: AsRef<Path>>(
+        path: P,
+        mut options: OpenOptions,
+        fs: F,
+    ) -> Result<FileEdit<F>> {
+        options.write(true);
+        options.read(true);
+        Ok(FileEdit(FileOpen::open_in(path, options, fs)?))
+    }
+
+    /// Like `create`, in the file system `fs`.
+    pub fn create_in<P: AsRef<Path>>(path: P, fs: F) -> Result<FileEdit<F>> {
+        let mut options = OpenOptions::new();
+        options.truncate(true);
+        options.create(true);
+        FileEdit::open_in(path, options, fs)
+    }
+
+    /// Shortcut to open the file if the path is already absolute.
+    pub(crate) fn open_abs(
+        path: PathFile<F>,
+        mut options: OpenOptions,
+    ) -> Result<FileEdit<F>> {
+        options.write(true);
+        options.read(true);
+        Ok(FileEdit(FileOpen::open_path(path, options)?))
+    }
+
     /// Attempts to sync all OS-internal metadata to disk.
     pub fn sync_all(&self) -> Result<()> {
         self.0.file.sync_all().map_err(|err| {
@@ -75,16 +105,6 @@ impl FileEdit {
         })
     }
 
-    pub fn set_permissions(&mut self, perm: fs::Permissions) -> Result<()> {
-        self.0.file.set_permissions(perm).map_err(|err| {
-            Error::new(
-                err,
-                "setting permisions for",
-                self.0.path.clone().into(),
-            )
-        })
-    }
-
     /// Read what remains of the file to a `String`.
     pub fn read_string(&mut self) -> Result<String> {
         let mut s = String::new();
@@ -107,7 +127,7 @@ impl FileEdit {
     }
 }
 
-impl fmt::Debug for FileEdit {
+impl<F: FileSystem> fmt::Debug for FileEdit<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "FileEdit(")?;
         self.0.path.fmt(f)?;
@@ -115,7 +135,7 @@ impl fmt::Debug for FileEdit {
     }
 }
 
-impl io::Read for FileEdit {
+impl<F: FileSystem> io::Read for FileEdit<F> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.file.read(buf).map_err(|err| {
             io::Error::new(
@@ -126,7 +146,7 @@ impl io::Read for FileEdit {
     }
 }
 
-impl io::Write for FileEdit {
+impl<F: FileSystem> io::Write for FileEdit<F> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.0.file.write(buf).map_err(|err| {
             io::Error::new(
@@ -146,7 +166,7 @@ impl io::Write for FileEdit {
     }
 }
 
-impl io::Seek for FileEdit {
+impl<F: FileSystem> io::Seek for FileEdit<F> {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
         self.0.file.seek(pos).map_err(|err| {
             io::Error::new(
@@ -157,8 +177,8 @@ impl io::Seek for FileEdit {
     }
 }
 
-impl AsRef<FileOpen> for FileEdit {
-    fn as_ref(&self) -> &FileOpen {
+impl<F: FileSystem> AsRef<FileOpen<F>> for FileEdit<F> {
+    fn as_ref(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -169,8 +189,8 @@ impl AsRef<File> for FileEdit {
     }
 }
 
-impl Borrow<FileOpen> for FileEdit {
-    fn borrow(&self) -> &FileOpen {
+impl<F: FileSystem> Borrow<FileOpen<F>> for FileEdit<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -181,8 +201,8 @@ impl Borrow<File> for FileEdit {
     }
 }
 
-impl<'a> Borrow<FileOpen> for &'a FileEdit {
-    fn borrow(&self) -> &FileOpen {
+impl<'a, F: FileSystem> Borrow<FileOpen<F>> for &'a FileEdit<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -193,8 +213,8 @@ impl<'a> Borrow<File> for &'a FileEdit {
     }
 }
 
-impl From<FileEdit> for FileOpen {
-    fn from(orig: FileEdit) -> FileOpen {
+impl<F: FileSystem> From<FileEdit<F>> for FileOpen<F> {
+    fn from(orig: FileEdit<F>) -> FileOpen<F> {
         orig.0
     }
 }
@@ -3,14 +3,16 @@ use std::fmt;
 use std::fs;
 use std::path::Path;
 
-use super::{Error, PathAbs, PathFile, Result};
+use super::{
+    Error, FileSystem, FsFile, OpenOptions, OsFs, PathAbs, PathFile, Result,
+};
 
 /// **INTERNAL TYPE: do not use directly.**
 ///
 /// Use `FileRead`, `FileWrite` or `FileEdit` instead.
-pub struct FileOpen {
-    pub(crate) path: PathFile,
-    pub(crate) file: fs::File,
+pub struct FileOpen<F: FileSystem = OsFs> {
+    pub(crate) path: PathFile<F>,
+    pub(crate) file: F::File,
 }
 
 impl FileOpen {
@@ -41,18 +43,48 @@ impl FileOpen {
         Ok(FileOpen { path: PathFile::new_unchecked(path), file })
     }
 
-    /// Get the path associated with the open file.
-    pub fn path(&self) -> &PathFile {
-        &self.path
-    }
-
     pub fn metadata(&self) -> Result<fs::Metadata> {
         self.file.metadata().map_err(|err| {
             Error::new(err, "getting metadata for", self.path.clone().into())
         })
     }
+}
+
+impl<F: FileSystem> FileOpen<F> {
+    /// Like `open`, in the file system `fs`.
+    pub fn open_in<P: AsRef<Path>>(
+        path: P,
+        options: OpenOptions,
+        fs: F,
+    ) -> Result<FileOpen<F>> {
+        let abs = PathAbs::new(&path)?;
+        let file = fs.open(abs.as_path(), &options).map_err(|err| {
+            Error::new(err, "opening", path.as_ref().to_path_buf().into())
+        })?;
+
+        let path = PathFile::try_from_in(abs, fs)?;
+        Ok(FileOpen { path, file })
+    }
+
+    /// Open a file of its file system.
+    pub(crate) fn open_path(
+        path: PathFile<F>,
+        options: OpenOptions,
+    ) -> Result<FileOpen<F>> {
+        let file = path
+            .1
+            .open(path.as_path(), &options)
+            .map_err(|err| Error::new(err, "opening", path.clone().into()))?;
+
+        Ok(FileOpen { path, file })
+    }
+
+    /// Get the path associated with the open file.
+    pub fn path(&self) -> &PathFile<F> {
+        &self.path
+    }
 
-    pub fn try_clone(&self) -> Result<FileOpen> {
+    pub fn try_clone(&self) -> Result<FileOpen<F>> {
         let file = self.file.try_clone().map_err(|err| {
             Error::new(
                 err,
@@ -64,7 +96,7 @@ impl FileOpen {
     }
 }
 
-impl fmt::Debug for FileOpen {
+impl<F: FileSystem> fmt::Debug for FileOpen<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Open(")?;
         self.path.fmt(f)?;
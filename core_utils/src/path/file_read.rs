@@ -1,5 +1,7 @@
 use super::FileOpen;
-use super::{Error, PathAbs, PathFile, PathInfo, Result};
+use super::{
+    Error, FileSystem, OpenOptions, OsFs, PathFile, PathInfo, Result,
+};
 use std::borrow::Borrow;
 use std::fmt;
 use std::fs::{self, File};
@@ -7,7 +9,7 @@ use std::io;
 use std::io::Read;
 use std::path::Path;
 
-pub struct FileRead(pub(crate) FileOpen);
+pub struct FileRead<F: FileSystem = OsFs>(pub(crate) FileOpen<F>);
 
 impl FileRead {
     /// Open the file as read-only.
@@ -16,15 +18,24 @@ impl FileRead {
         options.read(true);
         Ok(FileRead(FileOpen::open(path, options)?))
     }
+}
+
+impl<F: FileSystem> FileRead<F> {
+    /// Like `open`, in the file system `fs`.
+    pub fn open_in<P: AsRef<Path>>(path: P, fs: F) -> Result<FileRead<F>> {
+        let mut options = OpenOptions::new();
+        options.read(true);
+        Ok(FileRead(FileOpen::open_in(path, options, fs)?))
+    }
 
     /// Shortcut to open the file if the path is already absolute.
-    pub(crate) fn open_abs<P: Into<PathAbs>>(path: P) -> Result<FileRead> {
-        let mut options = fs::OpenOptions::new();
+    pub(crate) fn open_abs(path: PathFile<F>) -> Result<FileRead<F>> {
+        let mut options = OpenOptions::new();
         options.read(true);
-        Ok(FileRead(FileOpen::open_abs(path, options)?))
+        Ok(FileRead(FileOpen::open_path(path, options)?))
     }
 
-    pub fn path(&self) -> &PathFile {
+    pub fn path(&self) -> &PathFile<F> {
         &self.0.path
     }
 
@@ -38,7 +49,7 @@ impl FileRead {
     }
 }
 
-impl fmt::Debug for FileRead {
+impl<F: FileSystem> fmt::Debug for FileRead<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "FileRead(")?;
         self.0.path.fmt(f)?;
@@ -46,7 +57,7 @@ impl fmt::Debug for FileRead {
     }
 }
 
-impl io::Read for FileRead {
+impl<F: FileSystem> io::Read for FileRead<F> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.file.read(buf).map_err(|err| {
             io::Error::new(
@@ -57,7 +68,7 @@ impl io::Read for FileRead {
     }
 }
 
-impl io::Seek for FileRead {
+impl<F: FileSystem> io::Seek for FileRead<F> {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
         self.0.file.seek(pos).map_err(|err| {
             io::Error::new(
@@ -68,8 +79,8 @@ impl io::Seek for FileRead {
     }
 }
 
-impl AsRef<FileOpen> for FileRead {
-    fn as_ref(&self) -> &FileOpen {
+impl<F: FileSystem> AsRef<FileOpen<F>> for FileRead<F> {
+    fn as_ref(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -80,8 +91,8 @@ impl AsRef<File> for FileRead {
     }
 }
 
-impl Borrow<FileOpen> for FileRead {
-    fn borrow(&self) -> &FileOpen {
+impl<F: FileSystem> Borrow<FileOpen<F>> for FileRead<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -92,8 +103,8 @@ impl Borrow<File> for FileRead {
     }
 }
 
-impl<'a> Borrow<FileOpen> for &'a FileRead {
-    fn borrow(&self) -> &FileOpen {
+impl<'a, F: FileSystem> Borrow<FileOpen<F>> for &'a FileRead<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -104,8 +115,8 @@ impl<'a> Borrow<File> for &'a FileRead {
     }
 }
 
-impl From<FileRead> for FileOpen {
-    fn from(orig: FileRead) -> FileOpen {
+impl<F: FileSystem> From<FileRead<F>> for FileOpen<F> {
+    fn from(orig: FileRead<F>) -> FileOpen<F> {
         orig.0
     }
 }
@@ -6,10 +6,12 @@ use std::io;
 use std::io::Write;
 use std::path::Path;
 
-use super::FileOpen;
-use super::{Error, PathAbs, PathFile, PathInfo, Result};
+use super::{AtomicWrite, FileOpen};
+use super::{
+    Error, FileSystem, FsFile, OpenOptions, OsFs, PathFile, PathInfo, Result,
+};
 
-pub struct FileWrite(pub(crate) FileOpen);
+pub struct FileWrite<F: FileSystem = OsFs>(pub(crate) FileOpen<F>);
 
 impl FileWrite {
     /// Open the file with the given `OpenOptions` but always sets `write` to true.
@@ -21,22 +23,16 @@ impl FileWrite {
         Ok(FileWrite(FileOpen::open(path, options)?))
     }
 
-    /// Shortcut to open the file if the path is already absolute.
-    pub(crate) fn open_abs<P: Into<PathAbs>>(
-        path: P,
-        mut options: fs::OpenOptions,
-    ) -> Result<FileWrite> {
-        options.write(true);
-        Ok(FileWrite(FileOpen::open_abs(path, options)?))
-    }
-
     /// Open the file in write-only mode, truncating it first if it exists and creating it
     /// otherwise.
     pub fn create<P: AsRef<Path>>(path: P) -> Result<FileWrite> {
-        let mut options = fs::OpenOptions::new();
-        options.truncate(true);
-        options.create(true);
-        FileWrite::open(path, options)
+        FileWrite::create_in(path, OsFs)
+    }
+
+    /// Write the file atomically: the content goes to a temporary file in the
+    /// same directory, which replaces `path` on `AtomicWrite::commit`.
+    pub fn atomic<P: AsRef<Path>>(path: P) -> Result<AtomicWrite> {
+        AtomicWrite::create(path)
     }
 
     /// Open the file for appending, creating it if it doesn't exist.
@@ -55,7 +51,46 @@ impl FileWrite {
         FileWrite::open(path, options)
     }
 
-    pub fn path(&self) -> &PathFile {
+    pub fn set_permissions(&mut self, perm: fs::Permissions) -> Result<()> {
+        self.0.file.set_permissions(perm).map_err(|err| {
+            Error::new(
+                err,
+                "setting permisions for",
+                self.0.path.clone().into(),
+            )
+        })
+    }
+}
+
+impl<F: FileSystem> FileWrite<F> {
+    /// Like `open`, in the file system `fs`.
+    pub fn open_in<P: AsRef<Path>>(
+        path: P,
+        mut options: OpenOptions,
+        fs: F,
+    ) -> Result<FileWrite<F>> {
+        options.write(true);
+        Ok(FileWrite(FileOpen::open_in(path, options, fs)?))
+    }
+
+    /// Like `create`, in the file system `fs`.
+    pub fn create_in<P: AsRef<Path>>(path: P, fs: F) -> Result<FileWrite<F>> {
+        let mut options = OpenOptions::new();
+        options.truncate(true);
+        options.create(true);
+        FileWrite::open_in(path, options, fs)
+    }
+
+    /// Shortcut to open the file if the path is already absolute.
+    pub(crate) fn open_abs(
+        path: PathFile<F>,
+        mut options: OpenOptions,
+    ) -> Result<FileWrite<F>> {
+        options.write(true);
+        Ok(FileWrite(FileOpen::open_path(path, options)?))
+    }
+
+    pub fn path(&self) -> &PathFile<F> {
         &self.0.path
     }
 
@@ -77,16 +112,6 @@ impl FileWrite {
         })
     }
 
-    pub fn set_permissions(&mut self, perm: fs::Permissions) -> Result<()> {
-        self.0.file.set_permissions(perm).map_err(|err| {
-            Error::new(
-                err,
-                "setting permisions for",
-                self.0.path.clone().into(),
-            )
-        })
-    }
-
     pub fn write_str(&mut self, s: &str) -> Result<()> {
         self.0.file.write_all(s.as_bytes()).map_err(|err| {
             Error::new(err, "writing", self.0.path.clone().into())
@@ -100,7 +125,7 @@ impl FileWrite {
     }
 }
 
-impl fmt::Debug for FileWrite {
+impl<F: FileSystem> fmt::Debug for FileWrite<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "FileWrite(")?;
         self.0.path.fmt(f)?;
@@ -108,7 +133,7 @@ impl fmt::Debug for FileWrite {
     }
 }
 
-impl io::Write for FileWrite {
+impl<F: FileSystem> io::Write for FileWrite<F> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.0.file.write(buf).map_err(|err| {
             io::Error::new(
@@ -128,7 +153,7 @@ impl io::Write for FileWrite {
     }
 }
 
-impl io::Seek for FileWrite {
+impl<F: FileSystem> io::Seek for FileWrite<F> {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
         self.0.file.seek(pos).map_err(|err| {
             io::Error::new(
@@ -139,8 +164,8 @@ impl io::Seek for FileWrite {
     }
 }
 
-impl AsRef<FileOpen> for FileWrite {
-    fn as_ref(&self) -> &FileOpen {
+impl<F: FileSystem> AsRef<FileOpen<F>> for FileWrite<F> {
+    fn as_ref(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -151,8 +176,8 @@ impl AsRef<File> for FileWrite {
     }
 }
 
-impl Borrow<FileOpen> for FileWrite {
-    fn borrow(&self) -> &FileOpen {
+impl<F: FileSystem> Borrow<FileOpen<F>> for FileWrite<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -163,8 +188,8 @@ impl Borrow<File> for FileWrite {
     }
 }
 
-impl<'a> Borrow<FileOpen> for &'a FileWrite {
-    fn borrow(&self) -> &FileOpen {
+impl<'a, F: FileSystem> Borrow<FileOpen<F>> for &'a FileWrite<F> {
+    fn borrow(&self) -> &FileOpen<F> {
         &self.0
     }
 }
@@ -175,8 +200,8 @@ impl<'a> Borrow<File> for &'a FileWrite {
     }
 }
 
-impl From<FileWrite> for FileOpen {
-    fn from(orig: FileWrite) -> FileOpen {
+impl<F: FileSystem> From<FileWrite<F>> for FileOpen<F> {
+    fn from(orig: FileWrite<F>) -> FileOpen<F> {
         orig.0
     }
 }
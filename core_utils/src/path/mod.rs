@@ -1,4 +1,41 @@
+/// 比较和哈希只使用路径，不使用文件系统后端
+macro_rules! path_cmp {
+    ($name:ident) => {
+        impl<F> PartialEq for $name<F> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<F> Eq for $name<F> {}
+
+        impl<F> PartialOrd for $name<F> {
+            fn partial_cmp(
+                &self,
+                other: &Self,
+            ) -> Option<::std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<F> Ord for $name<F> {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl<F> ::std::hash::Hash for $name<F> {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+    };
+}
+
 mod abs;
+mod atomic_write;
+mod backend;
+mod copy_dir;
 mod error;
 mod file_edit;
 mod file_open;
@@ -12,8 +49,16 @@ mod path_ops;
 mod path_type;
 #[cfg(feature = "serialize")]
 mod ser;
+mod walk;
+mod watch;
 
 pub use abs::PathAbs;
+pub use atomic_write::AtomicWrite;
+pub use backend::{
+    Fault, FileKind, FileSystem, FsFile, MemFile, MemFs, Metadata,
+    OpenOptions, Operation, OsFs, Permissions,
+};
+pub use copy_dir::{Compare, CopyOptions, CopyProgress, Overwrite, Symlinks};
 pub use error::{Error, Result};
 pub use file_edit::FileEdit;
 pub use file_open::FileOpen;
@@ -27,6 +72,8 @@ pub use path_ops::PathOps;
 pub use path_type::PathType;
 #[cfg(feature = "serialize")]
 pub use ser::{PathSer, ToStfu8};
+pub use walk::{Walk, WalkIter};
+pub use watch::{Event, NextEvent, Watch, Watcher};
 
 // use regex::Regex;
 use std::path::Path;
@@ -1,42 +1,31 @@
-use super::{Error, Result};
-use super::{PathAbs, PathInfo, PathOps, PathType};
+use super::copy_dir::Mode;
+use super::{CopyOptions, Error, Result, Walk, Watch};
+use super::{FileSystem, OsFs, PathAbs, PathInfo, PathOps, PathType};
 use std::borrow::Borrow;
 use std::ffi;
 use std::fmt;
-use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone)]
 /// A `PathAbs` that is guaranteed to be a directory, with associated methods.
-pub struct PathDir(pub(crate) PathAbs);
+///
+/// Like `PathFile`, the file system is accessed through the backend `F`.
+pub struct PathDir<F = OsFs>(pub(crate) PathAbs, pub(crate) F);
 
 impl PathDir {
     /// Instantiate a new `PathDir`. The directory must exist or `io::Error` will be returned.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<PathDir> {
-        let abs = PathAbs::new(path)?;
-        PathDir::try_from(abs)
+        PathDir::new_in(path, OsFs)
     }
 
     pub fn new_unchecked<P: Into<Arc<PathBuf>>>(path: P) -> PathDir {
-        PathDir(PathAbs::new_unchecked(path))
+        PathDir(PathAbs::new_unchecked(path), OsFs)
     }
 
     pub fn try_from<P: Into<PathAbs>>(path: P) -> Result<PathDir> {
-        let abs = path.into();
-        if abs.is_dir() {
-            Ok(PathDir::new_unchecked(abs))
-        } else {
-            Err(Error::new(
-                io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "path is not a dir",
-                ),
-                "resolving",
-                abs.into(),
-            ))
-        }
+        PathDir::try_from_in(path, OsFs)
     }
 
     pub fn current_dir() -> Result<PathDir> {
@@ -51,7 +40,50 @@ impl PathDir {
     }
 
     pub fn create<P: AsRef<Path>>(path: P) -> Result<PathDir> {
-        if let Err(err) = fs::create_dir(&path) {
+        PathDir::create_in(path, OsFs)
+    }
+
+    pub fn create_all<P: AsRef<Path>>(path: P) -> Result<PathDir> {
+        PathDir::create_all_in(path, OsFs)
+    }
+
+    /// Watch the directory for changes, see `Watch`.
+    pub fn watch(&self) -> Watch {
+        Watch::new(self.clone())
+    }
+}
+
+impl<F: FileSystem> PathDir<F> {
+    /// Like `new`, in the file system `fs`.
+    pub fn new_in<P: AsRef<Path>>(path: P, fs: F) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(path)?;
+        PathDir::try_from_in(abs, fs)
+    }
+
+    /// Like `try_from`, in the file system `fs`.
+    pub fn try_from_in<P: Into<PathAbs>>(
+        path: P,
+        fs: F,
+    ) -> Result<PathDir<F>> {
+        let abs = path.into();
+        if fs.metadata(abs.as_path()).is_ok_and(|m| m.is_dir()) {
+            Ok(PathDir(abs, fs))
+        } else {
+            Err(Error::new(
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "path is not a dir",
+                ),
+                "resolving",
+                abs.into(),
+            ))
+        }
+    }
+
+    /// Like `create`, in the file system `fs`.
+    pub fn create_in<P: AsRef<Path>>(path: P, fs: F) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&path)?;
+        if let Err(err) = fs.create_dir(abs.as_path()) {
             match err.kind() {
                 io::ErrorKind::AlreadyExists => {}
                 _ => {
@@ -63,61 +95,197 @@ impl PathDir {
                 }
             }
         }
-        PathDir::new(path)
+        PathDir::try_from_in(abs, fs)
     }
 
-    pub fn create_all<P: AsRef<Path>>(path: P) -> Result<PathDir> {
-        fs::create_dir_all(&path).map_err(|err| {
+    /// Like `create_all`, in the file system `fs`.
+    pub fn create_all_in<P: AsRef<Path>>(
+        path: P,
+        fs: F,
+    ) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&path)?;
+        fs.create_dir_all(abs.as_path()).map_err(|err| {
             Error::new(err, "creating-all", path.as_ref().to_path_buf().into())
         })?;
-        PathDir::new(path)
+        PathDir::try_from_in(abs, fs)
     }
 
-    pub fn list(&self) -> Result<ListDir> {
-        let fsread = fs::read_dir(self).map_err(|err| {
+    /// The file system of the directory.
+    pub fn fs(&self) -> &F {
+        &self.1
+    }
+
+    /// Whether the path exists in the file system of the directory.
+    ///
+    /// Unlike `PathInfo::exists`, which always queries the OS.
+    pub fn exists(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok()
+    }
+
+    /// Whether the path is a file in the file system of the directory.
+    pub fn is_file(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok_and(|m| m.is_file())
+    }
+
+    /// Whether the path is a directory in the file system of the directory.
+    pub fn is_dir(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok_and(|m| m.is_dir())
+    }
+
+    pub fn list(&self) -> Result<ListDir<F>> {
+        let fsread = self.1.read_dir(self.as_path()).map_err(|err| {
             Error::new(err, "reading dir", self.clone().into())
         })?;
         Ok(ListDir { dir: self.clone(), fsread: fsread })
     }
 
     pub fn remove(self) -> Result<()> {
-        fs::remove_dir(&self)
+        self.1
+            .remove_dir(self.as_path())
             .map_err(|err| Error::new(err, "removing", self.into()))
     }
 
     pub fn remove_all(self) -> Result<()> {
-        fs::remove_dir_all(&self)
+        self.1
+            .remove_dir_all(self.as_path())
             .map_err(|err| Error::new(err, "removing-all", self.into()))
     }
 
-    pub fn symlink<P: AsRef<Path>>(&self, dst: P) -> Result<PathDir> {
-        symlink_dir(&self, &dst).map_err(|err| {
+    pub fn symlink<P: AsRef<Path>>(&self, dst: P) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&dst)?;
+        self.1.symlink_dir(self.as_path(), abs.as_path()).map_err(|err| {
             Error::new(
                 err,
                 &format!("linking from {} to", dst.as_ref().display()),
                 self.clone().into(),
             )
         })?;
-        PathDir::new(dst)
+        PathDir::try_from_in(abs, self.1.clone())
     }
 
     pub fn as_path(&self) -> &Path {
         self.as_ref()
     }
 
-    pub fn canonicalize(&self) -> Result<PathDir> {
-        Ok(PathDir(self.0.canonicalize()?))
+    pub fn canonicalize(&self) -> Result<PathDir<F>> {
+        let path = self.1.canonicalize(self.as_path()).map_err(|err| {
+            Error::new(err, "canonicalizing", self.clone().into())
+        })?;
+        Ok(PathDir(PathAbs::new_unchecked(path), self.1.clone()))
     }
 
-    pub fn parent_dir(&self) -> Option<PathDir> {
+    pub fn parent_dir(&self) -> Option<PathDir<F>> {
         match self.parent() {
-            Ok(path) => Some(PathDir(PathAbs(Arc::new(path.to_path_buf())))),
+            Ok(path) => Some(PathDir(
+                PathAbs(Arc::new(path.to_path_buf())),
+                self.1.clone(),
+            )),
             Err(_) => None,
         }
     }
+
+    /// Walk the directory recursively, see `Walk`.
+    pub fn walk(&self) -> Walk<F> {
+        Walk::new(self.clone())
+    }
+
+    /// Copy the content of the directory into `dst`, which is created if
+    /// missing, and return `dst`.
+    pub fn copy_to<P: AsRef<Path>>(
+        &self,
+        dst: P,
+        mut options: CopyOptions<'_>,
+    ) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&dst)?;
+        options.run(&self.1, self.as_path(), abs.as_path(), Mode::Copy)?;
+        PathDir::try_from_in(abs, self.1.clone())
+    }
+
+    /// Like `copy_to`, but only copy what is missing or changed in `dst`,
+    /// as decided by `CopyOptions::compare`. The files of `dst` that are not
+    /// in the directory are kept.
+    pub fn sync_to<P: AsRef<Path>>(
+        &self,
+        dst: P,
+        mut options: CopyOptions<'_>,
+    ) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&dst)?;
+        options.run(&self.1, self.as_path(), abs.as_path(), Mode::Sync)?;
+        PathDir::try_from_in(abs, self.1.clone())
+    }
+
+    /// Move the content of the directory into `dst`, and return `dst`.
+    ///
+    /// The directory is renamed when `dst` doesn't exist and nothing is
+    /// filtered out, without calling the progress callback. Otherwise, e.g.
+    /// across file systems, the entries are copied then removed. What is
+    /// filtered out or skipped stays in place, and followed symlinks are
+    /// removed but not their targets.
+    pub fn move_to<P: AsRef<Path>>(
+        self,
+        dst: P,
+        mut options: CopyOptions<'_>,
+    ) -> Result<PathDir<F>> {
+        let abs = PathAbs::new(&dst)?;
+        let fs = self.1.clone();
+        if options.is_unfiltered()
+            && fs.symlink_metadata(abs.as_path()).is_err()
+        {
+            if let Some(parent) = abs.as_path().parent() {
+                fs.create_dir_all(parent).map_err(|err| {
+                    Error::new(
+                        err,
+                        "creating-all",
+                        parent.to_path_buf().into(),
+                    )
+                })?;
+            }
+            match fs.rename(self.as_path(), abs.as_path()) {
+                Ok(()) => return PathDir::try_from_in(abs, fs),
+                // 跨文件系统时复制后删除
+                Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {}
+                Err(err) => {
+                    return Err(Error::new(
+                        err,
+                        &format!(
+                            "renaming to {} from",
+                            dst.as_ref().display()
+                        ),
+                        self.into(),
+                    ))
+                }
+            }
+        }
+
+        let moved =
+            options.run(&fs, self.as_path(), abs.as_path(), Mode::Copy)?;
+        // 目录排在内容之前，倒序删除
+        for entry in moved.iter().rev().filter(|entry| !entry.is_linked()) {
+            let path = self.as_path().join(entry.relative());
+            let result = if entry.is_dir() && !entry.is_symlink() {
+                remove_moved_dir(&fs, &path)
+            } else {
+                fs.remove_file(&path)
+            };
+            result.map_err(|err| Error::new(err, "removing", path.into()))?;
+        }
+        remove_moved_dir(&fs, self.as_path())
+            .map_err(|err| Error::new(err, "removing", self.into()))?;
+        PathDir::try_from_in(abs, fs)
+    }
+}
+
+/// 跳过的项还在目录里时保留目录
+fn remove_moved_dir<F: FileSystem>(fs: &F, path: &Path) -> io::Result<()> {
+    match fs.remove_dir(path) {
+        Err(err) if err.kind() == io::ErrorKind::DirectoryNotEmpty => Ok(()),
+        result => result,
+    }
 }
 
-impl PathOps for PathDir {
+path_cmp!(PathDir);
+
+impl<F: Clone> PathOps for PathDir<F> {
     type Output = PathAbs;
 
     fn concat<P: AsRef<Path>>(&self, path: P) -> Result<Self::Output> {
@@ -144,134 +312,110 @@ impl PathOps for PathDir {
     }
 }
 
-impl fmt::Debug for PathDir {
+impl<F> fmt::Debug for PathDir<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
-impl AsRef<ffi::OsStr> for PathDir {
+impl<F> AsRef<ffi::OsStr> for PathDir<F> {
     fn as_ref(&self) -> &std::ffi::OsStr {
         self.0.as_ref()
     }
 }
 
-impl AsRef<PathAbs> for PathDir {
+impl<F> AsRef<PathAbs> for PathDir<F> {
     fn as_ref(&self) -> &PathAbs {
         &self.0
     }
 }
 
-impl AsRef<Path> for PathDir {
+impl<F> AsRef<Path> for PathDir<F> {
     fn as_ref(&self) -> &Path {
         self.0.as_ref()
     }
 }
 
-impl AsRef<PathBuf> for PathDir {
+impl<F> AsRef<PathBuf> for PathDir<F> {
     fn as_ref(&self) -> &PathBuf {
         self.0.as_ref()
     }
 }
 
-impl Borrow<PathAbs> for PathDir {
+impl<F> Borrow<PathAbs> for PathDir<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl Borrow<Path> for PathDir {
+impl<F> Borrow<Path> for PathDir<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl Borrow<PathBuf> for PathDir {
+impl<F> Borrow<PathBuf> for PathDir<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathAbs> for &'a PathDir {
+impl<'a, F> Borrow<PathAbs> for &'a PathDir<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<Path> for &'a PathDir {
+impl<'a, F> Borrow<Path> for &'a PathDir<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathBuf> for &'a PathDir {
+impl<'a, F> Borrow<PathBuf> for &'a PathDir<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl From<PathDir> for PathAbs {
-    fn from(path: PathDir) -> PathAbs {
+impl<F> From<PathDir<F>> for PathAbs {
+    fn from(path: PathDir<F>) -> PathAbs {
         path.0
     }
 }
 
-impl From<PathDir> for Arc<PathBuf> {
-    fn from(path: PathDir) -> Arc<PathBuf> {
+impl<F> From<PathDir<F>> for Arc<PathBuf> {
+    fn from(path: PathDir<F>) -> Arc<PathBuf> {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-impl From<PathDir> for PathBuf {
-    fn from(path: PathDir) -> PathBuf {
+impl<F> From<PathDir<F>> for PathBuf {
+    fn from(path: PathDir<F>) -> PathBuf {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-#[cfg(target_os = "wasi")]
-fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    std::os::wasi::fs::symlink_path(src, dst)
-}
-
-#[cfg(unix)]
-fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    ::std::os::unix::fs::symlink(src, dst)
-}
-
-#[cfg(windows)]
-fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    ::std::os::windows::fs::symlink_dir(src, dst)
-}
-
-impl PathDir {
-    pub fn join_abs<P: AsRef<Path>>(&self, path: P) -> Result<PathType> {
+impl<F: FileSystem> PathDir<F> {
+    pub fn join_abs<P: AsRef<Path>>(&self, path: P) -> Result<PathType<F>> {
         let joined = self.concat(path.as_ref())?;
-        PathType::new(joined)
+        PathType::new_in(joined, self.1.clone())
     }
 }
 
 /// An iterator over `PathType` objects, returned by `PathDir::list`.
-pub struct ListDir {
+pub struct ListDir<F: FileSystem = OsFs> {
     // TODO: this should be a reference...?
     // Or is this a good excuse to use Arc under the hood everywhere?
-    dir: PathDir,
-    fsread: fs::ReadDir,
+    dir: PathDir<F>,
+    fsread: F::ReadDir,
 }
 
-impl ::std::iter::Iterator for ListDir {
-    type Item = Result<PathType>;
-    fn next(&mut self) -> Option<Result<PathType>> {
+impl<F: FileSystem> ::std::iter::Iterator for ListDir<F> {
+    type Item = Result<PathType<F>>;
+    fn next(&mut self) -> Option<Result<PathType<F>>> {
         let entry = match self.fsread.next() {
             Some(r) => match r {
                 Ok(e) => e,
@@ -285,6 +429,6 @@ impl ::std::iter::Iterator for ListDir {
             },
             None => return None,
         };
-        Some(PathType::new(entry.path()))
+        Some(PathType::new_in(entry, self.dir.1.clone()))
     }
 }
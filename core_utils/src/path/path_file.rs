@@ -1,35 +1,58 @@
 use super::{Error, Result};
 use super::{
-    FileEdit, FileRead, FileWrite, PathAbs, PathDir, PathInfo, PathOps,
+    FileEdit, FileRead, FileSystem, FileWrite, OpenOptions, OsFs, PathAbs,
+    PathDir, PathInfo, PathOps,
 };
 use std::borrow::Borrow;
 use std::ffi;
 use std::fmt;
-use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone)]
 /// a `PathAbs` that was a file at the time of initialization, with associated methods.
-pub struct PathFile(pub(crate) PathAbs);
+///
+/// The file system is accessed through the backend `F`, the OS by default.
+/// `exists`, `is_file` and `is_dir` query `F`, the other `PathInfo` queries
+/// like `metadata` always use the OS, use `fs()` instead.
+pub struct PathFile<F = OsFs>(pub(crate) PathAbs, pub(crate) F);
 
 impl PathFile {
     /// Instantiate a new `PathFile`. The file must exist or `io::Error` will be returned.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<PathFile> {
-        let abs = PathAbs::new(path)?;
-        PathFile::try_from(abs)
+        PathFile::new_in(path, OsFs)
     }
 
     pub fn new_unchecked<P: Into<Arc<PathBuf>>>(path: P) -> PathFile {
-        PathFile(PathAbs::new_unchecked(path))
+        PathFile(PathAbs::new_unchecked(path), OsFs)
     }
 
     /// Convert a `PathAbs` into a `PathFile`, first validating that the path is a file.
     pub fn try_from<P: Into<PathAbs>>(path: P) -> Result<PathFile> {
+        PathFile::try_from_in(path, OsFs)
+    }
+
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<PathFile> {
+        PathFile::create_in(path, OsFs)
+    }
+}
+
+impl<F: FileSystem> PathFile<F> {
+    /// Like `new`, in the file system `fs`.
+    pub fn new_in<P: AsRef<Path>>(path: P, fs: F) -> Result<PathFile<F>> {
+        let abs = PathAbs::new(path)?;
+        PathFile::try_from_in(abs, fs)
+    }
+
+    /// Like `try_from`, in the file system `fs`.
+    pub fn try_from_in<P: Into<PathAbs>>(
+        path: P,
+        fs: F,
+    ) -> Result<PathFile<F>> {
         let abs = path.into();
-        if abs.is_file() {
-            Ok(PathFile::new_unchecked(abs))
+        if fs.metadata(abs.as_path()).is_ok_and(|m| m.is_file()) {
+            Ok(PathFile(abs, fs))
         } else {
             Err(Error::new(
                 io::Error::new(
@@ -42,50 +65,77 @@ impl PathFile {
         }
     }
 
-    pub fn create<P: AsRef<Path>>(path: P) -> Result<PathFile> {
-        fs::OpenOptions::new().write(true).create(true).open(&path).map_err(
-            |err| {
+    /// Like `create`, in the file system `fs`.
+    pub fn create_in<P: AsRef<Path>>(path: P, fs: F) -> Result<PathFile<F>> {
+        let abs = PathAbs::new(&path)?;
+        fs.open(abs.as_path(), OpenOptions::new().write(true).create(true))
+            .map_err(|err| {
                 Error::new(err, "opening", path.as_ref().to_path_buf().into())
-            },
-        )?;
-        PathFile::new(path)
+            })?;
+        PathFile::try_from_in(abs, fs)
+    }
+
+    /// The file system of the file.
+    pub fn fs(&self) -> &F {
+        &self.1
+    }
+
+    /// Whether the path exists in the file system of the file.
+    ///
+    /// Unlike `PathInfo::exists`, which always queries the OS.
+    pub fn exists(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok()
+    }
+
+    /// Whether the path is a file in the file system of the file.
+    pub fn is_file(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok_and(|m| m.is_file())
+    }
+
+    /// Whether the path is a directory in the file system of the file.
+    pub fn is_dir(&self) -> bool {
+        self.1.metadata(self.as_path()).is_ok_and(|m| m.is_dir())
     }
 
-    pub fn copy<P: AsRef<Path>>(&self, path: P) -> Result<PathFile> {
-        fs::copy(&self, &path).map_err(|err| {
+    pub fn copy<P: AsRef<Path>>(&self, path: P) -> Result<PathFile<F>> {
+        let abs = PathAbs::new(&path)?;
+        self.1.copy(self.as_path(), abs.as_path()).map_err(|err| {
             Error::new(
                 err,
                 &format!("copying {} from", path.as_ref().display()),
                 self.clone().into(),
             )
         })?;
-        Ok(PathFile::new(path)?)
+        PathFile::try_from_in(abs, self.1.clone())
     }
 
-    pub fn rename<P: AsRef<Path>>(self, to: P) -> Result<PathFile> {
-        fs::rename(&self, &to).map_err(|err| {
+    pub fn rename<P: AsRef<Path>>(self, to: P) -> Result<PathFile<F>> {
+        let abs = PathAbs::new(&to)?;
+        self.1.rename(self.as_path(), abs.as_path()).map_err(|err| {
             Error::new(
                 err,
                 &format!("renaming to {} from", to.as_ref().display()),
                 self.clone().into(),
             )
         })?;
-        Ok(PathFile::new(to)?)
+        PathFile::try_from_in(abs, self.1)
     }
 
-    pub fn symlink<P: AsRef<Path>>(&self, dst: P) -> Result<PathFile> {
-        symlink_file(&self, &dst).map_err(|err| {
+    pub fn symlink<P: AsRef<Path>>(&self, dst: P) -> Result<PathFile<F>> {
+        let abs = PathAbs::new(&dst)?;
+        self.1.symlink_file(self.as_path(), abs.as_path()).map_err(|err| {
             Error::new(
                 err,
                 &format!("linking from {} to", dst.as_ref().display()),
                 self.clone().into(),
             )
         })?;
-        PathFile::new(dst)
+        PathFile::try_from_in(abs, self.1.clone())
     }
 
     pub fn remove(self) -> Result<()> {
-        fs::remove_file(&self)
+        self.1
+            .remove_file(self.as_path())
             .map_err(|err| Error::new(err, "removing", self.into()))
     }
 
@@ -94,98 +144,103 @@ impl PathFile {
         self.as_ref()
     }
 
-    pub fn canonicalize(&self) -> Result<PathFile> {
-        Ok(PathFile(self.0.canonicalize()?))
+    pub fn canonicalize(&self) -> Result<PathFile<F>> {
+        let path = self.1.canonicalize(self.as_path()).map_err(|err| {
+            Error::new(err, "canonicalizing", self.clone().into())
+        })?;
+        Ok(PathFile(PathAbs::new_unchecked(path), self.1.clone()))
     }
 }
 
-impl fmt::Debug for PathFile {
+path_cmp!(PathFile);
+
+impl<F> fmt::Debug for PathFile<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
-impl AsRef<ffi::OsStr> for PathFile {
+impl<F> AsRef<ffi::OsStr> for PathFile<F> {
     fn as_ref(&self) -> &std::ffi::OsStr {
         self.0.as_ref()
     }
 }
 
-impl AsRef<PathAbs> for PathFile {
+impl<F> AsRef<PathAbs> for PathFile<F> {
     fn as_ref(&self) -> &PathAbs {
         &self.0
     }
 }
 
-impl AsRef<Path> for PathFile {
+impl<F> AsRef<Path> for PathFile<F> {
     fn as_ref(&self) -> &Path {
         self.0.as_ref()
     }
 }
 
-impl AsRef<PathBuf> for PathFile {
+impl<F> AsRef<PathBuf> for PathFile<F> {
     fn as_ref(&self) -> &PathBuf {
         self.0.as_ref()
     }
 }
 
-impl Borrow<PathAbs> for PathFile {
+impl<F> Borrow<PathAbs> for PathFile<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl Borrow<Path> for PathFile {
+impl<F> Borrow<Path> for PathFile<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl Borrow<PathBuf> for PathFile {
+impl<F> Borrow<PathBuf> for PathFile<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathAbs> for &'a PathFile {
+impl<'a, F> Borrow<PathAbs> for &'a PathFile<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<Path> for &'a PathFile {
+impl<'a, F> Borrow<Path> for &'a PathFile<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathBuf> for &'a PathFile {
+impl<'a, F> Borrow<PathBuf> for &'a PathFile<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl From<PathFile> for PathAbs {
-    fn from(path: PathFile) -> PathAbs {
+impl<F> From<PathFile<F>> for PathAbs {
+    fn from(path: PathFile<F>) -> PathAbs {
         path.0
     }
 }
 
-impl From<PathFile> for Arc<PathBuf> {
-    fn from(path: PathFile) -> Arc<PathBuf> {
+impl<F> From<PathFile<F>> for Arc<PathBuf> {
+    fn from(path: PathFile<F>) -> Arc<PathBuf> {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-impl From<PathFile> for PathBuf {
-    fn from(path: PathFile) -> PathBuf {
+impl<F> From<PathFile<F>> for PathBuf {
+    fn from(path: PathFile<F>) -> PathBuf {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-impl PathOps for PathFile {
+impl<F: Clone> PathOps for PathFile<F> {
     type Output = PathAbs;
 
     fn concat<P: AsRef<Path>>(&self, path: P) -> Result<Self::Output> {
@@ -212,44 +267,20 @@ impl PathOps for PathFile {
     }
 }
 
-#[cfg(target_os = "wasi")]
-fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    std::os::wasi::fs::symlink_path(src, dst)
-}
-
-#[cfg(unix)]
-fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    ::std::os::unix::fs::symlink(src, dst)
-}
-
-#[cfg(windows)]
-fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(
-    src: P,
-    dst: Q,
-) -> io::Result<()> {
-    ::std::os::windows::fs::symlink_file(src, dst)
-}
-
-impl PathFile {
+impl<F: FileSystem> PathFile<F> {
     pub fn read_string(&self) -> Result<String> {
         let mut f = self.open_read()?;
         f.read_string()
     }
 
-    pub fn open_read(&self) -> Result<FileRead> {
+    pub fn open_read(&self) -> Result<FileRead<F>> {
         FileRead::open_abs(self.clone())
     }
 }
 
-impl PathFile {
+impl<F: FileSystem> PathFile<F> {
     pub fn write_str(&self, s: &str) -> Result<()> {
-        let mut options = fs::OpenOptions::new();
+        let mut options = OpenOptions::new();
         options.create(true);
         options.truncate(true);
         let mut f = FileWrite::open_abs(self.clone(), options)?;
@@ -269,22 +300,40 @@ impl PathFile {
         f.flush()
     }
 
-    pub fn open_append(&self) -> Result<FileWrite> {
-        let mut options = fs::OpenOptions::new();
+    pub fn open_append(&self) -> Result<FileWrite<F>> {
+        let mut options = OpenOptions::new();
         options.append(true);
         FileWrite::open_abs(self.clone(), options)
     }
 }
 
 impl PathFile {
-    pub fn open_edit(&self) -> Result<FileEdit> {
-        FileEdit::open_abs(self.clone(), fs::OpenOptions::new())
+    /// Replace the content of the file atomically, see `FileWrite::atomic`.
+    pub fn write_atomic(&self, s: &str) -> Result<()> {
+        let mut f = FileWrite::atomic(self)?;
+        f.write_str(s)?;
+        f.commit()?;
+        Ok(())
+    }
+
+    /// Like `write_atomic`, but keep the previous content as `<name>.bak`.
+    pub fn write_with_backup(&self, s: &str) -> Result<()> {
+        let mut f = FileWrite::atomic(self)?;
+        f.write_str(s)?;
+        f.commit_with_backup()?;
+        Ok(())
     }
 }
 
-impl PathFile {
-    pub fn parent_dir(&self) -> PathDir {
+impl<F: FileSystem> PathFile<F> {
+    pub fn open_edit(&self) -> Result<FileEdit<F>> {
+        FileEdit::open_abs(self.clone(), OpenOptions::new())
+    }
+}
+
+impl<F: Clone> PathFile<F> {
+    pub fn parent_dir(&self) -> PathDir<F> {
         let path = self.parent().expect("PathFile did not have a parent.");
-        PathDir::new_unchecked(PathAbs::new_unchecked(path.to_path_buf()))
+        PathDir(PathAbs::new_unchecked(path.to_path_buf()), self.1.clone())
     }
 }
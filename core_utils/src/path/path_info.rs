@@ -7,6 +7,11 @@ use std::io;
 use std::path::{self, Components, Path, PathBuf};
 use std::sync::Arc;
 
+/// Methods of `Path` shared by the path types.
+///
+/// The file system queries use the OS. `PathFile` and `PathDir` have their
+/// own `exists`, `is_file` and `is_dir` querying their file system backend,
+/// which take precedence over these when called as methods.
 pub trait PathInfo {
     fn as_path(&self) -> &Path;
 
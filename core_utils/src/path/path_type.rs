@@ -1,7 +1,9 @@
-use super::Result;
-use super::{PathAbs, PathDir, PathFile, PathInfo, PathOps};
+use super::{Error, Result};
+use super::{FileSystem, OsFs, PathAbs, PathDir, PathFile, PathInfo, PathOps};
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::ffi;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -10,7 +12,16 @@ use std::sync::Arc;
     feature = "serialize",
     serde(tag = "type", content = "path", rename_all = "lowercase")
 )]
-#[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serialize",
+    serde(bound(
+        serialize = "PathFile<F>: serde::Serialize, \
+                     PathDir<F>: serde::Serialize",
+        deserialize = "PathFile<F>: serde::Deserialize<'de>, \
+                       PathDir<F>: serde::Deserialize<'de>"
+    ))
+)]
+#[derive(Debug, Clone)]
 /// An an enum containing either a file or a directory.
 ///
 /// This is used primarily for:
@@ -18,34 +29,50 @@ use std::sync::Arc;
 /// - Serializing paths of different types.
 ///
 /// Note that for symlinks, this returns the underlying file type.
-pub enum PathType {
-    File(PathFile),
-    Dir(PathDir),
+pub enum PathType<F = OsFs> {
+    File(PathFile<F>),
+    Dir(PathDir<F>),
 }
 
 impl PathType {
     /// Resolves and returns the `PathType` of the given path.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<PathType> {
-        let abs = PathAbs::new(&path)?;
-        PathType::try_from(abs)
+        PathType::new_in(path, OsFs)
     }
 
     /// Consume the `PathAbs` returning the `PathType`.
     pub fn try_from<P: Into<PathAbs>>(path: P) -> Result<PathType> {
+        PathType::try_from_in(path, OsFs)
+    }
+}
+
+impl<F: FileSystem> PathType<F> {
+    /// Like `new`, in the file system `fs`.
+    pub fn new_in<P: AsRef<Path>>(path: P, fs: F) -> Result<PathType<F>> {
+        let abs = PathAbs::new(&path)?;
+        PathType::try_from_in(abs, fs)
+    }
+
+    /// Like `try_from`, in the file system `fs`.
+    pub fn try_from_in<P: Into<PathAbs>>(
+        path: P,
+        fs: F,
+    ) -> Result<PathType<F>> {
         let abs = path.into();
-        let ty = abs.metadata()?.file_type();
-        if ty.is_file() {
-            Ok(PathType::File(PathFile(abs)))
-        } else if ty.is_dir() {
-            Ok(PathType::Dir(PathDir(abs)))
+        let metadata = fs.metadata(abs.as_path()).map_err(|err| {
+            Error::new(err, "getting metadata of", abs.clone().into())
+        })?;
+        if metadata.is_dir() {
+            Ok(PathType::Dir(PathDir(abs, fs)))
         } else {
-            unreachable!(
-                "rust docs: The fs::metadata function follows symbolic links"
-            )
+            // metadata 会解析符号链接
+            Ok(PathType::File(PathFile(abs, fs)))
         }
     }
+}
 
-    pub fn unwrap_file(self) -> PathFile {
+impl<F: Clone> PathType<F> {
+    pub fn unwrap_file(self) -> PathFile<F> {
         match self {
             PathType::File(f) => f,
             PathType::Dir(d) => {
@@ -57,7 +84,7 @@ impl PathType {
         }
     }
 
-    pub fn unwrap_dir(self) -> PathDir {
+    pub fn unwrap_dir(self) -> PathDir<F> {
         match self {
             PathType::Dir(d) => d,
             PathType::File(f) => panic!(
@@ -86,13 +113,50 @@ impl PathType {
     }
 }
 
-impl AsRef<ffi::OsStr> for PathType {
+impl<F> PathType<F> {
+    /// 排序时文件在目录之前
+    fn key(&self) -> (u8, &PathAbs) {
+        match self {
+            PathType::File(p) => (0, &p.0),
+            PathType::Dir(p) => (1, &p.0),
+        }
+    }
+}
+
+impl<F> PartialEq for PathType<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<F> Eq for PathType<F> {}
+
+impl<F> PartialOrd for PathType<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F> Ord for PathType<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl<F> Hash for PathType<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl<F> AsRef<ffi::OsStr> for PathType<F> {
     fn as_ref(&self) -> &std::ffi::OsStr {
-        self.as_path().as_ref()
+        let r: &Path = self.as_ref();
+        r.as_ref()
     }
 }
 
-impl AsRef<PathAbs> for PathType {
+impl<F> AsRef<PathAbs> for PathType<F> {
     fn as_ref(&self) -> &PathAbs {
         match *self {
             PathType::File(ref file) => file.as_ref(),
@@ -101,58 +165,58 @@ impl AsRef<PathAbs> for PathType {
     }
 }
 
-impl AsRef<Path> for PathType {
+impl<F> AsRef<Path> for PathType<F> {
     fn as_ref(&self) -> &Path {
         let r: &PathAbs = self.as_ref();
         r.as_ref()
     }
 }
 
-impl AsRef<PathBuf> for PathType {
+impl<F> AsRef<PathBuf> for PathType<F> {
     fn as_ref(&self) -> &PathBuf {
         let r: &PathAbs = self.as_ref();
         r.as_ref()
     }
 }
 
-impl Borrow<PathAbs> for PathType {
+impl<F> Borrow<PathAbs> for PathType<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl Borrow<Path> for PathType {
+impl<F> Borrow<Path> for PathType<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl Borrow<PathBuf> for PathType {
+impl<F> Borrow<PathBuf> for PathType<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathAbs> for &'a PathType {
+impl<'a, F> Borrow<PathAbs> for &'a PathType<F> {
     fn borrow(&self) -> &PathAbs {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<Path> for &'a PathType {
+impl<'a, F> Borrow<Path> for &'a PathType<F> {
     fn borrow(&self) -> &Path {
         self.as_ref()
     }
 }
 
-impl<'a> Borrow<PathBuf> for &'a PathType {
+impl<'a, F> Borrow<PathBuf> for &'a PathType<F> {
     fn borrow(&self) -> &PathBuf {
         self.as_ref()
     }
 }
 
-impl From<PathType> for PathAbs {
-    fn from(path: PathType) -> PathAbs {
+impl<F> From<PathType<F>> for PathAbs {
+    fn from(path: PathType<F>) -> PathAbs {
         match path {
             PathType::File(p) => p.into(),
             PathType::Dir(p) => p.into(),
@@ -160,21 +224,21 @@ impl From<PathType> for PathAbs {
     }
 }
 
-impl From<PathType> for Arc<PathBuf> {
-    fn from(path: PathType) -> Arc<PathBuf> {
+impl<F> From<PathType<F>> for Arc<PathBuf> {
+    fn from(path: PathType<F>) -> Arc<PathBuf> {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-impl From<PathType> for PathBuf {
-    fn from(path: PathType) -> PathBuf {
+impl<F> From<PathType<F>> for PathBuf {
+    fn from(path: PathType<F>) -> PathBuf {
         let abs: PathAbs = path.into();
         abs.into()
     }
 }
 
-impl PathOps for PathType {
+impl<F: Clone> PathOps for PathType<F> {
     type Output = PathAbs;
 
     fn concat<P: AsRef<Path>>(&self, path: P) -> Result<Self::Output> {
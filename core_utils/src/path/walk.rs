@@ -0,0 +1,422 @@
+use globset::GlobSet;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use rayon::prelude::*;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::vec;
+
+use super::copy_dir::build_globs;
+use super::{
+    Error, FileSystem, OpenOptions, OsFs, PathAbs, PathDir, PathFile,
+    PathType, Result,
+};
+
+/// 按顺序读取的忽略文件，后面的优先
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// A recursive walk of a directory, created with `PathDir::walk`.
+///
+/// Iterating yields the entries below the directory depth first, sorted by
+/// name, each directory before its content. `par_for_each` visits them in
+/// parallel instead. Errors are yielded and the walk goes on.
+///
+/// ```ignore
+/// let sources = PathDir::new("src")?
+///     .walk()
+///     .gitignore(true)
+///     .include("**/*.rs")
+///     .into_iter()
+///     .collect::<Result<Vec<_>>>()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Walk<F: FileSystem = OsFs> {
+    root: PathDir<F>,
+    min_depth: usize,
+    max_depth: usize,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    includes: GlobSet,
+    excludes: GlobSet,
+    gitignore: bool,
+    skip_hidden: bool,
+    follow_links: bool,
+}
+
+/// The iterator of a `Walk`.
+pub struct WalkIter<F: FileSystem = OsFs> {
+    walk: Walk<F>,
+    // 第一次调用 next 时才读取根目录
+    root: Option<Frame>,
+    stack: Vec<vec::IntoIter<Child<F>>>,
+}
+
+/// 一个要读取的目录
+#[derive(Clone)]
+struct Frame {
+    dir: PathBuf,
+    depth: usize,
+    /// 从根目录到这个目录的忽略规则
+    ignores: Vec<Arc<Gitignore>>,
+    /// 跟随符号链接时，上级目录的真实路径
+    ancestors: Vec<PathBuf>,
+}
+
+/// 目录的一项，可能要返回，也可能要进入
+struct Child<F: FileSystem> {
+    item: Option<Result<PathType<F>>>,
+    descend: Option<Frame>,
+}
+
+impl<F: FileSystem> Walk<F> {
+    pub(crate) fn new(root: PathDir<F>) -> Walk<F> {
+        Walk {
+            root,
+            min_depth: 1,
+            max_depth: usize::MAX,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            includes: GlobSet::empty(),
+            excludes: GlobSet::empty(),
+            gitignore: false,
+            skip_hidden: false,
+            follow_links: false,
+        }
+    }
+
+    /// Skip the entries less than `depth` levels below the directory. The
+    /// children of the directory are at depth 1, the default.
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Don't go more than `depth` levels below the directory, `1` lists the
+    /// children only and `0` nothing.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Only yield the entries whose path, relative to the directory, matches
+    /// one of the `include` globs. Directories are walked all the same.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self.includes = build_globs(&self.include);
+        self
+    }
+
+    /// Skip the entries whose path, relative to the directory, matches
+    /// `glob`, and the content of such directories.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self.excludes = build_globs(&self.exclude);
+        self
+    }
+
+    /// Skip what the `.gitignore` and `.ignore` files of the directory and
+    /// its subdirectories ignore. Rules of `.ignore` win.
+    pub fn gitignore(mut self, yes: bool) -> Self {
+        self.gitignore = yes;
+        self
+    }
+
+    /// Skip the entries whose name starts with a dot, and their content.
+    pub fn skip_hidden(mut self, yes: bool) -> Self {
+        self.skip_hidden = yes;
+        self
+    }
+
+    /// Walk the directories that symlinks point to. A symlink pointing to
+    /// one of its parents, or to nothing, is an error. Otherwise symlinks
+    /// are yielded without being followed, a dangling one as a file.
+    pub fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self
+    }
+
+    /// Call `callback` with every entry, visiting the directories in
+    /// parallel. The order is not deterministic.
+    pub fn par_for_each<C>(self, callback: C)
+    where
+        F: Send + Sync,
+        C: Fn(Result<PathType<F>>) + Send + Sync,
+    {
+        match self.root_frame() {
+            Ok(frame) => self.par_visit(frame, &callback),
+            Err(err) => callback(Err(err)),
+        }
+    }
+
+    fn par_visit<C>(&self, frame: Frame, callback: &C)
+    where
+        F: Send + Sync,
+        C: Fn(Result<PathType<F>>) + Send + Sync,
+    {
+        let mut subdirs = Vec::new();
+        for child in self.read(&frame) {
+            if let Some(item) = child.item {
+                callback(item);
+            }
+            subdirs.extend(child.descend);
+        }
+        subdirs
+            .into_par_iter()
+            .for_each(|frame| self.par_visit(frame, callback));
+    }
+
+    fn root_frame(&self) -> Result<Frame> {
+        let dir = self.root.as_path().to_path_buf();
+        let mut ancestors = Vec::new();
+        if self.follow_links {
+            ancestors.push(self.canonicalize(&dir)?);
+        }
+        Ok(Frame { dir, depth: 0, ignores: Vec::new(), ancestors })
+    }
+
+    /// 读取一个目录，按名字排序
+    fn read(&self, frame: &Frame) -> Vec<Child<F>> {
+        // 深度范围为空时什么都不返回
+        if self.max_depth == 0 || self.max_depth < self.min_depth {
+            return Vec::new();
+        }
+        let fs = self.root.fs();
+        let mut paths = match fs
+            .read_dir(&frame.dir)
+            .and_then(|read| read.collect::<io::Result<Vec<_>>>())
+        {
+            Ok(paths) => paths,
+            Err(err) => {
+                let path = frame.dir.clone().into();
+                return vec![Child::error(Error::new(
+                    err,
+                    "reading dir",
+                    path,
+                ))];
+            }
+        };
+        paths.sort();
+
+        let mut children = Vec::new();
+        let mut ignores = frame.ignores.clone();
+        if self.gitignore {
+            match self.load_ignores(&frame.dir) {
+                Ok(Some(ignore)) => ignores.push(Arc::new(ignore)),
+                Ok(None) => {}
+                Err(err) => children.push(Child::error(err)),
+            }
+        }
+
+        let depth = frame.depth + 1;
+        for path in paths {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            let relative = path
+                .strip_prefix(self.root.as_path())
+                .unwrap_or(&path)
+                .to_path_buf();
+            if (self.skip_hidden && hidden)
+                || self.excludes.is_match(&relative)
+            {
+                continue;
+            }
+            // 不跟随符号链接时，断开的链接作为文件返回
+            let metadata = match fs.metadata(&path).or_else(|err| {
+                if self.follow_links {
+                    Err(err)
+                } else {
+                    fs.symlink_metadata(&path).map_err(|_| err)
+                }
+            }) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    let path = path.into();
+                    children.push(Child::error(Error::new(
+                        err,
+                        "getting metadata of",
+                        path,
+                    )));
+                    continue;
+                }
+            };
+            let is_dir = metadata.is_dir();
+            if is_ignored(&ignores, &path, is_dir) {
+                continue;
+            }
+
+            let mut child = Child { item: None, descend: None };
+            if is_dir && depth < self.max_depth {
+                match self.descend(frame, &path, depth, &ignores) {
+                    Ok(descend) => child.descend = descend,
+                    Err(err) => child.item = Some(Err(err)),
+                }
+            }
+            if child.item.is_none()
+                && depth >= self.min_depth
+                && (self.include.is_empty()
+                    || self.includes.is_match(relative))
+            {
+                let abs = PathAbs::new_unchecked(path);
+                let fs = fs.clone();
+                child.item = Some(Ok(if is_dir {
+                    PathType::Dir(PathDir(abs, fs))
+                } else {
+                    PathType::File(PathFile(abs, fs))
+                }));
+            }
+            children.push(child);
+        }
+        children
+    }
+
+    /// 进入子目录，默认不进入符号链接
+    fn descend(
+        &self,
+        frame: &Frame,
+        path: &Path,
+        depth: usize,
+        ignores: &[Arc<Gitignore>],
+    ) -> Result<Option<Frame>> {
+        let fs = self.root.fs();
+        let mut ancestors = Vec::new();
+        if self.follow_links {
+            let real = self.canonicalize(path)?;
+            if frame.ancestors.contains(&real) {
+                return Err(Error::new(
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "symlink to a parent directory",
+                    ),
+                    "walking",
+                    path.to_path_buf().into(),
+                ));
+            }
+            ancestors = frame.ancestors.clone();
+            ancestors.push(real);
+        } else if fs.symlink_metadata(path).is_ok_and(|m| m.is_symlink()) {
+            return Ok(None);
+        }
+        Ok(Some(Frame {
+            dir: path.to_path_buf(),
+            depth,
+            ignores: ignores.to_vec(),
+            ancestors,
+        }))
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        self.root.fs().canonicalize(path).map_err(|err| {
+            Error::new(err, "canonicalizing", path.to_path_buf().into())
+        })
+    }
+
+    /// 读取目录的忽略文件，没有时返回 None
+    fn load_ignores(&self, dir: &Path) -> Result<Option<Gitignore>> {
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        for name in IGNORE_FILES {
+            let path = dir.join(name);
+            let mut content = String::new();
+            let read = self
+                .root
+                .fs()
+                .open(&path, OpenOptions::new().read(true))
+                .and_then(|mut file| file.read_to_string(&mut content));
+            match read {
+                Ok(_) => found = true,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(Error::new(err, "reading", path.into()))
+                }
+            }
+            for line in content.lines() {
+                builder.add_line(Some(path.clone()), line).map_err(|err| {
+                    Error::new(
+                        io::Error::new(io::ErrorKind::InvalidData, err),
+                        "parsing",
+                        path.clone().into(),
+                    )
+                })?;
+            }
+        }
+        if !found {
+            return Ok(None);
+        }
+        builder.build().map(Some).map_err(|err| {
+            Error::new(
+                io::Error::new(io::ErrorKind::InvalidData, err),
+                "parsing ignore files in",
+                dir.to_path_buf().into(),
+            )
+        })
+    }
+}
+
+impl<F: FileSystem> Child<F> {
+    fn error(err: Error) -> Child<F> {
+        Child { item: Some(Err(err)), descend: None }
+    }
+}
+
+/// 从最近的目录开始匹配，第一个匹配的规则生效
+fn is_ignored(ignores: &[Arc<Gitignore>], path: &Path, is_dir: bool) -> bool {
+    for ignore in ignores.iter().rev() {
+        let relative = path.strip_prefix(ignore.path()).unwrap_or(path);
+        match ignore.matched(relative, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+    }
+    false
+}
+
+impl<F: FileSystem> IntoIterator for Walk<F> {
+    type Item = Result<PathType<F>>;
+    type IntoIter = WalkIter<F>;
+
+    fn into_iter(self) -> WalkIter<F> {
+        let mut iter = WalkIter { walk: self, root: None, stack: Vec::new() };
+        match iter.walk.root_frame() {
+            Ok(frame) => iter.root = Some(frame),
+            Err(err) => iter.stack.push(vec![Child::error(err)].into_iter()),
+        }
+        iter
+    }
+}
+
+impl<F: FileSystem> Iterator for WalkIter<F> {
+    type Item = Result<PathType<F>>;
+
+    fn next(&mut self) -> Option<Result<PathType<F>>> {
+        if let Some(frame) = self.root.take() {
+            self.stack.push(self.walk.read(&frame).into_iter());
+        }
+        loop {
+            let child = match self.stack.last_mut()?.next() {
+                Some(child) => child,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            // 先返回目录，再返回它的内容
+            if let Some(frame) = child.descend {
+                self.stack.push(self.walk.read(&frame).into_iter());
+            }
+            if let Some(item) = child.item {
+                return Some(item);
+            }
+        }
+    }
+}
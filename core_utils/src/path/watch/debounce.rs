@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use super::super::PathAbs;
+use super::Event;
+
+/// 合并同一个路径在 `delay` 内的事件
+pub(super) struct Debouncer {
+    delay: Duration,
+    /// 按第一次出现的顺序
+    pending: Vec<Pending>,
+}
+
+struct Pending {
+    path: PathAbs,
+    kind: Kind,
+    last: Instant,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Create,
+    Modify,
+    Remove,
+    /// 从这个路径改名而来
+    Rename(PathAbs),
+}
+
+impl Debouncer {
+    pub(super) fn new(delay: Duration) -> Debouncer {
+        Debouncer { delay, pending: Vec::new() }
+    }
+
+    pub(super) fn push(&mut self, event: Event, now: Instant) {
+        match event {
+            Event::Create(path) => self.merge(path, Kind::Create, now),
+            Event::Modify(path) => self.merge(path, Kind::Modify, now),
+            Event::Remove(path) => self.merge(path, Kind::Remove, now),
+            Event::Rename(from, to) => {
+                // 刚创建的文件改名后还是创建，连续改名只保留最初的路径
+                let kind = match self.take(&from) {
+                    Some(Kind::Create) => Kind::Create,
+                    Some(Kind::Rename(first)) => Kind::Rename(first),
+                    _ => Kind::Rename(from),
+                };
+                self.merge(to, kind, now);
+            }
+        }
+    }
+
+    fn take(&mut self, path: &PathAbs) -> Option<Kind> {
+        let index = self.pending.iter().position(|p| &p.path == path)?;
+        Some(self.pending.remove(index).kind)
+    }
+
+    fn merge(&mut self, path: PathAbs, kind: Kind, now: Instant) {
+        let index = match self.pending.iter().position(|p| p.path == path) {
+            Some(index) => index,
+            None => {
+                self.pending.push(Pending { path, kind, last: now });
+                return;
+            }
+        };
+        let merged = match (&self.pending[index].kind, kind) {
+            (Kind::Create, Kind::Modify) => Some(Kind::Create),
+            (Kind::Create, Kind::Remove) => None,
+            (Kind::Remove, Kind::Create | Kind::Modify) => Some(Kind::Modify),
+            (Kind::Rename(from), Kind::Modify) => {
+                Some(Kind::Rename(from.clone()))
+            }
+            (Kind::Rename(from), Kind::Remove) => {
+                // 改名后又删除，等于删除了原来的路径
+                let from = from.clone();
+                self.pending.remove(index);
+                self.merge(from, Kind::Remove, now);
+                return;
+            }
+            (_, kind) => Some(kind),
+        };
+        let pending = &mut self.pending[index];
+        pending.last = now;
+        match merged {
+            Some(kind) => pending.kind = kind,
+            None => {
+                self.pending.remove(index);
+            }
+        }
+    }
+
+    /// 取出安静了 `delay` 的事件
+    pub(super) fn ready(&mut self, now: Instant) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.pending.retain(|pending| {
+            if now.duration_since(pending.last) < self.delay {
+                return true;
+            }
+            let path = pending.path.clone();
+            events.push(match &pending.kind {
+                Kind::Create => Event::Create(path),
+                Kind::Modify => Event::Modify(path),
+                Kind::Remove => Event::Remove(path),
+                Kind::Rename(from) => Event::Rename(from.clone(), path),
+            });
+            false
+        });
+        events
+    }
+}
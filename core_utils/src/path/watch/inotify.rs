@@ -0,0 +1,227 @@
+use nix::errno::Errno;
+use nix::sys::inotify::{
+    AddWatchFlags, InitFlags, Inotify as Fd, InotifyEvent, WatchDescriptor,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::super::{Error, PathAbs, Result};
+use super::{Event, Filter};
+
+/// 每个目录监视的事件
+const MASK: AddWatchFlags = AddWatchFlags::IN_CREATE
+    .union(AddWatchFlags::IN_MODIFY)
+    .union(AddWatchFlags::IN_DELETE)
+    .union(AddWatchFlags::IN_MOVED_FROM)
+    .union(AddWatchFlags::IN_MOVED_TO)
+    .union(AddWatchFlags::IN_DELETE_SELF)
+    .union(AddWatchFlags::IN_ONLYDIR)
+    .union(AddWatchFlags::IN_DONT_FOLLOW);
+
+/// 用 inotify 监视目录，每个子目录一个 watch
+pub(super) struct Inotify {
+    fd: Fd,
+    root: PathBuf,
+    recursive: bool,
+    filter: Arc<Filter>,
+    dirs: HashMap<WatchDescriptor, PathBuf>,
+}
+
+impl Inotify {
+    /// watch 不够用（ENOSPC）等情况返回错误
+    pub(super) fn new(
+        root: &Path,
+        recursive: bool,
+        filter: Arc<Filter>,
+    ) -> io::Result<Inotify> {
+        let fd = Fd::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+        let mut inotify = Inotify {
+            fd,
+            root: root.to_path_buf(),
+            recursive,
+            filter,
+            dirs: HashMap::new(),
+        };
+        inotify.watch_tree(root, &mut Vec::new())?;
+        Ok(inotify)
+    }
+
+    /// 监视目录和它的子目录，把里面已有的路径放进 `found`
+    fn watch_tree(
+        &mut self,
+        dir: &Path,
+        found: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        let wd = self.fd.add_watch(dir, MASK)?;
+        self.dirs.insert(wd, dir.to_path_buf());
+
+        // 读取失败的目录只监视新的变化
+        let mut entries = match fs::read_dir(dir) {
+            Ok(read) => read.filter_map(|entry| entry.ok()).collect(),
+            Err(_) => Vec::new(),
+        };
+        entries.sort_by_key(|entry| entry.path());
+        for entry in entries {
+            let path = entry.path();
+            if self.filter.is_excluded(&path) {
+                continue;
+            }
+            found.push(path.clone());
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if self.recursive && is_dir {
+                self.watch_tree(&path, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 新目录里可能已经有内容，当作新建的
+    fn watch_new(&mut self, dir: &Path, out: &mut Vec<Result<Event>>) {
+        if !self.recursive || self.filter.is_excluded(dir) {
+            return;
+        }
+        let mut found = Vec::new();
+        match self.watch_tree(dir, &mut found) {
+            Ok(()) => {}
+            // 已经被删除了
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                let path = dir.to_path_buf().into();
+                out.push(Err(Error::new(err, "watching", path)));
+            }
+        }
+        for path in found {
+            out.push(Ok(Event::Create(PathAbs::new_unchecked(path))));
+        }
+    }
+
+    /// 改名后的目录还是同一个 watch，更新路径
+    fn rename_dirs(&mut self, from: &Path, to: &Path) {
+        for dir in self.dirs.values_mut() {
+            if let Ok(rest) = dir.strip_prefix(from) {
+                *dir = to.join(rest);
+            }
+        }
+    }
+
+    /// 移出去的目录不再监视
+    fn unwatch(&mut self, dir: &Path) {
+        let fd = &self.fd;
+        self.dirs.retain(|wd, path| {
+            if !path.starts_with(dir) {
+                return true;
+            }
+            let _ = fd.rm_watch(*wd);
+            false
+        });
+    }
+
+    pub(super) fn wait(
+        &mut self,
+        timeout: Duration,
+        out: &mut Vec<Result<Event>>,
+    ) {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_fd().as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+        // 超时或者被信号打断
+        if unsafe { libc::poll(&mut pollfd, 1, timeout) } <= 0 {
+            return;
+        }
+        match self.fd.read_events() {
+            Ok(events) => self.handle(events, out),
+            Err(Errno::EAGAIN) | Err(Errno::EINTR) => {}
+            Err(err) => {
+                let path = self.root.clone().into();
+                out.push(Err(Error::new(
+                    err.into(),
+                    "reading events of",
+                    path,
+                )));
+            }
+        }
+    }
+
+    fn handle(
+        &mut self,
+        events: Vec<InotifyEvent>,
+        out: &mut Vec<Result<Event>>,
+    ) {
+        // 同一批里按 cookie 配对 IN_MOVED_FROM 和 IN_MOVED_TO
+        let mut moved: Vec<(u32, PathBuf, bool)> = Vec::new();
+        for event in events {
+            let mask = event.mask;
+            if mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                let err = io::Error::other("inotify event queue overflowed");
+                let path = self.root.clone().into();
+                out.push(Err(Error::new(err, "watching", path)));
+                continue;
+            }
+            if mask.contains(AddWatchFlags::IN_IGNORED) {
+                self.dirs.remove(&event.wd);
+                continue;
+            }
+            let path = match (self.dirs.get(&event.wd), &event.name) {
+                (Some(dir), Some(name)) => dir.join(name),
+                (Some(dir), None) => dir.clone(),
+                (None, _) => continue,
+            };
+            let is_dir = mask.contains(AddWatchFlags::IN_ISDIR);
+            let abs = || PathAbs::new_unchecked(path.clone());
+
+            if mask.contains(AddWatchFlags::IN_DELETE_SELF) {
+                // 子目录的删除由上级目录报告
+                if path == self.root {
+                    out.push(Ok(Event::Remove(abs())));
+                }
+            } else if mask.contains(AddWatchFlags::IN_CREATE) {
+                out.push(Ok(Event::Create(abs())));
+                if is_dir {
+                    self.watch_new(&path, out);
+                }
+            } else if mask.contains(AddWatchFlags::IN_MODIFY) {
+                if !is_dir {
+                    out.push(Ok(Event::Modify(abs())));
+                }
+            } else if mask.contains(AddWatchFlags::IN_DELETE) {
+                out.push(Ok(Event::Remove(abs())));
+            } else if mask.contains(AddWatchFlags::IN_MOVED_FROM) {
+                moved.push((event.cookie, path, is_dir));
+            } else if mask.contains(AddWatchFlags::IN_MOVED_TO) {
+                let from = moved
+                    .iter()
+                    .position(|(cookie, ..)| *cookie == event.cookie)
+                    .map(|index| moved.remove(index).1);
+                match from {
+                    Some(from) => {
+                        if is_dir {
+                            self.rename_dirs(&from, &path);
+                        }
+                        let from = PathAbs::new_unchecked(from);
+                        out.push(Ok(Event::Rename(from, abs())));
+                    }
+                    None => {
+                        out.push(Ok(Event::Create(abs())));
+                        if is_dir {
+                            self.watch_new(&path, out);
+                        }
+                    }
+                }
+            }
+        }
+        for (_, from, is_dir) in moved {
+            if is_dir {
+                self.unwatch(&from);
+            }
+            out.push(Ok(Event::Remove(PathAbs::new_unchecked(from))));
+        }
+    }
+}
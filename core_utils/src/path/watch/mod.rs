@@ -0,0 +1,439 @@
+use futures_core::Stream;
+use globset::GlobSet;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::copy_dir::build_globs;
+use super::{PathAbs, PathDir, Result};
+
+mod debounce;
+#[cfg(target_os = "linux")]
+mod inotify;
+mod poll;
+
+use debounce::Debouncer;
+
+/// 检查停止标志的最长间隔
+const MAX_TICK: Duration = Duration::from_millis(100);
+
+/// A change below a watched directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A file or directory was created, or moved into the directory.
+    Create(PathAbs),
+    /// The content of a file changed.
+    Modify(PathAbs),
+    /// A file or directory was removed, or moved out of the directory.
+    Remove(PathAbs),
+    /// A file or directory was renamed from the first path to the second.
+    Rename(PathAbs, PathAbs),
+}
+
+impl Event {
+    /// The path the event is about, the new path of a rename.
+    pub fn path(&self) -> &PathAbs {
+        match self {
+            Event::Create(path)
+            | Event::Modify(path)
+            | Event::Remove(path)
+            | Event::Rename(_, path) => path,
+        }
+    }
+}
+
+/// The options of a watcher, created with `PathDir::watch`.
+///
+/// On Linux the directory is watched with inotify, elsewhere or when
+/// inotify fails (e.g. too many watches) its content is polled. Polling
+/// can't tell renames apart, they are reported as a remove and a create.
+///
+/// ```ignore
+/// let watcher = PathDir::new("config")?
+///     .watch()
+///     .include("*.toml")
+///     .debounce(Duration::from_millis(200))
+///     .start()?;
+/// for event in watcher.iter() {
+///     println!("reloading after {:?}", event?);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Watch {
+    root: PathDir,
+    recursive: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    debounce: Duration,
+    poll_interval: Duration,
+    polling: bool,
+}
+
+/// A running watcher, created with `Watch::start`. The events are received
+/// with `recv` and friends, or awaited with `next_event` or as a `Stream`.
+/// Dropping it stops the background thread.
+///
+/// Only one task is woken when an event arrives, so `next_event` and the
+/// `Stream` impl borrow the watcher mutably: a single task consumes the
+/// events asynchronously. The blocking `recv` can be shared by threads.
+pub struct Watcher {
+    shared: Arc<Shared>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    polling: bool,
+}
+
+/// The future of `Watcher::next_event`.
+pub struct NextEvent<'a>(&'a mut Watcher);
+
+/// 后台线程和 Watcher 之间的队列
+struct Shared {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+}
+
+#[derive(Default)]
+struct Queue {
+    events: VecDeque<Result<Event>>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// 按相对路径过滤事件
+struct Filter {
+    root: PathBuf,
+    exclude: Vec<String>,
+    includes: Option<GlobSet>,
+    excludes: GlobSet,
+}
+
+/// 事件的来源
+enum Backend {
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::Inotify),
+    Poll(poll::Poller),
+}
+
+impl Watch {
+    pub(crate) fn new(root: PathDir) -> Watch {
+        Watch {
+            root,
+            recursive: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            debounce: Duration::from_millis(50),
+            poll_interval: Duration::from_secs(1),
+            polling: false,
+        }
+    }
+
+    /// Watch the subdirectories too, the default. Directories created later
+    /// are watched as well.
+    pub fn recursive(mut self, yes: bool) -> Self {
+        self.recursive = yes;
+        self
+    }
+
+    /// Only report the paths, relative to the directory, that match one of
+    /// the `include` globs. A rename is reported if either path matches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        build_globs(&self.include);
+        self
+    }
+
+    /// Ignore the paths, relative to the directory, that match `glob`.
+    /// Matching directories are not watched at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        build_globs(&self.exclude);
+        self
+    }
+
+    /// Wait until a path has been quiet for `delay` before reporting it,
+    /// merging its events meanwhile. Defaults to 50ms.
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// How often the directory is scanned when polling. Defaults to 1s.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Poll even where inotify is available.
+    pub fn polling(mut self, yes: bool) -> Self {
+        self.polling = yes;
+        self
+    }
+
+    /// Start watching in a background thread.
+    pub fn start(self) -> Result<Watcher> {
+        let filter = Arc::new(Filter {
+            root: self.root.as_path().to_path_buf(),
+            exclude: self.exclude.clone(),
+            includes: match self.include.is_empty() {
+                true => None,
+                false => Some(build_globs(&self.include)),
+            },
+            excludes: build_globs(&self.exclude),
+        });
+        let backend = self.backend(&filter)?;
+        let polling = matches!(backend, Backend::Poll(_));
+
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue::default()),
+            ready: Condvar::new(),
+        });
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::Builder::new()
+            .name("path-watcher".into())
+            .spawn({
+                let shared = shared.clone();
+                let stop = stop.clone();
+                let debounce = self.debounce;
+                move || run(backend, &filter, debounce, &shared, &stop)
+            })
+            .expect("failed to spawn the watcher thread");
+        Ok(Watcher { shared, stop, thread: Some(thread), polling })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn backend(&self, filter: &Arc<Filter>) -> Result<Backend> {
+        if !self.polling {
+            // inotify 不可用时退回轮询
+            let root = self.root.as_path();
+            if let Ok(inotify) =
+                inotify::Inotify::new(root, self.recursive, filter.clone())
+            {
+                return Ok(Backend::Inotify(inotify));
+            }
+        }
+        self.poller(filter)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn backend(&self, filter: &Arc<Filter>) -> Result<Backend> {
+        self.poller(filter)
+    }
+
+    fn poller(&self, filter: &Arc<Filter>) -> Result<Backend> {
+        let poller = poll::Poller::new(
+            self.root.clone(),
+            self.recursive,
+            filter.clone(),
+            self.poll_interval,
+        )?;
+        Ok(Backend::Poll(poller))
+    }
+}
+
+impl Backend {
+    /// 等待最多 `timeout`，把收到的事件放进 `out`
+    fn wait(&mut self, timeout: Duration, out: &mut Vec<Result<Event>>) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Backend::Inotify(inotify) => inotify.wait(timeout, out),
+            Backend::Poll(poller) => poller.wait(timeout, out),
+        }
+    }
+}
+
+/// 后台线程：收集事件，合并后放进队列
+fn run(
+    mut backend: Backend,
+    filter: &Filter,
+    debounce: Duration,
+    shared: &Shared,
+    stop: &AtomicBool,
+) {
+    let tick = (debounce / 2).clamp(Duration::from_millis(5), MAX_TICK);
+    let mut debouncer = Debouncer::new(debounce);
+    let mut raw = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        backend.wait(tick, &mut raw);
+        let now = Instant::now();
+        for event in raw.drain(..) {
+            match event {
+                Ok(event) if filter.matches_event(&event) => {
+                    debouncer.push(event, now)
+                }
+                Ok(_) => {}
+                Err(err) => shared.push(Err(err)),
+            }
+        }
+        for event in debouncer.ready(now) {
+            shared.push(Ok(event));
+        }
+    }
+    shared.close();
+}
+
+impl Filter {
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    /// 被排除的目录不用监视
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.excludes.is_match(self.relative(path))
+    }
+
+    fn exclude_globs(&self) -> &[String] {
+        &self.exclude
+    }
+
+    fn matches(&self, path: &PathAbs) -> bool {
+        let path = path.as_ref();
+        !self.is_excluded(path)
+            && match &self.includes {
+                Some(globs) => globs.is_match(self.relative(path)),
+                None => true,
+            }
+    }
+
+    fn matches_event(&self, event: &Event) -> bool {
+        match event {
+            Event::Rename(from, to) => self.matches(from) || self.matches(to),
+            event => self.matches(event.path()),
+        }
+    }
+}
+
+impl Shared {
+    fn push(&self, event: Result<Event>) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.events.push_back(event);
+        self.wake(queue);
+    }
+
+    fn close(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.closed = true;
+        self.wake(queue);
+    }
+
+    fn wake(&self, mut queue: std::sync::MutexGuard<'_, Queue>) {
+        let waker = queue.waker.take();
+        drop(queue);
+        self.ready.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Watcher {
+    /// Whether the directory is polled rather than watched with inotify.
+    pub fn is_polling(&self) -> bool {
+        self.polling
+    }
+
+    /// Wait for the next event. Returns `None` once the watcher stopped.
+    pub fn recv(&self) -> Option<Result<Event>> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self.shared.ready.wait(queue).unwrap();
+        }
+    }
+
+    /// Wait for the next event at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<Event>> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.queue.lock().unwrap();
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            let now = Instant::now();
+            if queue.closed || now >= deadline {
+                return None;
+            }
+            queue = self
+                .shared
+                .ready
+                .wait_timeout(queue, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// The next event if there is one already.
+    pub fn try_recv(&self) -> Option<Result<Event>> {
+        self.shared.queue.lock().unwrap().events.pop_front()
+    }
+
+    /// Iterate over the events, blocking for each.
+    pub fn iter(&self) -> impl Iterator<Item = Result<Event>> + '_ {
+        std::iter::from_fn(move || self.recv())
+    }
+
+    /// Wait for the next event asynchronously, with any executor.
+    pub fn next_event(&mut self) -> NextEvent<'_> {
+        NextEvent(self)
+    }
+
+    /// `Ready(None)` once the watcher stopped, otherwise registers the
+    /// waker of the task waiting for events.
+    fn poll_event(&self, cx: &mut Context<'_>) -> Poll<Option<Result<Event>>> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        if let Some(event) = queue.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Future for NextEvent<'_> {
+    type Output = Option<Result<Event>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_event(cx)
+    }
+}
+
+impl Stream for Watcher {
+    type Item = Result<Event>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_event(cx)
+    }
+}
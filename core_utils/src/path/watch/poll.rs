@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::super::{PathAbs, PathDir, Result};
+use super::{Event, Filter};
+
+/// 定期扫描目录，比较前后两次的快照
+pub(super) struct Poller {
+    root: PathDir,
+    recursive: bool,
+    filter: Arc<Filter>,
+    interval: Duration,
+    next_scan: Instant,
+    snapshot: BTreeMap<PathBuf, Stat>,
+}
+
+#[derive(PartialEq)]
+struct Stat {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Poller {
+    /// 根目录读取失败时返回错误
+    pub(super) fn new(
+        root: PathDir,
+        recursive: bool,
+        filter: Arc<Filter>,
+        interval: Duration,
+    ) -> Result<Poller> {
+        root.list()?;
+        let mut poller = Poller {
+            root,
+            recursive,
+            filter,
+            interval,
+            next_scan: Instant::now() + interval,
+            snapshot: BTreeMap::new(),
+        };
+        poller.snapshot = poller.scan();
+        Ok(poller)
+    }
+
+    pub(super) fn wait(
+        &mut self,
+        timeout: Duration,
+        out: &mut Vec<Result<Event>>,
+    ) {
+        let now = Instant::now();
+        if now < self.next_scan {
+            thread::sleep(timeout.min(self.next_scan - now));
+            return;
+        }
+        self.next_scan = now + self.interval;
+
+        let snapshot = self.scan();
+        for (path, stat) in &snapshot {
+            let event = match self.snapshot.get(path) {
+                None => Event::Create,
+                // 目录的修改时间随内容变化，不算修改
+                Some(old) if old.is_dir && stat.is_dir => continue,
+                Some(old) if old != stat => Event::Modify,
+                Some(_) => continue,
+            };
+            out.push(Ok(event(PathAbs::new_unchecked(path.clone()))));
+        }
+        for path in self.snapshot.keys() {
+            if !snapshot.contains_key(path) {
+                let path = PathAbs::new_unchecked(path.clone());
+                out.push(Ok(Event::Remove(path)));
+            }
+        }
+        self.snapshot = snapshot;
+    }
+
+    /// 扫描时消失的文件留到下一次
+    fn scan(&self) -> BTreeMap<PathBuf, Stat> {
+        let mut walk = self.root.walk();
+        if !self.recursive {
+            walk = walk.max_depth(1);
+        }
+        for glob in self.filter.exclude_globs() {
+            walk = walk.exclude(glob.as_str());
+        }
+        walk.into_iter()
+            .filter_map(|entry| {
+                let path: PathBuf = entry.ok()?.into();
+                let metadata = fs::metadata(&path).ok()?;
+                let stat = Stat {
+                    is_dir: metadata.is_dir(),
+                    len: metadata.len(),
+                    modified: metadata.modified().ok(),
+                };
+                Some((path, stat))
+            })
+            .collect()
+    }
+}
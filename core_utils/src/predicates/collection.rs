@@ -0,0 +1,537 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::debug::DebugAdapter;
+use crate::predicates::core::{
+    Case, Child, Palette, Parameter, Predicate, PredicateReflection, Product,
+};
+
+// The predicates of this module accept any collection `C` whose reference
+// iterates over `&T`: slices, arrays, `Vec`, `VecDeque`, sets, ...
+
+/// 集合的所有元素，用于输出
+fn elements<'x, C, T>(variable: &'x C) -> DebugAdapter<Vec<&'x T>>
+where
+    C: ?Sized,
+    &'x C: IntoIterator<Item = &'x T>,
+    T: fmt::Debug + 'x,
+{
+    DebugAdapter::new(variable.into_iter().collect())
+}
+
+/// Predicate that returns `true` if every element of the collection
+/// satisfies `predicate`.
+///
+/// This is created by the `predicates::all` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllPredicate<P> {
+    predicate: P,
+}
+
+impl<C, T, P> Predicate<C> for AllPredicate<P>
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    T: fmt::Debug,
+    P: Predicate<T>,
+{
+    fn eval(&self, variable: &C) -> bool {
+        variable.into_iter().all(|item| self.predicate.eval(item))
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        // 第一个不满足条件的元素
+        let failed = variable.into_iter().enumerate().find_map(|(i, item)| {
+            self.predicate
+                .find_case(false, item)
+                .map(|child| (i, DebugAdapter::new(item).to_string(), child))
+        });
+        match (expected, failed) {
+            (true, None) => Some(Case::new(Some(self), true).add_product(
+                Product::new("var", elements(variable).to_string()),
+            )),
+            (false, Some((index, item, child))) => Some(
+                Case::new(Some(self), false)
+                    .add_product(Product::new("index", index))
+                    .add_product(Product::new("var", item))
+                    .add_child(child),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<P> PredicateReflection for AllPredicate<P>
+where
+    P: PredicateReflection,
+{
+    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = Child<'a>> + 'a> {
+        let params = vec![Child::new("predicate", &self.predicate)];
+        Box::new(params.into_iter())
+    }
+}
+
+impl<P> fmt::Display for AllPredicate<P>
+where
+    P: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{}.{}({})",
+            palette.var("var"),
+            palette.description("all"),
+            self.predicate
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when every element of the
+/// given collection satisfies `predicate`.
+pub fn all<P>(predicate: P) -> AllPredicate<P> {
+    AllPredicate { predicate }
+}
+
+/// Predicate that returns `true` if at least one element of the collection
+/// satisfies `predicate`.
+///
+/// This is created by the `predicates::any` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnyPredicate<P> {
+    predicate: P,
+}
+
+impl<C, T, P> Predicate<C> for AnyPredicate<P>
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    T: fmt::Debug,
+    P: Predicate<T>,
+{
+    fn eval(&self, variable: &C) -> bool {
+        variable.into_iter().any(|item| self.predicate.eval(item))
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        // 第一个满足条件的元素
+        let passed = variable.into_iter().enumerate().find_map(|(i, item)| {
+            self.predicate
+                .find_case(true, item)
+                .map(|child| (i, DebugAdapter::new(item).to_string(), child))
+        });
+        match (expected, passed) {
+            (true, Some((index, item, child))) => Some(
+                Case::new(Some(self), true)
+                    .add_product(Product::new("index", index))
+                    .add_product(Product::new("var", item))
+                    .add_child(child),
+            ),
+            (false, None) => Some(Case::new(Some(self), false).add_product(
+                Product::new("var", elements(variable).to_string()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl<P> PredicateReflection for AnyPredicate<P>
+where
+    P: PredicateReflection,
+{
+    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = Child<'a>> + 'a> {
+        let params = vec![Child::new("predicate", &self.predicate)];
+        Box::new(params.into_iter())
+    }
+}
+
+impl<P> fmt::Display for AnyPredicate<P>
+where
+    P: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{}.{}({})",
+            palette.var("var"),
+            palette.description("any"),
+            self.predicate
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when at least one element
+/// of the given collection satisfies `predicate`.
+pub fn any<P>(predicate: P) -> AnyPredicate<P> {
+    AnyPredicate { predicate }
+}
+
+/// Predicate that checks the number of elements of the collection.
+///
+/// This is created by the `predicates::len` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LenPredicate<P> {
+    predicate: P,
+}
+
+impl<C, T, P> Predicate<C> for LenPredicate<P>
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    P: Predicate<usize>,
+{
+    fn eval(&self, variable: &C) -> bool {
+        self.predicate.eval(&variable.into_iter().count())
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        let len = variable.into_iter().count();
+        self.predicate.find_case(expected, &len).map(|child| {
+            Case::new(Some(self), expected)
+                .add_product(Product::new("len", len))
+                .add_child(child)
+        })
+    }
+}
+
+impl<P> PredicateReflection for LenPredicate<P>
+where
+    P: PredicateReflection,
+{
+    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = Child<'a>> + 'a> {
+        let params = vec![Child::new("predicate", &self.predicate)];
+        Box::new(params.into_iter())
+    }
+}
+
+impl<P> fmt::Display for LenPredicate<P>
+where
+    P: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{}.{}({})",
+            palette.var("var"),
+            palette.description("len"),
+            self.predicate
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when the number of
+/// elements of the given collection satisfies `predicate`.
+pub fn len<P>(predicate: P) -> LenPredicate<P>
+where
+    P: Predicate<usize>,
+{
+    LenPredicate { predicate }
+}
+
+/// Predicate that returns `true` if the collection contains the pre-defined
+/// items in the same order, not necessarily next to each other.
+///
+/// This is created by the `predicates::contains_in_order` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainsInOrderPredicate<T>
+where
+    T: fmt::Debug,
+{
+    items: DebugAdapter<Vec<T>>,
+}
+
+impl<T> ContainsInOrderPredicate<T>
+where
+    T: PartialEq + fmt::Debug,
+{
+    /// 返回第一个找不到的期望元素，以及上一个元素匹配的位置
+    fn missing<'x, C>(&self, variable: &'x C) -> Option<(usize, Option<usize>)>
+    where
+        C: ?Sized,
+        &'x C: IntoIterator<Item = &'x T>,
+        T: 'x,
+    {
+        let mut actual = variable.into_iter().enumerate();
+        let mut last = None;
+        for (index, item) in self.items.debug.iter().enumerate() {
+            match actual.find(|(_, actual)| *actual == item) {
+                Some((position, _)) => last = Some(position),
+                None => return Some((index, last)),
+            }
+        }
+        None
+    }
+}
+
+impl<C, T> Predicate<C> for ContainsInOrderPredicate<T>
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    T: PartialEq + fmt::Debug,
+{
+    fn eval(&self, variable: &C) -> bool {
+        self.missing(variable).is_none()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        let missing = self.missing(variable);
+        if missing.is_none() != expected {
+            return None;
+        }
+
+        let mut case = Case::new(Some(self), expected)
+            .add_product(Product::new("var", elements(variable).to_string()));
+        if let Some((index, last)) = missing {
+            case = case.add_product(Product::new(
+                "missing",
+                DebugAdapter::new(&self.items.debug[index]).to_string(),
+            ));
+            case = case.add_product(Product::new("index", index));
+            if let Some(last) = last {
+                case = case.add_product(Product::new("after", last));
+            }
+        }
+        Some(case)
+    }
+}
+
+impl<T> PredicateReflection for ContainsInOrderPredicate<T>
+where
+    T: fmt::Debug,
+{
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = Parameter<'a>> + 'a> {
+        let params = vec![Parameter::new("values", &self.items)];
+        Box::new(params.into_iter())
+    }
+}
+
+impl<T> fmt::Display for ContainsInOrderPredicate<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{}.{}({})",
+            palette.var("var"),
+            palette.description("contains_in_order"),
+            palette.expected("values")
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when the given collection
+/// contains the items, in order, with any other elements in between.
+pub fn contains_in_order<I, T>(items: I) -> ContainsInOrderPredicate<T>
+where
+    I: IntoIterator<Item = T>,
+    T: PartialEq + fmt::Debug,
+{
+    ContainsInOrderPredicate {
+        items: DebugAdapter::new(items.into_iter().collect()),
+    }
+}
+
+/// Predicate that returns `true` if the elements of the collection are in
+/// ascending order.
+///
+/// This is created by the `predicates::is_sorted` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsSortedPredicate {
+    descending: bool,
+}
+
+impl IsSortedPredicate {
+    /// Expect the elements in descending order instead.
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// 返回第一个顺序错误的位置
+    fn unsorted<'x, C, T>(&self, variable: &'x C) -> Option<usize>
+    where
+        C: ?Sized,
+        &'x C: IntoIterator<Item = &'x T>,
+        T: PartialOrd + 'x,
+    {
+        let wrong =
+            if self.descending { Ordering::Less } else { Ordering::Greater };
+        let mut items = variable.into_iter();
+        let mut previous = items.next()?;
+        for (index, item) in items.enumerate() {
+            // 无法比较的元素（例如 NaN）也视为顺序错误
+            match previous.partial_cmp(item) {
+                Some(ordering) if ordering != wrong => previous = item,
+                _ => return Some(index),
+            }
+        }
+        None
+    }
+}
+
+impl<C, T> Predicate<C> for IsSortedPredicate
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    T: PartialOrd + fmt::Debug,
+{
+    fn eval(&self, variable: &C) -> bool {
+        self.unsorted(variable).is_none()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        let unsorted = self.unsorted(variable);
+        if unsorted.is_none() != expected {
+            return None;
+        }
+
+        let case = Case::new(Some(self), expected);
+        let Some(index) = unsorted else {
+            return Some(case.add_product(Product::new(
+                "var",
+                elements(variable).to_string(),
+            )));
+        };
+        let items = elements(variable).debug;
+        Some(
+            case.add_product(Product::new("index", index + 1))
+                .add_product(Product::new(
+                    "previous",
+                    DebugAdapter::new(items[index]).to_string(),
+                ))
+                .add_product(Product::new(
+                    "var",
+                    DebugAdapter::new(items[index + 1]).to_string(),
+                )),
+        )
+    }
+}
+
+impl PredicateReflection for IsSortedPredicate {}
+
+impl fmt::Display for IsSortedPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        let description =
+            if self.descending { "is_sorted_desc" } else { "is_sorted" };
+        write!(
+            f,
+            "{}.{}()",
+            palette.var("var"),
+            palette.description(description)
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when the elements of the
+/// given collection are sorted in ascending order.
+pub fn is_sorted() -> IsSortedPredicate {
+    IsSortedPredicate { descending: false }
+}
+
+/// Predicate that returns `true` if no element of the collection appears
+/// twice.
+///
+/// This is created by the `predicates::unique` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniquePredicate;
+
+impl UniquePredicate {
+    /// 返回第一个重复元素的位置，以及它第一次出现的位置
+    fn duplicate<'x, C, T>(&self, variable: &'x C) -> Option<(usize, usize)>
+    where
+        C: ?Sized,
+        &'x C: IntoIterator<Item = &'x T>,
+        T: PartialEq + 'x,
+    {
+        // 只要求 PartialEq，所以线性查找
+        let mut seen: Vec<&T> = Vec::new();
+        for (index, item) in variable.into_iter().enumerate() {
+            if let Some(first) = seen.iter().position(|seen| *seen == item) {
+                return Some((index, first));
+            }
+            seen.push(item);
+        }
+        None
+    }
+}
+
+impl<C, T> Predicate<C> for UniquePredicate
+where
+    C: ?Sized,
+    for<'x> &'x C: IntoIterator<Item = &'x T>,
+    T: PartialEq + fmt::Debug,
+{
+    fn eval(&self, variable: &C) -> bool {
+        self.duplicate(variable).is_none()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &C,
+    ) -> Option<Case<'a>> {
+        let duplicate = self.duplicate(variable);
+        if duplicate.is_none() != expected {
+            return None;
+        }
+
+        let case = Case::new(Some(self), expected);
+        let Some((index, first)) = duplicate else {
+            return Some(case.add_product(Product::new(
+                "var",
+                elements(variable).to_string(),
+            )));
+        };
+        let items = elements(variable).debug;
+        Some(
+            case.add_product(Product::new("index", index))
+                .add_product(Product::new("first", first))
+                .add_product(Product::new(
+                    "var",
+                    DebugAdapter::new(items[index]).to_string(),
+                )),
+        )
+    }
+}
+
+impl PredicateReflection for UniquePredicate {}
+
+impl fmt::Display for UniquePredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(f, "{}.{}()", palette.var("var"), palette.description("unique"))
+    }
+}
+
+/// Creates a new predicate that will return `true` when no element of the
+/// given collection is equal to another one.
+pub fn unique() -> UniquePredicate {
+    UniquePredicate
+}
@@ -5,6 +5,7 @@ pub mod str;
 
 mod boolean;
 mod boxed;
+mod collection;
 mod function;
 mod iter;
 mod name;
@@ -12,9 +13,17 @@ mod ord;
 
 pub use boolean::{always, never, BooleanPredicate, PredicateBooleanExt};
 pub use boxed::{BoxPredicate, PredicateBoxExt};
+pub use collection::{
+    all, any, contains_in_order, is_sorted, len, unique, AllPredicate,
+    AnyPredicate, ContainsInOrderPredicate, IsSortedPredicate, LenPredicate,
+    UniquePredicate,
+};
 pub use function::{function, FnPredicate};
 pub use iter::{
     in_hash, in_iter, HashableInPredicate, InPredicate, OrdInPredicate,
 };
 pub use name::{NamePredicate, PredicateNameExt};
-pub use ord::{eq, ge, gt, le, lt, ne, EqPredicate, OrdPredicate};
+pub use ord::{
+    between, eq, ge, gt, le, lt, ne, BetweenPredicate, EqPredicate,
+    OrdPredicate,
+};
@@ -190,3 +190,72 @@ where
 {
     OrdPredicate { constant, op: OrdOps::GreaterThan }
 }
+
+/// Predicate that returns `true` if `variable` is within the pre-defined
+/// inclusive range, otherwise returns `false`.
+///
+/// This is created by the `predicate::between` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BetweenPredicate<T> {
+    low: T,
+    high: T,
+}
+
+impl<P, T> Predicate<P> for BetweenPredicate<T>
+where
+    T: std::borrow::Borrow<P> + fmt::Debug,
+    P: fmt::Debug + PartialOrd + ?Sized,
+{
+    fn eval(&self, variable: &P) -> bool {
+        variable.ge(self.low.borrow()) && variable.le(self.high.borrow())
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &P,
+    ) -> Option<Case<'a>> {
+        default_find_case(self, expected, variable).map(|case| {
+            let case = case.add_product(Product::new(
+                "var",
+                DebugAdapter::new(variable).to_string(),
+            ));
+            // 说明超出了哪一边
+            if variable.lt(self.low.borrow()) {
+                case.add_product(Product::new("reason", "below the range"))
+            } else if variable.gt(self.high.borrow()) {
+                case.add_product(Product::new("reason", "above the range"))
+            } else {
+                case
+            }
+        })
+    }
+}
+
+impl<T> PredicateReflection for BetweenPredicate<T> where T: fmt::Debug {}
+
+impl<T> fmt::Display for BetweenPredicate<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{} {} {}..={}",
+            palette.var("var"),
+            palette.description("in"),
+            palette.expected(DebugAdapter::new(&self.low)),
+            palette.expected(DebugAdapter::new(&self.high)),
+        )
+    }
+}
+
+/// Creates a new predicate that will return `true` when the given `variable`
+/// is greater than or equal to `low` and less than or equal to `high`.
+pub fn between<T>(low: T, high: T) -> BetweenPredicate<T>
+where
+    T: PartialOrd + fmt::Debug,
+{
+    BetweenPredicate { low, high }
+}
@@ -0,0 +1,258 @@
+use crate::predicates::core::{
+    Case, Palette, Parameter, Predicate, PredicateReflection, Product,
+};
+use crate::predicates::str::diff;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path;
+use walkdir::WalkDir;
+
+/// 目录中的一项
+#[derive(Debug, PartialEq, Eq)]
+enum Entry {
+    File,
+    Dir,
+    Symlink(path::PathBuf),
+}
+
+/// 两个目录的一处不同
+#[derive(Debug)]
+enum Change {
+    Added(String),
+    Missing(String),
+    Changed(String, String), // 文件名和差异
+}
+
+/// Predicate that compares a directory tree to an expected one.
+///
+/// This is created by `predicates::path::eq_dir`.
+#[derive(Debug, Clone)]
+pub struct DirPredicate {
+    path: path::PathBuf,
+    ignore: Vec<String>,
+    globs: GlobSet,
+    normalize_newlines: bool,
+}
+
+impl DirPredicate {
+    /// Skip the files and directories whose path, relative to the tree root,
+    /// matches `glob`, in both trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glob` is not a valid glob.
+    pub fn ignore<S: Into<String>>(mut self, glob: S) -> Self {
+        self.ignore.push(glob.into());
+        let mut builder = GlobSetBuilder::new();
+        for glob in &self.ignore {
+            builder.add(Glob::new(glob).expect("invalid glob"));
+        }
+        self.globs = builder.build().expect("invalid glob");
+        self
+    }
+
+    /// Treat `\r\n` and `\n` as equal when comparing file contents.
+    pub fn normalize_newlines(mut self) -> Self {
+        self.normalize_newlines = true;
+        self
+    }
+
+    /// 遍历目录，返回相对路径到文件类型的映射
+    fn entries(
+        &self,
+        root: &path::Path,
+    ) -> io::Result<BTreeMap<path::PathBuf, Entry>> {
+        let mut entries = BTreeMap::new();
+        let walk = WalkDir::new(root).min_depth(1).into_iter().filter_entry(
+            |entry| {
+                let relative =
+                    entry.path().strip_prefix(root).unwrap_or(entry.path());
+                !self.globs.is_match(relative)
+            },
+        );
+        for entry in walk {
+            let entry = entry?;
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .unwrap_or(entry.path())
+                .to_path_buf();
+            let file_type = entry.file_type();
+            let kind = if file_type.is_symlink() {
+                Entry::Symlink(fs::read_link(entry.path())?)
+            } else if file_type.is_dir() {
+                Entry::Dir
+            } else {
+                Entry::File
+            };
+            entries.insert(relative, kind);
+        }
+        Ok(entries)
+    }
+
+    /// 比较两个目录，返回所有不同
+    fn changes(&self, actual: &path::Path) -> io::Result<Vec<Change>> {
+        let expected_entries = self.entries(&self.path)?;
+        let actual_entries = self.entries(actual)?;
+
+        let mut changes: Vec<(&path::Path, Change)> = Vec::new();
+        for (path, expected) in &expected_entries {
+            let name = path.display().to_string();
+            match (expected, actual_entries.get(path)) {
+                (_, None) => changes.push((path, Change::Missing(name))),
+                (Entry::Dir, Some(Entry::Dir)) => {}
+                (Entry::File, Some(Entry::File)) => {
+                    let expected = fs::read(self.path.join(path))?;
+                    let actual = fs::read(actual.join(path))?;
+                    if let Some(diff) = self.content_diff(expected, actual) {
+                        changes.push((path, Change::Changed(name, diff)));
+                    }
+                }
+                (Entry::Symlink(expected), Some(Entry::Symlink(actual)))
+                    if expected == actual => {}
+                (expected, Some(actual)) => changes.push((
+                    path,
+                    Change::Changed(
+                        name,
+                        format!("expected {:?}, got {:?}", expected, actual),
+                    ),
+                )),
+            }
+        }
+        for path in actual_entries.keys() {
+            if !expected_entries.contains_key(path) {
+                let name = path.display().to_string();
+                changes.push((path, Change::Added(name)));
+            }
+        }
+
+        // 已经报告为新增或者缺失的目录，不再报告其中的文件
+        let mut reported: Vec<&path::Path> = Vec::new();
+        let changes = changes
+            .into_iter()
+            .filter(|(path, change)| {
+                if reported.iter().any(|dir| path.starts_with(dir)) {
+                    return false;
+                }
+                if !matches!(change, Change::Changed(..)) {
+                    reported.push(path);
+                }
+                true
+            })
+            .map(|(_, change)| change)
+            .collect();
+        Ok(changes)
+    }
+
+    /// 比较文件内容，不同时返回差异
+    fn content_diff(
+        &self,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    ) -> Option<String> {
+        let (expected, actual) =
+            match (String::from_utf8(expected), String::from_utf8(actual)) {
+                (Ok(expected), Ok(actual)) => (expected, actual),
+                (expected, actual) => {
+                    let expected = expected
+                        .map_or_else(|e| e.into_bytes(), String::into_bytes);
+                    let actual = actual
+                        .map_or_else(|e| e.into_bytes(), String::into_bytes);
+                    return if expected == actual {
+                        None
+                    } else {
+                        Some(format!(
+                            "binary content differs ({} bytes, got {} bytes)",
+                            expected.len(),
+                            actual.len()
+                        ))
+                    };
+                }
+            };
+        let (expected, actual) = if self.normalize_newlines {
+            (expected.replace("\r\n", "\n"), actual.replace("\r\n", "\n"))
+        } else {
+            (expected, actual)
+        };
+        diff(expected).find_case(false, &actual).and_then(|case| {
+            case.products()
+                .find(|product| product.name() == "diff")
+                .map(|product| product.value().to_string())
+        })
+    }
+}
+
+impl Predicate<path::Path> for DirPredicate {
+    fn eval(&self, path: &path::Path) -> bool {
+        self.changes(path).map(|changes| changes.is_empty()).unwrap_or(false)
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &path::Path,
+    ) -> Option<Case<'a>> {
+        let changes = self.changes(variable);
+        let result = matches!(&changes, Ok(changes) if changes.is_empty());
+        if result != expected {
+            return None;
+        }
+
+        let mut case = Case::new(Some(self), result)
+            .add_product(Product::new("var", variable.display().to_string()));
+        match changes {
+            Ok(changes) => {
+                for change in changes {
+                    let product = match change {
+                        Change::Added(name) => Product::new("added", name),
+                        Change::Missing(name) => Product::new("missing", name),
+                        Change::Changed(name, diff) => Product::new(
+                            "changed",
+                            format!("{}\n{}", name, diff.trim_end()),
+                        ),
+                    };
+                    case = case.add_product(product);
+                }
+            }
+            Err(err) => case = case.add_product(Product::new("error", err)),
+        }
+        Some(case)
+    }
+}
+
+impl PredicateReflection for DirPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = Parameter<'a>> + 'a> {
+        let params =
+            self.ignore.iter().map(|glob| Parameter::new("ignore", glob));
+        Box::new(params)
+    }
+}
+
+impl fmt::Display for DirPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        write!(
+            f,
+            "{} {} {}",
+            palette.var("var"),
+            palette.description("eq_dir"),
+            palette.expected(self.path.display())
+        )
+    }
+}
+
+/// Creates a new `Predicate` that ensures a directory tree has the same
+/// files, with the same content, as the tree at `path`.
+pub fn eq_dir<P: Into<path::PathBuf>>(path: P) -> DirPredicate {
+    DirPredicate {
+        path: path.into(),
+        ignore: Vec::new(),
+        globs: GlobSet::empty(),
+        normalize_newlines: false,
+    }
+}
@@ -1,8 +1,10 @@
+mod dir;
 mod existence;
 mod fc;
 mod fs;
 mod ft;
 
+pub use dir::{eq_dir, DirPredicate};
 pub use existence::{exists, missing, ExistencePredicate};
 pub use fc::{FileContentPredicate, PredicateFileContentExt};
 pub use fs::{eq_file, BinaryFilePredicate, StrFilePredicate};
@@ -7,6 +7,7 @@ mod matches;
 mod normalize;
 mod regex;
 mod starts_with;
+mod structured;
 mod trim;
 mod utf8;
 
@@ -19,5 +20,6 @@ pub use matches::*;
 pub use normalize::*;
 pub use regex::*;
 pub use starts_with::*;
+pub use structured::*;
 pub use trim::*;
 pub use utf8::*;
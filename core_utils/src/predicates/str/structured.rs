@@ -0,0 +1,280 @@
+use crate::predicates::core::{
+    Case, Palette, Parameter, Predicate, PredicateReflection, Product,
+};
+use serde_json::Value;
+use std::fmt;
+
+/// 被比较的文本格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+        }
+    }
+
+    fn parse(self, variable: &str) -> Result<Value, String> {
+        match self {
+            Format::Json => {
+                serde_json::from_str(variable).map_err(|e| e.to_string())
+            }
+            Format::Yaml => {
+                serde_yaml::from_str(variable).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Predicate that parses a str as JSON or YAML and compares it structurally
+/// to an expected value, ignoring key order and formatting.
+///
+/// This is created by `predicates::str::json` and `predicates::str::yaml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredPredicate {
+    format: Format,
+    expected: Value,
+    pointer: Option<String>,
+    subset: bool,
+}
+
+impl StructuredPredicate {
+    /// Only compare the value at the JSON pointer `pointer`, e.g.
+    /// `/items/0/name`.
+    pub fn at<P: Into<String>>(mut self, pointer: P) -> Self {
+        self.pointer = Some(pointer.into());
+        self
+    }
+
+    /// Allow keys that are not in the expected value, and extra trailing
+    /// elements in arrays.
+    pub fn subset(mut self) -> Self {
+        self.subset = true;
+        self
+    }
+
+    /// 找到第一个不同的地方
+    fn mismatch(&self, variable: &str) -> Option<Mismatch> {
+        let actual = match self.format.parse(variable) {
+            Ok(actual) => actual,
+            Err(error) => {
+                return Some(Mismatch {
+                    path: String::new(),
+                    reason: format!(
+                        "invalid {}: {}",
+                        self.format.name(),
+                        error
+                    ),
+                    expected: None,
+                    actual: None,
+                })
+            }
+        };
+
+        let (path, actual) = match self.pointer.as_deref() {
+            Some(pointer) => match actual.pointer(pointer) {
+                Some(actual) => (pointer.to_owned(), actual),
+                None => {
+                    return Some(Mismatch {
+                        path: pointer.to_owned(),
+                        reason: "missing".to_owned(),
+                        expected: Some(self.expected.clone()),
+                        actual: None,
+                    })
+                }
+            },
+            None => (String::new(), &actual),
+        };
+        compare(&self.expected, actual, self.subset, path)
+    }
+}
+
+/// 第一个不同的地方
+#[derive(Debug)]
+struct Mismatch {
+    path: String,
+    reason: String,
+    expected: Option<Value>,
+    actual: Option<Value>,
+}
+
+/// 递归比较，path 是 JSON pointer
+fn compare(
+    expected: &Value,
+    actual: &Value,
+    subset: bool,
+    path: String,
+) -> Option<Mismatch> {
+    let mismatch = |reason: &str| Mismatch {
+        path: path.clone(),
+        reason: reason.to_owned(),
+        expected: Some(expected.clone()),
+        actual: Some(actual.clone()),
+    };
+
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let path = format!("{}/{}", path, escape(key));
+                match actual.get(key) {
+                    Some(actual) => {
+                        if let Some(m) = compare(value, actual, subset, path) {
+                            return Some(m);
+                        }
+                    }
+                    None => {
+                        return Some(Mismatch {
+                            path,
+                            reason: "missing".to_owned(),
+                            expected: Some(value.clone()),
+                            actual: None,
+                        })
+                    }
+                }
+            }
+            if subset {
+                return None;
+            }
+            actual.iter().find(|(key, _)| !expected.contains_key(*key)).map(
+                |(key, value)| Mismatch {
+                    path: format!("{}/{}", path, escape(key)),
+                    reason: "unexpected".to_owned(),
+                    expected: None,
+                    actual: Some(value.clone()),
+                },
+            )
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            let len_matches = if subset {
+                actual_items.len() >= expected_items.len()
+            } else {
+                actual_items.len() == expected_items.len()
+            };
+            let items = expected_items.iter().zip(actual_items);
+            for (index, (expected, actual)) in items.enumerate() {
+                let path = format!("{}/{}", path, index);
+                if let Some(m) = compare(expected, actual, subset, path) {
+                    return Some(m);
+                }
+            }
+            if len_matches {
+                None
+            } else {
+                Some(mismatch(&format!(
+                    "expected {} items, got {}",
+                    expected_items.len(),
+                    actual_items.len()
+                )))
+            }
+        }
+        // 1 和 1.0 相等
+        (Value::Number(e), Value::Number(a)) if e.as_f64() == a.as_f64() => {
+            None
+        }
+        _ if expected == actual => None,
+        _ => Some(mismatch("differs")),
+    }
+}
+
+/// JSON pointer 中的 `~` 和 `/` 需要转义
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+impl Predicate<str> for StructuredPredicate {
+    fn eval(&self, variable: &str) -> bool {
+        self.mismatch(variable).is_none()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &str,
+    ) -> Option<Case<'a>> {
+        let mismatch = self.mismatch(variable);
+        let result = mismatch.is_none();
+        if result != expected {
+            return None;
+        }
+
+        let case = Case::new(Some(self), result);
+        let Some(mismatch) = mismatch else {
+            return Some(
+                case.add_product(Product::new("var", variable.to_owned())),
+            );
+        };
+        let path = if mismatch.path.is_empty() {
+            "(root)".to_owned()
+        } else {
+            mismatch.path
+        };
+        let mut case = case
+            .add_product(Product::new("path", path))
+            .add_product(Product::new("reason", mismatch.reason));
+        if let Some(expected) = mismatch.expected {
+            case = case.add_product(Product::new("expected", expected));
+        }
+        if let Some(actual) = mismatch.actual {
+            case = case.add_product(Product::new("actual", actual));
+        }
+        Some(case)
+    }
+}
+
+impl PredicateReflection for StructuredPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = Parameter<'a>> + 'a> {
+        let params = self
+            .pointer
+            .as_ref()
+            .map(|pointer| Parameter::new("pointer", pointer));
+        Box::new(params.into_iter())
+    }
+}
+
+impl fmt::Display for StructuredPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let palette = Palette::new(f.alternate());
+        let description = match (self.format, self.subset) {
+            (Format::Json, false) => "json_eq",
+            (Format::Json, true) => "json_subset",
+            (Format::Yaml, false) => "yaml_eq",
+            (Format::Yaml, true) => "yaml_subset",
+        };
+        write!(
+            f,
+            "{}.{}({})",
+            palette.var("var"),
+            palette.description(description),
+            palette.expected(&self.expected),
+        )
+    }
+}
+
+/// Creates a new `Predicate` that parses a str as JSON and ensures it equals
+/// `expected`.
+pub fn json<V: Into<Value>>(expected: V) -> StructuredPredicate {
+    StructuredPredicate {
+        format: Format::Json,
+        expected: expected.into(),
+        pointer: None,
+        subset: false,
+    }
+}
+
+/// Creates a new `Predicate` that parses a str as YAML and ensures it equals
+/// `expected`.
+pub fn yaml<V: Into<Value>>(expected: V) -> StructuredPredicate {
+    StructuredPredicate {
+        format: Format::Yaml,
+        expected: expected.into(),
+        pointer: None,
+        subset: false,
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::random::{Arbitrary, Gen};
+
+impl<A: Arbitrary> Arbitrary for Vec<A> {
+    fn arbitrary(g: &mut Gen) -> Vec<A> {
+        let len = g.gen_range(0..=g.size());
+        (0..len).map(|_| A::arbitrary(g)).collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Vec<A>>> {
+        let xs = self.clone();
+
+        // 删除元素：先删除全部，然后依次删除一半、四分之一……的连续元素
+        let mut removed = Vec::new();
+        if !xs.is_empty() {
+            removed.push(Vec::new());
+        }
+        let mut k = xs.len() / 2;
+        while k > 0 {
+            for start in (0..=xs.len() - k).step_by(k) {
+                let mut ys = xs[..start].to_vec();
+                ys.extend_from_slice(&xs[start + k..]);
+                removed.push(ys);
+            }
+            k /= 2;
+        }
+
+        // 长度不变，依次收缩每个元素
+        let elements = (0..xs.len()).flat_map(move |i| {
+            let xs = xs.clone();
+            xs[i].shrink().map(move |x| {
+                let mut ys = xs.clone();
+                ys[i] = x;
+                ys
+            })
+        });
+        Box::new(removed.into_iter().chain(elements))
+    }
+}
+
+impl<K, V> Arbitrary for HashMap<K, V>
+where
+    K: Arbitrary + Eq + Hash,
+    V: Arbitrary,
+{
+    fn arbitrary(g: &mut Gen) -> HashMap<K, V> {
+        let pairs: Vec<(K, V)> = Arbitrary::arbitrary(g);
+        pairs.into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = HashMap<K, V>>> {
+        let pairs: Vec<(K, V)> = self.clone().into_iter().collect();
+        Box::new(pairs.shrink().map(|pairs| pairs.into_iter().collect()))
+    }
+}
+
+impl<A: Arbitrary, const N: usize> Arbitrary for [A; N] {
+    fn arbitrary(g: &mut Gen) -> [A; N] {
+        std::array::from_fn(|_| A::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = [A; N]>> {
+        // 长度固定，只能收缩元素
+        let xs = self.clone();
+        Box::new((0..N).flat_map(move |i| {
+            let xs = xs.clone();
+            xs[i].shrink().map(move |x| {
+                let mut ys = xs.clone();
+                ys[i] = x;
+                ys
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::arby;
+
+    #[test]
+    fn test_vec_arbitrary() {
+        let mut g = Gen::new(5);
+        for _ in 0..20 {
+            assert!(Vec::<u8>::arbitrary(&mut g).len() <= 5);
+        }
+    }
+
+    #[test]
+    fn test_vec_shrink() {
+        let xs: Vec<Vec<u8>> = vec![1u8, 2].shrink().collect();
+        assert_eq!(
+            xs,
+            [vec![], vec![2], vec![1], vec![0, 2], vec![1, 0], vec![1, 1]]
+        );
+        assert_eq!(Vec::<u8>::new().shrink().count(), 0);
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let map = arby::<HashMap<u8, bool>>(10);
+        assert!(map.len() <= 10);
+        let map: HashMap<u8, bool> = [(3, true)].into_iter().collect();
+        let smaller: Vec<HashMap<u8, bool>> = map.shrink().collect();
+        assert_eq!(smaller[0], HashMap::new());
+    }
+
+    #[test]
+    fn test_array_shrink() {
+        let xs: Vec<[u8; 2]> = [0u8, 2].shrink().collect();
+        assert_eq!(xs, [[0, 0], [0, 1]]);
+    }
+}
@@ -1,6 +1,10 @@
 mod arbitrary;
+mod collections;
+mod numeric;
 mod option;
 mod result;
+mod text;
+mod tuple;
 
 pub use arbitrary::*;
 pub use option::*;
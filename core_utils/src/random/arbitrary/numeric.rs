@@ -0,0 +1,162 @@
+use crate::iterator::empty_shrinker;
+use crate::random::{Arbitrary, Gen};
+
+/// 整数向 0 收缩：0，然后逐步接近自身
+macro_rules! shrink_toward_zero {
+    ($x:expr) => {{
+        let x = $x;
+        let first = if x == 0 { None } else { Some(0) };
+        // x - x/2, x - x/4, ...，直到等于 x
+        let mut delta = x / 2;
+        let steps = std::iter::from_fn(move || {
+            if delta == 0 {
+                return None;
+            }
+            let next = x - delta;
+            delta /= 2;
+            Some(next)
+        });
+        first.into_iter().chain(steps)
+    }};
+}
+
+macro_rules! unsigned_arbitrary {
+    ($($ty:ty),*) => {
+        $(
+            impl Arbitrary for $ty {
+                fn arbitrary(g: &mut Gen) -> $ty {
+                    // 偶尔生成边界值
+                    if g.gen_range(0..8) == 0 {
+                        return *g.choose(&[<$ty>::MIN, 1, <$ty>::MAX]).unwrap();
+                    }
+                    let max = <$ty>::try_from(g.size()).unwrap_or(<$ty>::MAX);
+                    g.gen_range(0..=max)
+                }
+
+                fn shrink(&self) -> Box<dyn Iterator<Item = $ty>> {
+                    Box::new(shrink_toward_zero!(*self))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_arbitrary {
+    ($($ty:ty),*) => {
+        $(
+            impl Arbitrary for $ty {
+                fn arbitrary(g: &mut Gen) -> $ty {
+                    // 偶尔生成边界值
+                    if g.gen_range(0..8) == 0 {
+                        let edges = [<$ty>::MIN, -1, 0, 1, <$ty>::MAX];
+                        return *g.choose(&edges).unwrap();
+                    }
+                    let max = <$ty>::try_from(g.size()).unwrap_or(<$ty>::MAX);
+                    g.gen_range(-max..=max)
+                }
+
+                fn shrink(&self) -> Box<dyn Iterator<Item = $ty>> {
+                    let x = *self;
+                    // 负数先尝试对应的正数
+                    let abs = if x < 0 { x.checked_neg() } else { None };
+                    Box::new(abs.into_iter().chain(shrink_toward_zero!(x)))
+                }
+            }
+        )*
+    };
+}
+
+unsigned_arbitrary!(u8, u16, u32, u64, u128, usize);
+signed_arbitrary!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! float_arbitrary {
+    ($($ty:ty),*) => {
+        $(
+            impl Arbitrary for $ty {
+                fn arbitrary(g: &mut Gen) -> $ty {
+                    // 偶尔生成特殊值
+                    if g.gen_range(0..8) == 0 {
+                        let specials = [
+                            0.0,
+                            -0.0,
+                            1.0,
+                            -1.0,
+                            <$ty>::EPSILON,
+                            <$ty>::MIN,
+                            <$ty>::MAX,
+                            <$ty>::MIN_POSITIVE,
+                            <$ty>::INFINITY,
+                            <$ty>::NEG_INFINITY,
+                            <$ty>::NAN,
+                        ];
+                        return *g.choose(&specials).unwrap();
+                    }
+                    let max = g.size().max(1) as $ty;
+                    g.gen_range(-max..=max)
+                }
+
+                fn shrink(&self) -> Box<dyn Iterator<Item = $ty>> {
+                    let x = *self;
+                    if x == 0.0 {
+                        return empty_shrinker();
+                    }
+                    if !x.is_finite() {
+                        return Box::new(std::iter::once(0.0));
+                    }
+                    if x.fract() != 0.0 {
+                        // 先去掉小数部分
+                        return Box::new([0.0, x.trunc()].into_iter());
+                    }
+                    if x.abs() < (1u64 << 53) as $ty {
+                        return Box::new(
+                            shrink_toward_zero!(x as i64).map(|x| x as $ty),
+                        );
+                    }
+                    Box::new([0.0, x / 2.0].into_iter())
+                }
+            }
+        )*
+    };
+}
+
+float_arbitrary!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::arby;
+
+    #[test]
+    fn test_integer_shrink() {
+        let xs: Vec<i32> = 100.shrink().collect();
+        assert_eq!(xs, [0, 50, 75, 88, 94, 97, 99]);
+
+        let xs: Vec<i32> = (-8).shrink().collect();
+        assert_eq!(xs, [8, 0, -4, -6, -7]);
+
+        assert_eq!(0u8.shrink().count(), 0);
+        assert!(i64::MIN.shrink().all(|x| x > i64::MIN));
+    }
+
+    #[test]
+    fn test_integer_arbitrary() {
+        let mut g = Gen::new(10);
+        for _ in 0..100 {
+            let x = i8::arbitrary(&mut g);
+            assert!(
+                (-10..=10).contains(&x) || [i8::MIN, i8::MAX].contains(&x)
+            );
+        }
+        let _ = arby::<u128>(5);
+        let _ = arby::<isize>(5);
+    }
+
+    #[test]
+    fn test_float_shrink() {
+        let xs: Vec<f64> = 2.5.shrink().collect();
+        assert_eq!(xs, [0.0, 2.0]);
+        let xs: Vec<f64> = f64::NAN.shrink().collect();
+        assert_eq!(xs, [0.0]);
+        assert_eq!(0.0f32.shrink().count(), 0);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::random::{Arbitrary, Gen};
+
+impl Arbitrary for char {
+    fn arbitrary(g: &mut Gen) -> char {
+        // 大部分是可打印的 ASCII 字符，偶尔是任意 unicode 字符
+        if g.gen_range(0..4) == 0 {
+            g.gen()
+        } else {
+            g.gen_range(' '..='~')
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = char>> {
+        // 依次尝试更简单的字符，只保留码点更小的，保证收缩会结束
+        let x = *self;
+        let simple = ['a', 'b', 'c', 'A', 'B', 'C', '0', '1', ' '];
+        let lower = x.to_ascii_lowercase();
+        let half = char::from_u32(x as u32 / 2);
+        let candidates = simple.into_iter().chain(Some(lower)).chain(half);
+
+        let mut chars = Vec::new();
+        for c in candidates {
+            if c < x && !chars.contains(&c) {
+                chars.push(c);
+            }
+        }
+        Box::new(chars.into_iter())
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(g: &mut Gen) -> String {
+        let chars: Vec<char> = Arbitrary::arbitrary(g);
+        chars.into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = String>> {
+        let chars: Vec<char> = self.chars().collect();
+        Box::new(chars.shrink().map(|chars| chars.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_shrink() {
+        let xs: Vec<char> = 'z'.shrink().collect();
+        assert_eq!(xs, ['a', 'b', 'c', 'A', 'B', 'C', '0', '1', ' ', '=']);
+        assert_eq!('\0'.shrink().count(), 0);
+    }
+
+    #[test]
+    fn test_string_shrink() {
+        let xs: Vec<String> = "ab".to_owned().shrink().collect();
+        assert_eq!(xs[0], "");
+        assert!(xs.contains(&"a".to_owned()));
+        assert!(xs.contains(&"aa".to_owned()));
+    }
+}
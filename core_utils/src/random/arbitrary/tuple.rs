@@ -0,0 +1,47 @@
+use crate::random::{Arbitrary, Gen};
+
+macro_rules! tuple_arbitrary {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: Arbitrary),+> Arbitrary for ($($name,)+) {
+            fn arbitrary(g: &mut Gen) -> ($($name,)+) {
+                ($($name::arbitrary(g),)+)
+            }
+
+            fn shrink(&self) -> Box<dyn Iterator<Item = ($($name,)+)>> {
+                // 每次只收缩一个元素，其余元素保持不变
+                let shrinkers: Vec<Box<dyn Iterator<Item = Self>>> = vec![$({
+                    let tuple = self.clone();
+                    Box::new(self.$index.shrink().map(move |x| {
+                        let mut tuple = tuple.clone();
+                        tuple.$index = x;
+                        tuple
+                    }))
+                }),+];
+                Box::new(shrinkers.into_iter().flatten())
+            }
+        }
+    };
+}
+
+tuple_arbitrary!(A: 0);
+tuple_arbitrary!(A: 0, B: 1);
+tuple_arbitrary!(A: 0, B: 1, C: 2);
+tuple_arbitrary!(A: 0, B: 1, C: 2, D: 3);
+tuple_arbitrary!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_arbitrary!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_arbitrary!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_arbitrary!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::arby;
+
+    #[test]
+    fn test_tuple_shrink() {
+        let xs: Vec<(u8, bool)> = (2u8, true).shrink().collect();
+        assert_eq!(xs, [(0, true), (1, true), (2, false)]);
+
+        let _ = arby::<(u8, i16, char, String, Vec<bool>, f32, u64, i8)>(5);
+    }
+}
@@ -1,9 +1,21 @@
 use rand::seq::SliceRandom;
-use rand::{self, Rng, SeedableRng};
+use rand::{self, Rng, RngCore, SeedableRng};
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::vec;
+
+/// Environment variable that forces the seed of `Gen::new`, to replay a
+/// failure printed by `forall`.
+pub const SEED_ENV: &str = "CORE_UTILS_SEED";
+
+/// `Gen::from_seed` 使用的默认大小
+const DEFAULT_SIZE: usize = 100;
 
 pub struct Gen {
-    rng: rand::rngs::SmallRng, // 伪随机数生成器
+    rng: Source, // 伪随机数生成器
     size: usize,
+    seed: u64, // 随机种子，用于复现
 }
 
 impl Gen {
@@ -14,8 +26,41 @@ impl Gen {
     /// vector, but is and should not be used to control the range of a
     /// randomly generated number. (Unless that number is used to control the
     /// size of a data structure.)
+    ///
+    /// The seed is random, unless the `CORE_UTILS_SEED` environment variable
+    /// is set.
     pub fn new(size: usize) -> Gen {
-        Gen { rng: rand::rngs::SmallRng::from_entropy(), size: size }
+        // 记录种子，失败时可以复现
+        let seed = env::var(SEED_ENV)
+            .ok()
+            .and_then(|seed| seed.trim().parse().ok())
+            .unwrap_or_else(rand::random);
+        Gen::from_seed(seed).with_size(size)
+    }
+
+    /// Returns a `Gen` that always generates the same values for the same
+    /// `seed`, with a size of 100.
+    pub fn from_seed(seed: u64) -> Gen {
+        Gen { rng: Source::new(seed), size: DEFAULT_SIZE, seed }
+    }
+
+    /// Returns a `Gen` that generates the values of `recording` again, then
+    /// continues from its seed once they are exhausted.
+    pub fn replay(recording: Recording) -> Gen {
+        let mut gen = Gen::from_seed(recording.seed);
+        gen.rng.mode = Mode::Replay(recording.values.into_iter());
+        gen
+    }
+
+    /// Change the size configuration.
+    pub fn with_size(mut self, size: usize) -> Gen {
+        self.size = size;
+        self
+    }
+
+    /// Returns the seed of this generator, printed by `forall` on failure.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
     /// Returns the size configured with this generator.
@@ -23,6 +68,23 @@ impl Gen {
         self.size
     }
 
+    /// Start recording the random values drawn from this generator.
+    ///
+    /// Record right after creating the generator so that `Gen::replay`
+    /// continues with the same values once the recording is exhausted.
+    pub fn record(&mut self) {
+        self.rng.mode = Mode::Record(Vec::new());
+    }
+
+    /// Stop recording and return the values drawn since `record`.
+    pub fn take_recording(&mut self) -> Recording {
+        let values = match std::mem::replace(&mut self.rng.mode, Mode::Live) {
+            Mode::Record(values) => values,
+            _ => Vec::new(),
+        };
+        Recording { seed: self.seed, values }
+    }
+
     // 在切片中随机选择一个
     /// Choose among the possible alternatives in the slice given. If the slice
     /// is empty, then `None` is returned. Otherwise, a non-`None` value is
@@ -48,3 +110,120 @@ impl Gen {
         self.rng.gen_range(range)
     }
 }
+
+/// `Gen` can be used wherever a `rand::Rng` is expected, e.g. by the
+/// `random::*_with` functions.
+impl RngCore for Gen {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// The random values drawn from a `Gen`, to replay them with `Gen::replay`.
+///
+/// It is formatted as the seed followed by the values, separated by spaces,
+/// and can be parsed back with `str::parse`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recording {
+    seed: u64,
+    values: Vec<u64>,
+}
+
+impl Recording {
+    /// The seed of the recorded generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The recorded values, in the order they were drawn.
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.seed)?;
+        for value in &self.values {
+            write!(f, " {}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut numbers = s.split_whitespace().map(u64::from_str);
+        // 空字符串按种子 0 处理
+        let seed = numbers.next().transpose()?.unwrap_or_default();
+        let values = numbers.collect::<Result<_, _>>()?;
+        Ok(Recording { seed, values })
+    }
+}
+
+/// 随机数来源，可以录制或者回放
+struct Source {
+    rng: rand::rngs::SmallRng,
+    mode: Mode,
+}
+
+enum Mode {
+    Live,
+    Record(Vec<u64>),
+    Replay(vec::IntoIter<u64>),
+}
+
+impl Source {
+    fn new(seed: u64) -> Source {
+        Source {
+            rng: rand::rngs::SmallRng::seed_from_u64(seed),
+            mode: Mode::Live,
+        }
+    }
+}
+
+impl RngCore for Source {
+    fn next_u32(&mut self) -> u32 {
+        // 统一按 u64 录制
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.mode {
+            Mode::Live => self.rng.next_u64(),
+            Mode::Record(values) => {
+                let value = self.rng.next_u64();
+                values.push(value);
+                value
+            }
+            // 同步推进生成器，回放结束后和录制时的序列一致
+            Mode::Replay(values) => {
+                let value = self.rng.next_u64();
+                values.next().unwrap_or(value)
+            }
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 mod gen;
 mod utils;
 mod arbitrary;
+mod property;
 
-pub use self::gen::Gen;
+pub use self::gen::{Gen, Recording, SEED_ENV};
 pub use self::utils::*;
 pub use self::arbitrary::{Arbitrary, arby};
+pub use self::property::{
+    forall, try_forall, PropertyFailure, TestResult, Testable,
+};
@@ -0,0 +1,312 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::predicates::core::{CaseTreeExt as _, Predicate};
+use crate::random::{Arbitrary, Gen, SEED_ENV};
+
+/// 每个属性默认测试的次数
+const TESTS: usize = 100;
+/// 收缩时最多尝试的次数，避免收缩不结束
+const MAX_SHRINK_ATTEMPTS: usize = 10_000;
+
+/// The outcome of a property for one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestResult {
+    Passed,
+    /// The property does not hold, with the reason.
+    Failed(String),
+    /// The input is not relevant to the property, try another one.
+    Discarded,
+}
+
+impl TestResult {
+    /// Check `variable` with `predicate`, failing with the case tree of the
+    /// predicate.
+    pub fn from_predicate<P, T>(predicate: &P, variable: &T) -> TestResult
+    where
+        P: Predicate<T>,
+        T: ?Sized,
+    {
+        match predicate.find_case(false, variable) {
+            Some(case) => TestResult::Failed(case.tree().to_string()),
+            None => TestResult::Passed,
+        }
+    }
+
+    /// Discard the input unless `condition` holds.
+    pub fn discard_unless(condition: bool) -> TestResult {
+        if condition {
+            TestResult::Passed
+        } else {
+            TestResult::Discarded
+        }
+    }
+}
+
+/// Values a property can return.
+pub trait Testable {
+    fn result(self) -> TestResult;
+}
+
+impl Testable for TestResult {
+    fn result(self) -> TestResult {
+        self
+    }
+}
+
+impl Testable for bool {
+    fn result(self) -> TestResult {
+        if self {
+            TestResult::Passed
+        } else {
+            TestResult::Failed("property returned false".to_owned())
+        }
+    }
+}
+
+impl Testable for () {
+    fn result(self) -> TestResult {
+        TestResult::Passed
+    }
+}
+
+impl<T: Testable, E: fmt::Debug> Testable for Result<T, E> {
+    fn result(self) -> TestResult {
+        match self {
+            Ok(result) => result.result(),
+            Err(err) => TestResult::Failed(format!("{:?}", err)),
+        }
+    }
+}
+
+/// Check that `prop` holds for 100 arbitrary values drawn from `gen`.
+///
+/// On failure the input is shrunk to a minimal failing value, and the panic
+/// message holds the seed of `gen`. Set the `CORE_UTILS_SEED` environment
+/// variable to that seed to replay the same inputs.
+///
+/// ```ignore
+/// forall(Gen::new(100), |xs: Vec<i32>| {
+///     let mut sorted = xs.clone();
+///     sorted.sort();
+///     TestResult::from_predicate(&predicates::is_sorted(), &sorted)
+/// });
+/// ```
+#[track_caller]
+pub fn forall<A, R, F>(gen: Gen, prop: F)
+where
+    A: Arbitrary + fmt::Debug,
+    R: Testable,
+    F: Fn(A) -> R,
+{
+    if let Err(err) = try_forall(gen, TESTS, prop) {
+        panic!("{}", err);
+    }
+}
+
+/// Variant of [`forall`] that runs `tests` inputs and returns a
+/// [`PropertyFailure`], or the number of inputs that passed.
+pub fn try_forall<A, R, F>(
+    mut gen: Gen,
+    tests: usize,
+    prop: F,
+) -> Result<usize, PropertyFailure>
+where
+    A: Arbitrary + fmt::Debug,
+    R: Testable,
+    F: Fn(A) -> R,
+{
+    let mut passed = 0;
+    // 丢弃太多输入时放弃
+    let max_discarded = tests.saturating_mul(10);
+    let mut discarded = 0;
+
+    while passed < tests && discarded < max_discarded {
+        let input = A::arbitrary(&mut gen);
+        match check(&prop, input.clone()) {
+            TestResult::Passed => passed += 1,
+            TestResult::Discarded => discarded += 1,
+            TestResult::Failed(reason) => {
+                let original = format!("{:?}", input);
+                let (minimal, reason, shrinks) = shrink(&prop, input, reason);
+                return Err(PropertyFailure {
+                    seed: gen.seed(),
+                    passed,
+                    original,
+                    minimal: format!("{:?}", minimal),
+                    shrinks,
+                    reason,
+                });
+            }
+        }
+    }
+    Ok(passed)
+}
+
+/// 执行属性，panic 视为失败
+fn check<A, R, F>(prop: &F, input: A) -> TestResult
+where
+    R: Testable,
+    F: Fn(A) -> R,
+{
+    match panic::catch_unwind(AssertUnwindSafe(|| prop(input).result())) {
+        Ok(result) => result,
+        Err(payload) => TestResult::Failed(format!(
+            "panicked: {}",
+            panic_message(&payload)
+        )),
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<unknown>".to_owned()
+    }
+}
+
+/// 反复收缩，直到没有更小的失败输入，返回最小输入、原因和收缩次数
+fn shrink<A, R, F>(prop: &F, input: A, reason: String) -> (A, String, usize)
+where
+    A: Arbitrary,
+    R: Testable,
+    F: Fn(A) -> R,
+{
+    let mut current = (input, reason);
+    let mut shrinks = 0;
+    let mut attempts = 0;
+
+    'outer: while attempts < MAX_SHRINK_ATTEMPTS {
+        for candidate in current.0.shrink() {
+            attempts += 1;
+            if let TestResult::Failed(reason) = check(prop, candidate.clone())
+            {
+                current = (candidate, reason);
+                shrinks += 1;
+                continue 'outer;
+            }
+            if attempts >= MAX_SHRINK_ATTEMPTS {
+                break;
+            }
+        }
+        break;
+    }
+    (current.0, current.1, shrinks)
+}
+
+/// [`try_forall`] error, a property that does not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyFailure {
+    seed: u64,
+    passed: usize,
+    original: String,
+    minimal: String,
+    shrinks: usize,
+    reason: String,
+}
+
+impl PropertyFailure {
+    /// The seed of the generator that found the failure.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The first failing input, formatted with `Debug`.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The shrunk failing input, formatted with `Debug`.
+    pub fn minimal(&self) -> &str {
+        &self.minimal
+    }
+
+    /// Why the property failed for the minimal input.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Error for PropertyFailure {}
+
+impl fmt::Display for PropertyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Property failed after {} passed tests (seed: {}, set {}={} to \
+             replay)",
+            self.passed, self.seed, SEED_ENV, self.seed
+        )?;
+        writeln!(f, "original input: {}", self.original)?;
+        writeln!(
+            f,
+            "minimal input ({} shrinks): {}",
+            self.shrinks, self.minimal
+        )?;
+        write!(f, "{}", self.reason.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::predicates;
+
+    #[test]
+    fn test_forall_passes() {
+        forall(Gen::new(20), |xs: Vec<i32>| {
+            let mut sorted = xs.clone();
+            sorted.sort();
+            TestResult::from_predicate(&predicates::is_sorted(), &sorted)
+        });
+        assert_eq!(try_forall(Gen::new(10), 50, |_: (u8, bool)| true), Ok(50));
+    }
+
+    #[test]
+    fn test_forall_shrinks_to_minimal_input() {
+        let err =
+            try_forall(Gen::new(100), 1000, |x: u32| x < 30).unwrap_err();
+        assert_eq!(err.minimal(), "30");
+        assert_eq!(err.reason(), "property returned false");
+
+        let err =
+            try_forall(Gen::new(20), 1000, |xs: Vec<u8>| !xs.contains(&7))
+                .unwrap_err();
+        assert_eq!(err.minimal(), "[7]");
+    }
+
+    #[test]
+    fn test_forall_predicate_case_tree() {
+        let err = try_forall(Gen::new(20), 1000, |xs: Vec<u8>| {
+            TestResult::from_predicate(
+                &predicates::all(predicates::lt(5)),
+                &xs,
+            )
+        })
+        .unwrap_err();
+        assert_eq!(err.minimal(), "[5]");
+        assert!(err.reason().contains("var < 5"));
+        let message = err.to_string();
+        assert!(message.contains(&format!("seed: {}", err.seed())));
+    }
+
+    #[test]
+    fn test_forall_panics_and_discards() {
+        let err = try_forall(Gen::new(50), 1000, |x: i64| {
+            assert!(x > -10, "too small");
+        })
+        .unwrap_err();
+        assert_eq!(err.minimal(), "-10");
+        assert!(err.reason().contains("too small"));
+
+        let passed = try_forall(Gen::new(10), 10, |x: u8| {
+            TestResult::discard_unless(x > u8::MAX)
+        });
+        assert_eq!(passed, Ok(0));
+    }
+}
@@ -8,7 +8,14 @@ abcdefghijklmnopqrstuvwxyz\
 
 /// 获得指定长度的随机字符串
 pub fn random_string(len: usize) -> String {
-    let mut rng = rand::thread_rng();
+    random_string_with(&mut rand::thread_rng(), len)
+}
+
+/// 使用指定的生成器获得随机字符串，例如 `Gen::from_seed` 可以得到确定的结果
+pub fn random_string_with<R>(rng: &mut R, len: usize) -> String
+where
+    R: Rng + ?Sized,
+{
     let password: String = (0..len)
         .map(|_| {
             let idx = rng.gen_range(0..CHARSET.len());
@@ -48,14 +55,24 @@ pub fn generate_iv_2() -> [u8; 16] {
 
 /// 生成 16 个字节的随机密码
 pub fn get_random_key16() -> [u8; 16] {
+    get_random_key16_with(&mut rand::thread_rng())
+}
+
+/// 使用指定的生成器生成 16 个字节的随机密码
+pub fn get_random_key16_with<R: Rng + ?Sized>(rng: &mut R) -> [u8; 16] {
     let mut arr = [0u8; 16];
-    rand::thread_rng().try_fill(&mut arr[..]).expect("Ooops!");
+    rng.try_fill(&mut arr[..]).expect("Ooops!");
     return arr;
 }
 
 /// 生成 32 个字节的随机密码
 pub fn get_random_key32() -> [u8; 32] {
+    get_random_key32_with(&mut rand::thread_rng())
+}
+
+/// 使用指定的生成器生成 32 个字节的随机密码
+pub fn get_random_key32_with<R: Rng + ?Sized>(rng: &mut R) -> [u8; 32] {
     let mut arr = [0u8; 32];
-    rand::thread_rng().try_fill(&mut arr[..]).expect("Ooops!");
+    rng.try_fill(&mut arr[..]).expect("Ooops!");
     return arr;
 }
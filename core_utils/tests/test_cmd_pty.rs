@@ -0,0 +1,114 @@
+#![cfg(unix)]
+
+use core_utils::cmd::process::Command;
+use predicates::prelude::*;
+use std::time::Duration;
+
+fn sh(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script);
+    cmd
+}
+
+#[test]
+fn test_prompt_exchange() {
+    let mut session = sh(r#"printf "name? "; read name; echo "hello $name""#)
+        .spawn_pty()
+        .unwrap();
+
+    session.expect(predicate::str::contains("name? "));
+    session.send_line("bob").unwrap();
+    let output = session.expect(predicate::str::contains("hello bob"));
+    // 终端会回显输入，并将 \n 转换为 \r\n
+    assert!(output.contains("bob\r\n"), "{:?}", output);
+
+    session.finish().success();
+}
+
+#[test]
+fn test_child_sees_terminal() {
+    let mut session = sh("test -t 0 && test -t 1 && test -t 2 && echo tty")
+        .spawn_pty()
+        .unwrap();
+
+    session.expect(predicate::str::contains("tty"));
+    session.finish().success();
+}
+
+#[test]
+fn test_send_control_interrupts() {
+    let mut session = sh(
+        r#"trap 'echo interrupted; exit 3' INT; echo ready; while true; do sleep 0.1; done"#,
+    )
+    .spawn_pty()
+    .unwrap();
+
+    session.expect(predicate::str::contains("ready"));
+    session.send_control('c').unwrap();
+    session.expect(predicate::str::contains("interrupted"));
+    session.finish().code(3);
+}
+
+#[test]
+fn test_send_control_invalid() {
+    let mut session = sh("true").spawn_pty().unwrap();
+
+    let err = session.send_control('1').unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_expect_timeout_reports_exchanges() {
+    let mut session = sh(r#"printf "password: "; read secret; sleep 5"#)
+        .spawn_pty()
+        .unwrap();
+    session.timeout(Duration::from_millis(300));
+
+    session.expect(predicate::str::contains("password: "));
+    session.send_line("hunter2").unwrap();
+    let err =
+        session.try_expect(predicate::str::contains("welcome")).unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.starts_with("Timed out after 300ms"), "{}", message);
+    // 每次交互都记录在上下文中
+    assert!(message.contains("var.contains(password: )"), "{}", message);
+    assert!(message.contains("\"hunter2\\n\""), "{}", message);
+    assert!(message.contains("var.contains(welcome)"), "{}", message);
+}
+
+#[test]
+fn test_expect_eof() {
+    let mut session = sh("echo bye").spawn_pty().unwrap();
+
+    let err =
+        session.try_expect(predicate::str::contains("never")).unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.starts_with("Unexpected end of output"), "{}", message);
+    assert!(message.contains("bye"), "{}", message);
+}
+
+#[test]
+fn test_write_stdin_is_sent() {
+    let mut session = sh("read line; echo \"got $line\"")
+        .write_stdin("abc\n")
+        .spawn_pty()
+        .unwrap();
+
+    session.expect(predicate::str::contains("got abc"));
+    session.finish().success();
+}
+
+#[test]
+fn test_command_reusable_after_pty() {
+    let mut cmd = sh("if test -t 1; then echo tty; else echo pipe; fi");
+    cmd.env("GREETING", "hi");
+
+    let mut session = cmd.spawn_pty().unwrap();
+    session.expect(predicate::str::contains("tty"));
+    session.finish().success();
+
+    // 之后用管道执行同一个 Command，不受终端设置影响
+    cmd.assert().success().stdout("pipe\n");
+}
@@ -0,0 +1,82 @@
+use core_utils::cmd::assert::{Assert, Redactions};
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::process::{ExitStatus, Output};
+
+#[cfg(unix)]
+fn success() -> ExitStatus {
+    std::os::unix::process::ExitStatusExt::from_raw(0)
+}
+
+#[cfg(windows)]
+fn success() -> ExitStatus {
+    std::os::windows::process::ExitStatusExt::from_raw(0)
+}
+
+fn assert(stdout: &str, stderr: &str) -> Assert {
+    Assert::new(Output {
+        status: success(),
+        stdout: stdout.as_bytes().to_vec(),
+        stderr: stderr.as_bytes().to_vec(),
+    })
+}
+
+#[test]
+fn test_stdout_snapshot() {
+    let temp = env::temp_dir().join("build-1234").join("out.log");
+    let stdout = format!(
+        "started at 2024-03-01T10:20:30.123Z (pid=4242)\nwriting {}\ndone\n",
+        temp.display()
+    );
+
+    assert(&stdout, "").success().stdout_snapshot("greeting");
+}
+
+#[test]
+fn test_custom_redactions() {
+    let redactions = Redactions::none()
+        .add(Regex::new(r"id=\w+").unwrap(), "id=[ID]")
+        .add(Regex::new(r"took \d+ms").unwrap(), "took [DURATION]");
+
+    assert("", "request id=a7f3 took 12ms\n")
+        .redactions(redactions)
+        .stderr_snapshot("request");
+}
+
+#[test]
+fn test_update_and_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshots").join("version.snap");
+
+    let err = assert("v1\n", "")
+        .update_snapshots(false)
+        .try_stdout_snapshot_at(&path)
+        .unwrap_err();
+    assert!(
+        err.to_string().starts_with(&format!(
+            "Missing stdout snapshot {}, run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )),
+        "{}",
+        err
+    );
+
+    assert("v1\n", "").update_snapshots(true).stdout_snapshot_at(&path);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v1\n");
+
+    assert("v1\n", "").stdout_snapshot_at(&path);
+    let err = assert("v2\n", "")
+        .update_snapshots(false)
+        .try_stdout_snapshot_at(&path)
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.starts_with("Unexpected stdout, snapshot"), "{}", message);
+    // diff 带有颜色
+    assert!(message.contains("diff:"), "{}", message);
+    assert!(
+        message.contains("v1\n") && message.contains("v2\n"),
+        "{}",
+        message
+    );
+}
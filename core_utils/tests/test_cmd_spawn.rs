@@ -0,0 +1,137 @@
+#![cfg(unix)]
+
+use core_utils::cmd::process::Command;
+use nix::sys::signal::Signal;
+use predicates::prelude::*;
+use std::time::{Duration, Instant};
+
+fn sh(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script);
+    cmd
+}
+
+#[test]
+fn test_wait_for_stdout() {
+    let script = "echo starting; sleep 0.1; \
+                  echo listening on port 8080; sleep 10";
+    let mut running = sh(script).spawn().unwrap();
+
+    let output =
+        running.wait_for_stdout(predicate::str::contains("listening on"));
+    assert_eq!(output, "starting\nlistening on port 8080\n");
+}
+
+#[test]
+fn test_wait_for_prompt_without_newline() {
+    let mut running =
+        sh("printf 'password: '; read line; echo \"got $line\"; sleep 10")
+            .timeout(Duration::from_secs(5))
+            .write_stdin("secret\n")
+            .spawn()
+            .unwrap();
+
+    let output = running.wait_for_stdout(predicate::str::ends_with(": "));
+    assert_eq!(output, "password: ");
+    running.wait_for_stdout(predicate::str::contains("got secret"));
+}
+
+#[test]
+fn test_spawn_does_not_block_on_stdin() {
+    // 子进程不读取 stdin，写入的内容超过管道缓冲区
+    let start = Instant::now();
+    let mut running = sh("echo ready; sleep 10")
+        .timeout(Duration::from_secs(5))
+        .write_stdin(vec![b'x'; 1 << 20])
+        .spawn()
+        .unwrap();
+
+    running.wait_for_stdout(predicate::str::contains("ready"));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_wait_for_stderr() {
+    let mut running = sh("echo ready >&2; read line; echo \"got $line\" >&2")
+        .write_stdin("ping\n")
+        .spawn()
+        .unwrap();
+
+    running.wait_for_stderr(predicate::str::contains("ready"));
+    running.wait_for_stderr(predicate::str::contains("got ping"));
+    running.finish().success().stdout("");
+}
+
+#[test]
+fn test_read_lines() {
+    let mut running =
+        sh("echo one; echo two >&2; echo three").spawn().unwrap();
+
+    assert_eq!(running.read_stdout_line().unwrap().unwrap(), "one\n");
+    assert_eq!(running.read_stdout_line().unwrap().unwrap(), "three\n");
+    assert_eq!(running.read_stdout_line().unwrap(), None);
+    assert_eq!(running.read_stderr_line().unwrap().unwrap(), "two\n");
+    assert_eq!(running.read_stderr_line().unwrap(), None);
+
+    // 逐行读取不影响最终的输出
+    running.finish().success().stdout("one\nthree\n");
+}
+
+#[test]
+fn test_signal() {
+    let script = "trap 'echo stopping; exit 4' TERM; echo ready; \
+                  while true; do sleep 0.1; done";
+    let mut running = sh(script).spawn().unwrap();
+
+    running.wait_for_stdout(predicate::str::contains("ready"));
+    running.signal(Signal::SIGTERM).unwrap();
+    running.wait_for_stdout(predicate::str::contains("stopping"));
+    running.finish().code(4);
+}
+
+#[test]
+fn test_timeout_reports_partial_output() {
+    let mut running = sh("echo booting; echo warming up >&2; sleep 10")
+        .timeout(Duration::from_millis(300))
+        .spawn()
+        .unwrap();
+
+    let err = running
+        .try_wait_for_stdout(predicate::str::contains("listening"))
+        .unwrap_err();
+
+    let message = err.to_string();
+    assert!(
+        message.starts_with("Timed out after 300ms waiting for stdout"),
+        "{}",
+        message
+    );
+    assert!(message.contains("var: booting"), "{}", message);
+    assert!(message.contains("warming up"), "{}", message);
+}
+
+#[test]
+fn test_unexpected_exit() {
+    let mut running = sh("echo crashed; exit 1").spawn().unwrap();
+
+    let err = running
+        .try_wait_for_stdout(predicate::str::contains("listening"))
+        .unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.starts_with("Unexpected end of stdout"), "{}", message);
+    assert!(message.contains("crashed"), "{}", message);
+}
+
+#[test]
+fn test_kill_on_drop() {
+    let running = sh("sleep 10").spawn().unwrap();
+    let pid = nix::unistd::Pid::from_raw(running.id() as i32);
+
+    let start = Instant::now();
+    drop(running);
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+    // 进程已经退出并被回收
+    assert!(nix::sys::signal::kill(pid, None).is_err());
+}
@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+use core_utils::cmd::testcase::{Status, TestCase, TestCases};
+
+#[test]
+fn test_run_directory() {
+    let steps = TestCases::new().case("tests/cmd").try_run().unwrap();
+    // sandbox.in/Cargo.toml 不是测试用例
+    assert_eq!(steps, 11);
+}
+
+#[test]
+fn test_load_markdown() {
+    let case = TestCase::load("tests/cmd/session.md").unwrap();
+
+    assert_eq!(case.steps.len(), 6);
+    assert!(case.sandbox.is_none());
+    assert_eq!(case.steps[0].location, "tests/cmd/session.md:6");
+    assert_eq!(case.steps[0].bin, "sh");
+    assert_eq!(case.steps[0].args, ["-c", "echo created > out.txt"]);
+    assert_eq!(case.steps[2].env["GREETING"], "hi");
+    assert_eq!(case.steps[3].status, Status::Code(2));
+    assert_eq!(case.steps[3].stdout.as_deref(), Some(""));
+    // stdout 和 stderr 按写入顺序交错
+    assert_eq!(case.steps[5].stdout.as_deref(), Some("one\ntwo\nthree\n"));
+}
+
+#[test]
+fn test_load_toml() {
+    let case = TestCase::load("tests/cmd/sandbox.toml").unwrap();
+
+    assert_eq!(
+        case.sandbox.unwrap(),
+        std::path::Path::new("tests/cmd/sandbox.in")
+    );
+    let step = &case.steps[0];
+    assert_eq!(step.status, Status::Success);
+    assert_eq!(step.stdout.as_deref(), Some("from the sandbox\n"));
+    assert_eq!(step.stderr, None);
+
+    let case = TestCase::load("tests/cmd/status.toml").unwrap();
+    assert_eq!(case.steps[0].args, ["-c", "echo oops >&2; exit 3"]);
+
+    let case = TestCase::load("tests/cmd/signal.toml").unwrap();
+    assert_eq!(case.steps[0].status, Status::Signal(15));
+}
+
+#[test]
+fn test_report_mismatches() {
+    let err =
+        TestCases::new().case("tests/cmd_failing").try_run().unwrap_err();
+
+    let locations: Vec<_> =
+        err.failures().iter().map(|f| f.location.as_str()).collect();
+    assert_eq!(
+        locations,
+        ["tests/cmd_failing/session.md:4", "tests/cmd_failing/wrong.toml"]
+    );
+
+    let wrong = &err.failures()[1];
+    assert_eq!(wrong.reasons.len(), 2);
+    assert_eq!(
+        wrong.reasons[0],
+        "Unexpected status, expected success but got 1"
+    );
+    assert!(wrong.reasons[1].starts_with("Unexpected stdout"), "{}", wrong);
+    assert!(wrong.reasons[1].contains("goodbye"), "{}", wrong);
+
+    let message = err.to_string();
+    assert!(
+        message.starts_with("2 of 3 test case steps failed"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+#[should_panic(expected = "test case steps failed")]
+fn test_run_panics() {
+    TestCases::new().case("tests/cmd_failing/wrong.toml").run();
+}
+
+#[test]
+fn test_register_bin() {
+    let err = TestCases::new()
+        .register_bin("sh", "/nonexistent/sh")
+        .case("tests/cmd/status.toml")
+        .try_run()
+        .unwrap_err();
+
+    assert!(err.failures()[0].reasons[0].starts_with("Failed to spawn"));
+}
+
+#[test]
+fn test_report_signal() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("killed.toml");
+    std::fs::write(
+        &path,
+        "bin = \"sh\"\nargs = \"-c 'kill -TERM $$'\"\nstatus = \"signal 9\"\n",
+    )
+    .unwrap();
+
+    let err = TestCases::new().case(&path).try_run().unwrap_err();
+    assert_eq!(
+        err.failures()[0].reasons,
+        ["Unexpected status, expected signal 9 but got signal 15"]
+    );
+}
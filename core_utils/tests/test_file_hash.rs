@@ -18,3 +18,14 @@ fn test_sha1() {
     let expect = "09b97787f67e6470945da3db502bf12c0012ff5c".to_string();
     assert_eq!(actual, expect);
 }
+
+#[test]
+fn test_hash_reader() {
+    let path = env::current_dir().unwrap().join("tests/data.txt");
+    let data = std::fs::read(&path).unwrap();
+
+    let actual = file::md5_reader(&data[..]).unwrap();
+    assert_eq!(actual, file::md5(&path).unwrap());
+    let actual = file::sha1_reader(&data[..]).unwrap();
+    assert_eq!(actual, file::sha1(&path).unwrap());
+}
@@ -0,0 +1,97 @@
+use core_utils::file::tempdir;
+use core_utils::path::{FileWrite, PathFile};
+use std::fs;
+use std::io::Write;
+
+#[test]
+fn test_atomic_write_new_file() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+
+    let mut f = FileWrite::atomic(&path).unwrap();
+    f.write_str("name = \"a\"\n").unwrap();
+    write!(f, "age = {}\n", 1).unwrap();
+    // 提交之前目标文件不存在
+    assert!(!path.exists());
+    let temp = f.temp_path().to_path_buf();
+    assert_eq!(temp.parent(), Some(dir.path()));
+
+    let file = f.commit().unwrap();
+    assert_eq!(file.read_string().unwrap(), "name = \"a\"\nage = 1\n");
+    assert!(!temp.exists());
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_atomic_write_drop_keeps_target() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "old").unwrap();
+
+    let mut f = FileWrite::atomic(&path).unwrap();
+    f.write_str("half written").unwrap();
+    drop(f);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_atomic_write_preserves_permissions() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("script.sh");
+    fs::write(&path, "old").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+    let before = fs::metadata(&path).unwrap();
+
+    let file = PathFile::new(&path).unwrap();
+    file.write_atomic("new").unwrap();
+
+    let after = fs::metadata(&path).unwrap();
+    assert_eq!(after.permissions().mode() & 0o777, 0o750);
+    assert_eq!((after.uid(), after.gid()), (before.uid(), before.gid()));
+    assert_ne!(after.ino(), before.ino());
+    assert_eq!(file.read_string().unwrap(), "new");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_atomic_write_follows_symlink() {
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target.txt");
+    let link = dir.path().join("link.txt");
+    fs::write(&target, "old").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    let mut f = FileWrite::atomic(&link).unwrap();
+    f.write_str("new").unwrap();
+    f.commit().unwrap();
+
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+}
+
+#[test]
+fn test_write_with_backup() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let backup = dir.path().join("config.toml.bak");
+
+    // 新文件没有旧版本
+    let mut f = FileWrite::atomic(&path).unwrap();
+    f.write_str("v1").unwrap();
+    let file = f.commit_with_backup().unwrap();
+    assert!(!backup.exists());
+
+    file.write_with_backup("v2").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v2");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "v1");
+
+    // 只保留上一个版本
+    file.write_with_backup("v3").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v3");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "v2");
+}
@@ -0,0 +1,151 @@
+use core_utils::path::{
+    Fault, FileSystem, FileWrite, MemFs, Operation, PathDir, PathFile,
+    PathType, Permissions,
+};
+use std::io::{ErrorKind, Read, Write};
+
+#[test]
+fn test_mem_fs_files() {
+    let fs = MemFs::new();
+    let dir = PathDir::create_in("/etc", fs.clone()).unwrap();
+    let file = PathFile::create_in("/etc/app.toml", fs.clone()).unwrap();
+    file.write_str("name = \"a\"\n").unwrap();
+    file.append_str("age = 1\n").unwrap();
+    assert_eq!(file.read_string().unwrap(), "name = \"a\"\nage = 1\n");
+
+    let mut f = file.open_edit().unwrap();
+    let mut s = String::new();
+    f.read_to_string(&mut s).unwrap();
+    f.write_all(b"x").unwrap();
+    assert_eq!(file.read_string().unwrap(), format!("{}x", s));
+
+    let copy = file.copy("/etc/copy.toml").unwrap();
+    let moved = copy.rename("/etc/moved.toml").unwrap();
+    assert_eq!(moved.read_string().unwrap(), file.read_string().unwrap());
+
+    let mut entries =
+        dir.list().unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    entries.sort();
+    assert_eq!(
+        entries,
+        vec![PathType::File(file.clone()), PathType::File(moved.clone())]
+    );
+    // 内存文件系统不会写入磁盘
+    assert!(!std::path::Path::new("/etc/moved.toml").exists());
+    // 查询使用内存文件系统而不是磁盘
+    assert!(moved.exists() && moved.is_file() && !moved.is_dir());
+    assert!(dir.exists() && dir.is_dir() && !dir.is_file());
+
+    let removed = moved.clone();
+    moved.remove().unwrap();
+    assert!(!removed.exists() && !removed.is_file());
+    assert!(fs.metadata("/etc/moved.toml".as_ref()).is_err());
+    let err = dir.clone().remove().unwrap_err();
+    assert_eq!(err.path(), dir.as_path());
+    dir.remove_all().unwrap();
+    assert!(PathFile::new_in("/etc/app.toml", fs).is_err());
+}
+
+#[test]
+fn test_mem_fs_symlinks() {
+    let fs = MemFs::new();
+    let dir = PathDir::create_all_in("/data/real", fs.clone()).unwrap();
+    let file = PathFile::create_in("/data/real/a.txt", fs.clone()).unwrap();
+    file.write_str("hello").unwrap();
+
+    let link_dir = dir.symlink("/data/link").unwrap();
+    let link_file = PathFile::new_in("/data/link/a.txt", fs.clone()).unwrap();
+    assert_eq!(link_file.read_string().unwrap(), "hello");
+    assert_eq!(link_dir.canonicalize().unwrap(), dir);
+    assert_eq!(link_file.canonicalize().unwrap(), file);
+
+    let link = file.symlink("/data/a.link").unwrap();
+    link.write_str("world").unwrap();
+    assert_eq!(file.read_string().unwrap(), "world");
+    assert!(fs.symlink_metadata(link.as_path()).unwrap().is_symlink());
+    assert_eq!(
+        fs.read_link(link.as_path()).unwrap().as_path(),
+        file.as_path()
+    );
+
+    // 删除目标后符号链接悬空
+    file.remove().unwrap();
+    assert!(PathFile::new_in("/data/a.link", fs.clone()).is_err());
+    assert!(fs.symlink_metadata("/data/a.link".as_ref()).is_ok());
+
+    // 符号链接循环
+    fs.symlink_file("/loop/b".as_ref(), "/loop/a".as_ref()).unwrap_err();
+    PathDir::create_in("/loop", fs.clone()).unwrap();
+    fs.symlink_file("/loop/b".as_ref(), "/loop/a".as_ref()).unwrap();
+    fs.symlink_file("/loop/a".as_ref(), "/loop/b".as_ref()).unwrap();
+    let err = PathType::new_in("/loop/a", fs).unwrap_err();
+    #[cfg(unix)]
+    assert_eq!(err.io_error().raw_os_error(), Some(libc::ELOOP));
+    assert_eq!(err.path(), std::path::Path::new("/loop/a"));
+}
+
+#[test]
+fn test_mem_fs_permissions() {
+    let fs = MemFs::new();
+    let dir = PathDir::create_in("/ro", fs.clone()).unwrap();
+    let file = PathFile::create_in("/ro/a.txt", fs.clone()).unwrap();
+    file.write_str("a").unwrap();
+
+    fs.set_permissions(file.as_path(), Permissions::from_mode(0o444)).unwrap();
+    let err = file.write_str("b").unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(err.path(), file.as_path());
+    assert_eq!(file.read_string().unwrap(), "a");
+
+    fs.set_permissions(file.as_path(), Permissions::from_mode(0o200)).unwrap();
+    let err = file.read_string().unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::PermissionDenied);
+
+    // 目录不可写时不能创建和删除文件
+    fs.set_permissions(dir.as_path(), Permissions::from_mode(0o555)).unwrap();
+    let err = PathFile::create_in("/ro/b.txt", fs.clone()).unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::PermissionDenied);
+    let err = file.clone().remove().unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(
+        fs.metadata(file.as_path()).unwrap().permissions().mode(),
+        0o200
+    );
+}
+
+#[test]
+fn test_mem_fs_injected_faults() {
+    let fs = MemFs::new();
+    let dir = PathDir::create_in("/var", fs.clone()).unwrap();
+    let file = PathFile::create_in("/var/log.txt", fs.clone()).unwrap();
+
+    fs.fail(Operation::Write, "/var", Fault::NoSpace);
+    let err = file.write_str("line").unwrap_err();
+    #[cfg(unix)]
+    assert_eq!(err.io_error().raw_os_error(), Some(libc::ENOSPC));
+    assert_eq!(err.io_error().kind(), ErrorKind::StorageFull);
+    assert_eq!(err.path(), file.as_path());
+    assert!(err.to_string().ends_with("when writing /var/log.txt"));
+
+    let mut f = FileWrite::create_in("/var/other.txt", fs.clone()).unwrap();
+    assert!(f.write_all(b"x").is_err());
+    assert!(f.sync_all().is_ok());
+
+    fs.fail(Operation::ReadDir, dir.as_path(), Fault::PermissionDenied);
+    let err = dir.list().err().unwrap();
+    #[cfg(unix)]
+    assert_eq!(err.io_error().raw_os_error(), Some(libc::EACCES));
+    assert_eq!(err.path(), dir.as_path());
+
+    // 故障只影响指定的路径
+    fs.fail(Operation::Open, "/var/log.txt", Fault::PermissionDenied);
+    assert!(file.open_read().is_err());
+    let other = PathDir::create_in("/tmp", fs.clone()).unwrap();
+    assert!(other.list().is_ok());
+    assert!(PathFile::create_in("/tmp/a", fs.clone()).is_ok());
+
+    fs.clear_faults();
+    file.write_str("line").unwrap();
+    assert_eq!(file.read_string().unwrap(), "line");
+    assert_eq!(dir.list().unwrap().count(), 2);
+}
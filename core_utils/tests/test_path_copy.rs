@@ -0,0 +1,264 @@
+use core_utils::file::tempdir;
+use core_utils::path::{
+    Compare, CopyOptions, FileSystem, MemFs, Overwrite, PathDir, PathFile,
+    PathInfo, Symlinks,
+};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// 创建测试用的目录树
+fn tree(fs: &MemFs) -> PathDir<MemFs> {
+    let src = PathDir::create_all_in("/src/sub", fs.clone()).unwrap();
+    let src = src.parent_dir().unwrap();
+    for (path, content) in
+        [("/src/a.txt", "a"), ("/src/b.log", "bb"), ("/src/sub/c.txt", "ccc")]
+    {
+        PathFile::create_in(path, fs.clone())
+            .unwrap()
+            .write_str(content)
+            .unwrap();
+    }
+    fs.symlink_file("a.txt".as_ref(), "/src/link".as_ref()).unwrap();
+    src
+}
+
+fn read(fs: &MemFs, path: &str) -> String {
+    PathFile::new_in(path, fs.clone()).unwrap().read_string().unwrap()
+}
+
+fn exists(fs: &MemFs, path: &str) -> bool {
+    fs.symlink_metadata(path.as_ref()).is_ok()
+}
+
+#[test]
+fn test_copy_to() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+
+    let mut progress = Vec::new();
+    let options = CopyOptions::new().progress(|p| {
+        progress.push((
+            p.path().to_path_buf(),
+            p.files_done(),
+            p.files_total(),
+            p.bytes_done(),
+            p.bytes_total(),
+        ))
+    });
+    let dst = src.copy_to("/dst/copy", options).unwrap();
+    assert_eq!(dst.as_path(), Path::new("/dst/copy"));
+    assert_eq!(read(&fs, "/dst/copy/a.txt"), "a");
+    assert_eq!(read(&fs, "/dst/copy/sub/c.txt"), "ccc");
+    assert_eq!(
+        fs.read_link("/dst/copy/link".as_ref()).unwrap(),
+        PathBuf::from("a.txt")
+    );
+    assert_eq!(read(&fs, "/dst/copy/link"), "a");
+    assert_eq!(read(&fs, "/src/b.log"), "bb");
+    assert_eq!(
+        progress,
+        vec![
+            (PathBuf::from("a.txt"), 1, 4, 1, 6),
+            (PathBuf::from("b.log"), 2, 4, 3, 6),
+            (PathBuf::from("link"), 3, 4, 3, 6),
+            (PathBuf::from("sub/c.txt"), 4, 4, 6, 6),
+        ]
+    );
+
+    // 不能复制到自己里面
+    let err = src.copy_to("/src/sub/copy", CopyOptions::new()).unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.path(), src.as_path());
+}
+
+#[test]
+fn test_copy_to_overwrite() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+    PathDir::create_in("/dst", fs.clone()).unwrap();
+    let existing = PathFile::create_in("/dst/a.txt", fs.clone()).unwrap();
+    existing.write_str("old").unwrap();
+
+    let err = src.copy_to("/dst", CopyOptions::new()).unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::AlreadyExists);
+    assert_eq!(err.path(), existing.as_path());
+    assert_eq!(existing.read_string().unwrap(), "old");
+
+    let mut skipped = Vec::new();
+    let options = CopyOptions::new()
+        .overwrite(Overwrite::Skip)
+        .progress(|p| skipped.push(p.skipped()));
+    src.copy_to("/dst", options).unwrap();
+    assert_eq!(existing.read_string().unwrap(), "old");
+    assert_eq!(read(&fs, "/dst/sub/c.txt"), "ccc");
+    assert_eq!(skipped, vec![true, false, false, false]);
+
+    let options = CopyOptions::new().overwrite(Overwrite::Always);
+    src.copy_to("/dst", options).unwrap();
+    assert_eq!(existing.read_string().unwrap(), "a");
+}
+
+#[test]
+fn test_copy_to_globs() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+    PathDir::create_in("/src/empty", fs.clone()).unwrap();
+
+    let options = CopyOptions::new().include("*.txt").exclude("sub/**");
+    src.copy_to("/txt", options).unwrap();
+    assert!(exists(&fs, "/txt/a.txt"));
+    assert!(!exists(&fs, "/txt/b.log"));
+    assert!(!exists(&fs, "/txt/link"));
+    assert!(!exists(&fs, "/txt/sub"));
+    // 只创建有文件的目录
+    assert!(!exists(&fs, "/txt/empty"));
+
+    let options = CopyOptions::new().exclude("*.log");
+    src.copy_to("/all", options).unwrap();
+    assert!(!exists(&fs, "/all/b.log"));
+    assert!(exists(&fs, "/all/empty"));
+    assert!(exists(&fs, "/all/sub/c.txt"));
+}
+
+#[test]
+fn test_copy_to_symlinks() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+    fs.symlink_dir("sub".as_ref(), "/src/dir".as_ref()).unwrap();
+
+    let options = CopyOptions::new().symlinks(Symlinks::Skip);
+    src.copy_to("/skip", options).unwrap();
+    assert!(!exists(&fs, "/skip/link"));
+    assert!(!exists(&fs, "/skip/dir"));
+
+    let options = CopyOptions::new().symlinks(Symlinks::Follow);
+    src.copy_to("/follow", options).unwrap();
+    let metadata = fs.symlink_metadata("/follow/link".as_ref()).unwrap();
+    assert!(metadata.is_file());
+    assert_eq!(read(&fs, "/follow/link"), "a");
+    let metadata = fs.symlink_metadata("/follow/dir".as_ref()).unwrap();
+    assert!(metadata.is_dir());
+    assert_eq!(read(&fs, "/follow/dir/c.txt"), "ccc");
+
+    // 指向上级目录的符号链接
+    fs.symlink_dir("..".as_ref(), "/src/sub/up".as_ref()).unwrap();
+    let options = CopyOptions::new().symlinks(Symlinks::Follow);
+    let err = src.copy_to("/loop", options).unwrap_err();
+    assert_eq!(err.io_error().kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.path(), Path::new("/src/dir/up"));
+}
+
+/// 同步到 /dst，返回复制了的文件
+fn synced(
+    src: &PathDir<MemFs>,
+    options: CopyOptions<'static>,
+) -> Vec<PathBuf> {
+    let mut copied = Vec::new();
+    let options = options.progress(|p| {
+        if !p.skipped() {
+            copied.push(p.path().to_path_buf());
+        }
+    });
+    src.sync_to("/dst", options).unwrap();
+    copied
+}
+
+#[test]
+fn test_sync_to() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+    src.copy_to("/dst", CopyOptions::new()).unwrap();
+
+    let copied = |options| synced(&src, options);
+    assert!(copied(CopyOptions::new()).is_empty());
+
+    PathFile::new_in("/src/a.txt", fs.clone())
+        .unwrap()
+        .write_str("x")
+        .unwrap();
+    fs.remove_file("/dst/sub/c.txt".as_ref()).unwrap();
+    fs.remove_file("/dst/link".as_ref()).unwrap();
+    fs.symlink_file("b.log".as_ref(), "/dst/link".as_ref()).unwrap();
+    PathFile::create_in("/dst/extra", fs.clone()).unwrap();
+    assert_eq!(
+        copied(CopyOptions::new()),
+        vec![
+            PathBuf::from("a.txt"),
+            PathBuf::from("link"),
+            PathBuf::from("sub/c.txt"),
+        ]
+    );
+    assert_eq!(read(&fs, "/dst/a.txt"), "x");
+    assert_eq!(read(&fs, "/dst/link"), "x");
+    assert!(exists(&fs, "/dst/extra"));
+
+    // 只比较内容时忽略修改时间
+    PathFile::new_in("/dst/b.log", fs.clone())
+        .unwrap()
+        .write_str("bb")
+        .unwrap();
+    assert!(copied(CopyOptions::new().compare(Compare::Hash)).is_empty());
+    PathFile::new_in("/dst/b.log", fs.clone())
+        .unwrap()
+        .write_str("xx")
+        .unwrap();
+    assert_eq!(
+        copied(CopyOptions::new().compare(Compare::Hash)),
+        vec![PathBuf::from("b.log")]
+    );
+    assert_eq!(read(&fs, "/dst/b.log"), "bb");
+}
+
+#[test]
+fn test_move_to() {
+    let fs = MemFs::new();
+    let src = tree(&fs);
+    let dst = src.move_to("/moved/src", CopyOptions::new()).unwrap();
+    assert!(!exists(&fs, "/src"));
+    assert_eq!(read(&fs, "/moved/src/sub/c.txt"), "ccc");
+
+    // 过滤后只移动选中的文件
+    let options = CopyOptions::new().exclude("sub/c.txt");
+    let dst = dst.move_to("/other", options).unwrap();
+    assert_eq!(read(&fs, "/other/a.txt"), "a");
+    assert!(exists(&fs, "/other/link"));
+    assert!(!exists(&fs, "/other/sub/c.txt"));
+    assert!(exists(&fs, "/moved/src/sub/c.txt"));
+    assert!(!exists(&fs, "/moved/src/a.txt"));
+    assert!(!exists(&fs, "/moved/src/link"));
+
+    let options = CopyOptions::new().overwrite(Overwrite::Skip);
+    let src = PathDir::new_in("/moved/src", fs.clone()).unwrap();
+    src.move_to(dst.as_path(), options).unwrap();
+    assert!(!exists(&fs, "/moved/src"));
+    assert_eq!(read(&fs, "/other/sub/c.txt"), "ccc");
+}
+
+#[test]
+fn test_copy_to_os() {
+    let dir = tempdir().unwrap();
+    let src = PathDir::create(dir.path().join("src")).unwrap();
+    let file = PathFile::create(src.as_path().join("a.txt")).unwrap();
+    file.write_str("a").unwrap();
+
+    let dst = src.copy_to(dir.path().join("dst"), CopyOptions::new()).unwrap();
+    let copy = PathFile::new(dst.as_path().join("a.txt")).unwrap();
+    assert_eq!(copy.read_string().unwrap(), "a");
+    assert_eq!(
+        copy.metadata().unwrap().modified().unwrap(),
+        file.metadata().unwrap().modified().unwrap()
+    );
+
+    let mut copied = 0;
+    let options = CopyOptions::new().progress(|p| {
+        if !p.skipped() {
+            copied += 1;
+        }
+    });
+    src.sync_to(dst.as_path(), options).unwrap();
+    assert_eq!(copied, 0);
+
+    let moved = src.move_to(dir.path().join("moved"), CopyOptions::new());
+    assert!(moved.unwrap().as_path().join("a.txt").exists());
+    assert!(!dir.path().join("src").exists());
+}
@@ -0,0 +1,225 @@
+use core_utils::file::tempdir;
+use core_utils::path::{
+    Fault, FileSystem, MemFs, Operation, PathDir, PathFile, PathType, Walk,
+};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 创建测试用的目录树
+fn tree(fs: &MemFs) -> PathDir<MemFs> {
+    for dir in ["/root/src/bin", "/root/target/debug", "/root/.git"] {
+        PathDir::create_all_in(dir, fs.clone()).unwrap();
+    }
+    for (path, content) in [
+        ("/root/.gitignore", "target/\n*.log\n"),
+        ("/root/.git/HEAD", ""),
+        ("/root/README.md", ""),
+        ("/root/build.log", ""),
+        ("/root/src/lib.rs", ""),
+        ("/root/src/gen.rs", ""),
+        ("/root/src/.ignore", "gen.rs\n!keep.log\n"),
+        ("/root/src/keep.log", ""),
+        ("/root/src/bin/main.rs", ""),
+        ("/root/target/debug/app", ""),
+    ] {
+        PathFile::create_in(path, fs.clone())
+            .unwrap()
+            .write_str(content)
+            .unwrap();
+    }
+    PathDir::new_in("/root", fs.clone()).unwrap()
+}
+
+/// 相对路径，目录以 / 结尾
+fn names<I>(root: &PathDir<MemFs>, entries: I) -> Vec<String>
+where
+    I: IntoIterator<Item = core_utils::path::Result<PathType<MemFs>>>,
+{
+    entries
+        .into_iter()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let path: &Path = entry.as_ref();
+            let name = path.strip_prefix(root).unwrap().display().to_string();
+            if entry.is_dir() {
+                name + "/"
+            } else {
+                name
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_walk() {
+    let fs = MemFs::new();
+    let root = tree(&fs);
+
+    assert_eq!(
+        names(&root, root.walk()),
+        vec![
+            ".git/",
+            ".git/HEAD",
+            ".gitignore",
+            "README.md",
+            "build.log",
+            "src/",
+            "src/.ignore",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/gen.rs",
+            "src/keep.log",
+            "src/lib.rs",
+            "target/",
+            "target/debug/",
+            "target/debug/app",
+        ]
+    );
+    assert_eq!(
+        names(&root, root.walk().max_depth(1)),
+        vec![
+            ".git/",
+            ".gitignore",
+            "README.md",
+            "build.log",
+            "src/",
+            "target/"
+        ]
+    );
+    assert!(names(&root, root.walk().max_depth(0)).is_empty());
+    assert!(names(&root, root.walk().min_depth(3).max_depth(2)).is_empty());
+    assert_eq!(
+        names(&root, root.walk().min_depth(2).max_depth(2)),
+        vec![
+            ".git/HEAD",
+            "src/.ignore",
+            "src/bin/",
+            "src/gen.rs",
+            "src/keep.log",
+            "src/lib.rs",
+            "target/debug/",
+        ]
+    );
+}
+
+#[test]
+fn test_walk_filters() {
+    let fs = MemFs::new();
+    let root = tree(&fs);
+
+    assert_eq!(
+        names(&root, root.walk().gitignore(true).skip_hidden(true)),
+        vec![
+            "README.md",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/keep.log",
+            "src/lib.rs",
+        ]
+    );
+    assert_eq!(
+        names(&root, root.walk().include("*.rs").exclude("src/bin")),
+        vec!["src/gen.rs", "src/lib.rs"]
+    );
+    assert_eq!(
+        names(&root, root.walk().skip_hidden(true).exclude("target")),
+        vec![
+            "README.md",
+            "build.log",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/gen.rs",
+            "src/keep.log",
+            "src/lib.rs",
+        ]
+    );
+}
+
+#[test]
+fn test_walk_symlinks() {
+    let fs = MemFs::new();
+    let root = tree(&fs);
+    fs.symlink_dir("src/bin".as_ref(), "/root/bin".as_ref()).unwrap();
+    let walk = || root.walk().skip_hidden(true).exclude("{src,target}");
+
+    assert_eq!(names(&root, walk()), vec!["README.md", "bin/", "build.log"]);
+    assert_eq!(
+        names(&root, walk().follow_links(true)),
+        vec!["README.md", "bin/", "bin/main.rs", "build.log"]
+    );
+
+    fs.symlink_dir("../..".as_ref(), "/root/src/bin/up".as_ref()).unwrap();
+    let entries = walk().follow_links(true).into_iter().collect::<Vec<_>>();
+    let err = entries.iter().find_map(|entry| entry.as_ref().err()).unwrap();
+    assert_eq!(err.io_error().kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.path(), Path::new("/root/bin/up"));
+}
+
+#[test]
+fn test_walk_errors() {
+    let fs = MemFs::new();
+    let root = tree(&fs);
+    fs.fail(Operation::ReadDir, "/root/src", Fault::PermissionDenied);
+    fs.symlink_file("missing".as_ref(), "/root/dangling".as_ref()).unwrap();
+
+    let errors = |walk: Walk<MemFs>| {
+        walk.into_iter()
+            .filter_map(|entry| entry.err())
+            .map(|err| (err.path().to_path_buf(), err.action().to_string()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        errors(root.walk().skip_hidden(true)),
+        vec![(PathBuf::from("/root/src"), "reading dir".into())]
+    );
+    assert_eq!(
+        errors(root.walk().skip_hidden(true).follow_links(true)),
+        vec![
+            (PathBuf::from("/root/dangling"), "getting metadata of".into()),
+            (PathBuf::from("/root/src"), "reading dir".into()),
+        ]
+    );
+
+    // 不跟随符号链接时返回断开的链接，出错后继续遍历
+    let walk = root.walk().skip_hidden(true).exclude("src");
+    assert!(names(&root, walk).contains(&"dangling".to_owned()));
+    let count = root.walk().skip_hidden(true).into_iter().count();
+    assert_eq!(count, 8);
+}
+
+#[test]
+fn test_walk_parallel() {
+    let fs = MemFs::new();
+    let root = tree(&fs);
+
+    let entries = Mutex::new(Vec::new());
+    root.walk().gitignore(true).par_for_each(|entry| {
+        entries.lock().unwrap().push(entry);
+    });
+    let mut entries = names(&root, entries.into_inner().unwrap());
+    entries.sort();
+    let mut expected = names(&root, root.walk().gitignore(true));
+    expected.sort();
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn test_walk_os() {
+    let dir = tempdir().unwrap();
+    let root = PathDir::create_all(dir.path().join("a/b")).unwrap();
+    PathFile::create(root.as_path().join("c.txt")).unwrap();
+    let root = PathDir::new(dir.path()).unwrap();
+
+    let files = root
+        .walk()
+        .into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(PathType::is_file)
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 1);
+    let path: &Path = files[0].as_ref();
+    assert!(path.ends_with("a/b/c.txt"));
+}
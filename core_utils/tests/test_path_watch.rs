@@ -0,0 +1,227 @@
+use core_utils::file::tempdir;
+use core_utils::path::{Event, PathAbs, PathDir, PathFile, Watcher};
+use futures_core::Stream;
+use std::fs;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// 收集事件，直到一段时间没有新的事件
+fn events(watcher: &Watcher) -> Vec<Event> {
+    let mut events = Vec::new();
+    while let Some(event) = watcher.recv_timeout(Duration::from_millis(300)) {
+        events.push(event.unwrap());
+    }
+    events
+}
+
+fn abs(dir: &PathDir, name: &str) -> PathAbs {
+    PathAbs::new_unchecked(dir.as_path().join(name))
+}
+
+fn write(dir: &PathDir, name: &str, content: &str) {
+    fs::write(dir.as_path().join(name), content).unwrap();
+}
+
+#[test]
+fn test_watch() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    let watcher =
+        dir.watch().debounce(Duration::from_millis(20)).start().unwrap();
+    assert!(!watcher.is_polling());
+
+    // 新建后马上写入，合并成一个事件
+    write(&dir, "a.txt", "a");
+    write(&dir, "a.txt", "aa");
+    assert_eq!(events(&watcher), vec![Event::Create(abs(&dir, "a.txt"))]);
+
+    write(&dir, "a.txt", "b");
+    assert_eq!(events(&watcher), vec![Event::Modify(abs(&dir, "a.txt"))]);
+
+    fs::rename(tmp.path().join("a.txt"), tmp.path().join("b.txt")).unwrap();
+    assert_eq!(
+        events(&watcher),
+        vec![Event::Rename(abs(&dir, "a.txt"), abs(&dir, "b.txt"))]
+    );
+
+    fs::remove_file(tmp.path().join("b.txt")).unwrap();
+    assert_eq!(events(&watcher), vec![Event::Remove(abs(&dir, "b.txt"))]);
+
+    // 新建后马上删除，什么也没有发生
+    write(&dir, "c.txt", "c");
+    fs::remove_file(tmp.path().join("c.txt")).unwrap();
+    assert!(events(&watcher).is_empty());
+}
+
+#[test]
+fn test_watch_recursive() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    PathDir::create(tmp.path().join("old")).unwrap();
+    let watcher =
+        dir.watch().debounce(Duration::from_millis(20)).start().unwrap();
+
+    write(&dir, "old/a.txt", "a");
+    PathDir::create_all(tmp.path().join("new/sub")).unwrap();
+    write(&dir, "new/sub/b.txt", "b");
+    let mut paths = events(&watcher)
+        .into_iter()
+        .map(|event| match event {
+            Event::Create(path) => path,
+            event => panic!("unexpected {:?}", event),
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            abs(&dir, "new"),
+            abs(&dir, "new/sub"),
+            abs(&dir, "new/sub/b.txt"),
+            abs(&dir, "old/a.txt"),
+        ]
+    );
+
+    // 改名后的目录还在监视
+    fs::rename(tmp.path().join("new"), tmp.path().join("renamed")).unwrap();
+    events(&watcher);
+    write(&dir, "renamed/sub/b.txt", "bb");
+    assert_eq!(
+        events(&watcher),
+        vec![Event::Modify(abs(&dir, "renamed/sub/b.txt"))]
+    );
+
+    let watcher = dir.watch().recursive(false).start().unwrap();
+    write(&dir, "old/c.txt", "c");
+    write(&dir, "d.txt", "d");
+    assert_eq!(events(&watcher), vec![Event::Create(abs(&dir, "d.txt"))]);
+}
+
+#[test]
+fn test_watch_globs() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    PathDir::create(tmp.path().join("target")).unwrap();
+    let watcher = dir
+        .watch()
+        .include("*.toml")
+        .exclude("target")
+        .debounce(Duration::from_millis(20))
+        .start()
+        .unwrap();
+
+    write(&dir, "target/a.toml", "a");
+    write(&dir, "b.txt", "b");
+    write(&dir, "c.toml", "c");
+    assert_eq!(events(&watcher), vec![Event::Create(abs(&dir, "c.toml"))]);
+
+    // 改名时任意一个路径匹配就报告
+    fs::rename(tmp.path().join("b.txt"), tmp.path().join("b.toml")).unwrap();
+    assert_eq!(
+        events(&watcher),
+        vec![Event::Rename(abs(&dir, "b.txt"), abs(&dir, "b.toml"))]
+    );
+}
+
+#[test]
+fn test_watch_polling() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    let file = PathFile::create(tmp.path().join("a.txt")).unwrap();
+    let watcher = dir
+        .watch()
+        .polling(true)
+        .poll_interval(Duration::from_millis(20))
+        .debounce(Duration::from_millis(20))
+        .start()
+        .unwrap();
+    assert!(watcher.is_polling());
+
+    file.write_str("changed").unwrap();
+    write(&dir, "sub.txt", "b");
+    assert_eq!(
+        events(&watcher),
+        vec![
+            Event::Modify(abs(&dir, "a.txt")),
+            Event::Create(abs(&dir, "sub.txt")),
+        ]
+    );
+
+    // 轮询看不出改名
+    fs::rename(tmp.path().join("a.txt"), tmp.path().join("b.txt")).unwrap();
+    let mut events = events(&watcher);
+    events.sort_by_key(|event| event.path().clone());
+    assert_eq!(
+        events,
+        vec![
+            Event::Remove(abs(&dir, "a.txt")),
+            Event::Create(abs(&dir, "b.txt")),
+        ]
+    );
+}
+
+/// 唤醒时继续运行当前线程
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_watch_async() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    let mut watcher =
+        dir.watch().debounce(Duration::from_millis(20)).start().unwrap();
+
+    let writer = {
+        let dir = dir.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            write(&dir, "a.txt", "a");
+        })
+    };
+    let event = block_on(watcher.next_event()).unwrap().unwrap();
+    assert_eq!(event, Event::Create(abs(&dir, "a.txt")));
+    assert!(watcher.try_recv().is_none());
+    writer.join().unwrap();
+}
+
+#[test]
+fn test_watch_stream() {
+    let tmp = tempdir().unwrap();
+    let dir = PathDir::new(tmp.path()).unwrap();
+    let mut watcher =
+        dir.watch().debounce(Duration::from_millis(20)).start().unwrap();
+
+    let writer = {
+        let dir = dir.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            write(&dir, "a.txt", "a");
+        })
+    };
+    let event = block_on(std::future::poll_fn(|cx| {
+        Pin::new(&mut watcher).poll_next(cx)
+    }));
+    assert_eq!(event.unwrap().unwrap(), Event::Create(abs(&dir, "a.txt")));
+    writer.join().unwrap();
+}
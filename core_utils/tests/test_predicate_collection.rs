@@ -0,0 +1,120 @@
+use core_utils::predicates::{
+    self,
+    core::{Case, CaseTreeExt, Predicate},
+};
+use std::collections::{BTreeSet, VecDeque};
+
+/// 获得 case 中的 product
+fn product(case: &Case, name: &str) -> Option<String> {
+    case.products()
+        .find(|product| product.name() == name)
+        .map(|product| product.value().to_string())
+}
+
+#[test]
+fn test_predicate_all() {
+    let predicate_fn = predicates::all(predicates::gt(0));
+    assert!(predicate_fn.eval(&vec![1, 2, 3]));
+    assert!(predicate_fn.eval(&[1, 2, 3][..]));
+    assert!(predicate_fn.eval(&VecDeque::from([4, 5])));
+    assert!(predicate_fn.eval(&Vec::<i32>::new()));
+    assert!(!predicate_fn.eval(&vec![1, 2, 3, 0, -1]));
+
+    let case = predicate_fn.find_case(false, &vec![1, 2, 3, 0, -1]).unwrap();
+    assert_eq!(product(&case, "index").as_deref(), Some("3"));
+    assert_eq!(product(&case, "var").as_deref(), Some("0"));
+    assert_eq!(case.children().count(), 1);
+    assert!(case.tree().to_string().contains("var > 0"));
+    assert!(predicate_fn.find_case(true, &vec![0]).is_none());
+}
+
+#[test]
+fn test_predicate_any() {
+    let predicate_fn = predicates::any(predicates::eq("b"));
+    assert!(predicate_fn.eval(&["a", "b", "c"]));
+    assert!(!predicate_fn.eval(&["a", "c"]));
+    assert!(!predicate_fn.eval(&Vec::<&str>::new()));
+
+    let case = predicate_fn.find_case(true, &["a", "b", "c"]).unwrap();
+    assert_eq!(product(&case, "index").as_deref(), Some("1"));
+    assert!(predicate_fn.find_case(false, &["a", "c"]).is_some());
+    assert!(predicate_fn.find_case(false, &["b"]).is_none());
+}
+
+#[test]
+fn test_predicate_len() {
+    let predicate_fn = predicates::len(predicates::eq(3));
+    assert!(predicate_fn.eval(&vec!['a', 'b', 'c']));
+    assert!(predicate_fn.eval(&BTreeSet::from([1, 2, 3])));
+    assert!(!predicate_fn.eval(&vec!['a']));
+
+    let case = predicate_fn.find_case(false, &vec!['a']).unwrap();
+    assert_eq!(product(&case, "len").as_deref(), Some("1"));
+    assert_eq!(case.children().count(), 1);
+    assert_eq!(format!("{}", predicate_fn), "var.len(var == 3)");
+}
+
+#[test]
+fn test_predicate_contains_in_order() {
+    let predicate_fn = predicates::contains_in_order(vec![1, 3, 5]);
+    assert!(predicate_fn.eval(&vec![1, 2, 3, 4, 5]));
+    assert!(predicate_fn.eval(&vec![1, 3, 5]));
+    assert!(!predicate_fn.eval(&vec![1, 5, 3]));
+    assert!(!predicate_fn.eval(&vec![3, 5]));
+
+    let case = predicate_fn.find_case(false, &vec![1, 5, 3]).unwrap();
+    assert_eq!(product(&case, "missing").as_deref(), Some("5"));
+    assert_eq!(product(&case, "index").as_deref(), Some("2"));
+    assert_eq!(product(&case, "after").as_deref(), Some("2"));
+
+    let case = predicate_fn.find_case(false, &vec![3, 5]).unwrap();
+    assert_eq!(product(&case, "missing").as_deref(), Some("1"));
+    assert_eq!(product(&case, "after"), None);
+}
+
+#[test]
+fn test_predicate_is_sorted() {
+    let predicate_fn = predicates::is_sorted();
+    assert!(predicate_fn.eval(&vec![1, 1, 2, 3]));
+    assert!(predicate_fn.eval(&Vec::<i32>::new()));
+    assert!(!predicate_fn.eval(&vec![1, 3, 2]));
+    assert!(!predicate_fn.eval(&vec![1.0, f64::NAN]));
+    assert!(predicate_fn.descending().eval(&vec![3, 2, 2, 1]));
+    assert!(!predicate_fn.descending().eval(&vec![3, 4]));
+
+    let case = predicate_fn.find_case(false, &vec![1, 3, 2]).unwrap();
+    assert_eq!(product(&case, "index").as_deref(), Some("2"));
+    assert_eq!(product(&case, "previous").as_deref(), Some("3"));
+    assert_eq!(product(&case, "var").as_deref(), Some("2"));
+}
+
+#[test]
+fn test_predicate_unique() {
+    let predicate_fn = predicates::unique();
+    assert!(predicate_fn.eval(&vec!["a", "b", "c"]));
+    assert!(!predicate_fn.eval(&vec!["a", "b", "c", "b"]));
+
+    let case =
+        predicate_fn.find_case(false, &vec!["a", "b", "c", "b"]).unwrap();
+    assert_eq!(product(&case, "index").as_deref(), Some("3"));
+    assert_eq!(product(&case, "first").as_deref(), Some("1"));
+    assert_eq!(product(&case, "var").as_deref(), Some("\"b\""));
+}
+
+#[test]
+fn test_predicate_between() {
+    let predicate_fn = predicates::between(1, 5);
+    assert!(predicate_fn.eval(&1));
+    assert!(predicate_fn.eval(&5));
+    assert!(!predicate_fn.eval(&0));
+    assert!(!predicate_fn.eval(&6));
+
+    let case = predicate_fn.find_case(false, &0).unwrap();
+    assert_eq!(product(&case, "reason").as_deref(), Some("below the range"));
+    let case = predicate_fn.find_case(false, &6).unwrap();
+    assert_eq!(product(&case, "reason").as_deref(), Some("above the range"));
+    assert_eq!(format!("{}", predicate_fn), "var in 1..=5");
+
+    let predicate_fn = predicates::all(predicates::between(0.0, 1.0));
+    assert!(predicate_fn.eval(&[0.0, 0.5, 1.0]));
+}
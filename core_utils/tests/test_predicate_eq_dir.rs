@@ -0,0 +1,142 @@
+use core_utils::file::{tempdir, TempDir};
+use core_utils::predicates::{
+    self,
+    core::{Case, Predicate, PredicateReflection},
+};
+use std::fs;
+
+/// 获得 case 中所有名称为 name 的 product
+fn products(case: &Case, name: &str) -> Vec<String> {
+    case.products()
+        .filter(|product| product.name() == name)
+        .map(|product| product.value().to_string())
+        .collect()
+}
+
+/// 根据 (相对路径, 内容) 创建目录树，内容为 None 时创建目录
+fn tree(entries: &[(&str, Option<&str>)]) -> TempDir {
+    let dir = tempdir().unwrap();
+    for (path, content) in entries {
+        let path = dir.path().join(path);
+        match content {
+            Some(content) => {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+            None => fs::create_dir_all(path).unwrap(),
+        }
+    }
+    dir
+}
+
+#[test]
+fn test_eq_dir_same_tree() {
+    let entries = [("a.txt", Some("a\n")), ("sub/b.txt", Some("b\n"))];
+    let expected = tree(&entries);
+    let actual = tree(&entries);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    assert!(predicate_fn.eval(actual.path()));
+    assert!(predicate_fn.find_case(false, actual.path()).is_none());
+}
+
+#[test]
+fn test_eq_dir_reports_added_and_missing() {
+    let expected =
+        tree(&[("a.txt", Some("a\n")), ("gone/x.txt", Some("x\n"))]);
+    let actual = tree(&[
+        ("a.txt", Some("a\n")),
+        ("new.txt", Some("n\n")),
+        ("extra/y.txt", Some("y\n")),
+    ]);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    assert!(!predicate_fn.eval(actual.path()));
+    let case = predicate_fn.find_case(false, actual.path()).unwrap();
+    // 目录中的文件不再单独报告
+    assert_eq!(products(&case, "missing"), ["gone"]);
+    assert_eq!(products(&case, "added"), ["extra", "new.txt"]);
+    assert!(products(&case, "changed").is_empty());
+}
+
+#[test]
+fn test_eq_dir_reports_changed_content() {
+    let expected = tree(&[("a.txt", Some("one\ntwo\n"))]);
+    let actual = tree(&[("a.txt", Some("one\nthree\n"))]);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    let case = predicate_fn.find_case(false, actual.path()).unwrap();
+    let changed = products(&case, "changed");
+    assert_eq!(changed.len(), 1);
+    assert!(changed[0].starts_with("a.txt\n"));
+    assert!(changed[0].contains("two"));
+    assert!(changed[0].contains("three"));
+}
+
+#[test]
+fn test_eq_dir_reports_type_change() {
+    let expected = tree(&[("item", Some("file"))]);
+    let actual = tree(&[("item", None)]);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    let case = predicate_fn.find_case(false, actual.path()).unwrap();
+    assert_eq!(products(&case, "changed"), ["item\nexpected File, got Dir"]);
+}
+
+#[test]
+fn test_eq_dir_binary_content() {
+    let expected = tempdir().unwrap();
+    let actual = tempdir().unwrap();
+    fs::write(expected.path().join("data.bin"), [0xff, 0x00, 0x01]).unwrap();
+    fs::write(actual.path().join("data.bin"), [0xff, 0x00]).unwrap();
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    let case = predicate_fn.find_case(false, actual.path()).unwrap();
+    assert_eq!(
+        products(&case, "changed"),
+        ["data.bin\nbinary content differs (3 bytes, got 2 bytes)"]
+    );
+}
+
+#[test]
+fn test_eq_dir_ignore() {
+    let expected = tree(&[("a.txt", Some("a\n")), ("a.log", Some("1\n"))]);
+    let actual = tree(&[
+        ("a.txt", Some("a\n")),
+        ("b.log", Some("2\n")),
+        ("target/debug/out", Some("bin")),
+    ]);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    assert!(!predicate_fn.eval(actual.path()));
+
+    let predicate_fn = predicate_fn.ignore("*.log").ignore("target");
+    assert!(predicate_fn.eval(actual.path()));
+    assert!(predicate_fn.parameters().any(|p| p.name() == "ignore"));
+}
+
+#[test]
+fn test_eq_dir_normalize_newlines() {
+    let expected = tree(&[("a.txt", Some("one\ntwo\n"))]);
+    let actual = tree(&[("a.txt", Some("one\r\ntwo\r\n"))]);
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    assert!(!predicate_fn.eval(actual.path()));
+    assert!(predicate_fn.normalize_newlines().eval(actual.path()));
+}
+
+#[test]
+fn test_eq_dir_missing_root() {
+    let expected = tree(&[("a.txt", Some("a\n"))]);
+    let actual = expected.path().join("does-not-exist");
+
+    let predicate_fn = predicates::path::eq_dir(expected.path());
+    let case = predicate_fn.find_case(false, &actual).unwrap();
+    assert_eq!(products(&case, "error").len(), 1);
+}
+
+#[test]
+fn test_eq_dir_display() {
+    let predicate_fn = predicates::path::eq_dir("expected");
+    assert_eq!(format!("{}", predicate_fn), "var eq_dir expected");
+}
@@ -0,0 +1,132 @@
+use core_utils::predicates::{
+    self,
+    core::{Case, Predicate},
+};
+use serde_json::json;
+
+/// 获得 case 中的 product
+fn product(case: &Case, name: &str) -> Option<String> {
+    case.products()
+        .find(|product| product.name() == name)
+        .map(|product| product.value().to_string())
+}
+
+#[test]
+fn test_json_ignores_key_order_and_formatting() {
+    let predicate_fn =
+        predicates::str::json(json!({"a": 1, "b": [true, null]}));
+
+    assert!(predicate_fn.eval(r#"{"b":[true,null],"a":1}"#));
+    assert!(predicate_fn.eval("{\n  \"a\": 1.0,\n  \"b\": [true, null]\n}"));
+    assert!(!predicate_fn.eval(r#"{"a":1,"b":[true]}"#));
+    assert!(predicate_fn
+        .find_case(false, r#"{"a":1,"b":[true,null]}"#)
+        .is_none());
+}
+
+#[test]
+fn test_json_reports_differing_path() {
+    let predicate_fn = predicates::str::json(json!({
+        "items": [{"name": "a"}, {"name": "b"}]
+    }));
+    let actual = r#"{"items": [{"name": "a"}, {"name": "c"}]}"#;
+
+    let case = predicate_fn.find_case(false, actual).unwrap();
+    assert!(!case.result());
+    assert_eq!(product(&case, "path").unwrap(), "/items/1/name");
+    assert_eq!(product(&case, "reason").unwrap(), "differs");
+    assert_eq!(product(&case, "expected").unwrap(), "\"b\"");
+    assert_eq!(product(&case, "actual").unwrap(), "\"c\"");
+}
+
+#[test]
+fn test_json_missing_and_unexpected_keys() {
+    let predicate_fn = predicates::str::json(json!({"a": {"b/c": 1}}));
+
+    let case = predicate_fn.find_case(false, r#"{"a": {}}"#).unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/a/b~1c");
+    assert_eq!(product(&case, "reason").unwrap(), "missing");
+    assert_eq!(product(&case, "actual"), None);
+
+    let case =
+        predicate_fn.find_case(false, r#"{"a": {"b/c": 1, "d": 2}}"#).unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/a/d");
+    assert_eq!(product(&case, "reason").unwrap(), "unexpected");
+    assert_eq!(product(&case, "actual").unwrap(), "2");
+}
+
+#[test]
+fn test_json_array_length() {
+    let predicate_fn = predicates::str::json(json!([1, 2]));
+
+    let case = predicate_fn.find_case(false, "[1, 2, 3]").unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "(root)");
+    assert_eq!(product(&case, "reason").unwrap(), "expected 2 items, got 3");
+}
+
+#[test]
+fn test_json_subset() {
+    let predicate_fn =
+        predicates::str::json(json!({"user": {"name": "bob"}, "tags": ["x"]}))
+            .subset();
+
+    assert!(predicate_fn.eval(
+        r#"{"id": 7, "user": {"name": "bob", "age": 30}, "tags": ["x", "y"]}"#
+    ));
+    assert!(!predicate_fn.eval(r#"{"user": {"name": "bob"}, "tags": []}"#));
+    let case = predicate_fn
+        .find_case(false, r#"{"user": {"age": 30}, "tags": ["x"]}"#)
+        .unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/user/name");
+}
+
+#[test]
+fn test_json_pointer() {
+    let actual = r#"{"items": [{"name": "a", "size": 3}]}"#;
+
+    assert!(predicates::str::json("a").at("/items/0/name").eval(actual));
+    assert!(predicates::str::json(json!({"name": "a"}))
+        .subset()
+        .at("/items/0")
+        .eval(actual));
+
+    let predicate_fn = predicates::str::json(4).at("/items/0/size");
+    let case = predicate_fn.find_case(false, actual).unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/items/0/size");
+    assert_eq!(product(&case, "actual").unwrap(), "3");
+
+    let predicate_fn = predicates::str::json("a").at("/items/1/name");
+    let case = predicate_fn.find_case(false, actual).unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/items/1/name");
+    assert_eq!(product(&case, "reason").unwrap(), "missing");
+}
+
+#[test]
+fn test_invalid_input() {
+    let predicate_fn = predicates::str::json(json!({}));
+
+    let case = predicate_fn.find_case(false, "{not json").unwrap();
+    assert!(product(&case, "reason").unwrap().starts_with("invalid json: "));
+}
+
+#[test]
+fn test_yaml() {
+    let predicate_fn = predicates::str::yaml(json!({
+        "name": "service",
+        "ports": [80, 443],
+    }));
+
+    assert!(predicate_fn.eval("ports:\n  - 80\n  - 443\nname: service\n"));
+    assert!(predicates::str::yaml(443)
+        .at("/ports/1")
+        .eval("ports: [80, 443]"));
+
+    let case = predicate_fn
+        .find_case(false, "name: service\nports: [80, 8443]\n")
+        .unwrap();
+    assert_eq!(product(&case, "path").unwrap(), "/ports/1");
+    assert_eq!(
+        predicate_fn.to_string(),
+        r#"var.yaml_eq({"name":"service","ports":[80,443]})"#
+    );
+}
@@ -52,3 +52,71 @@ fn test_gen() {
     let c = gen.choose(&[1, 2, 3]).unwrap().to_owned(); // 2
     println!("{:}", c);
 }
+
+#[test]
+fn test_gen_from_seed() {
+    let mut a = Gen::from_seed(42);
+    let mut b = Gen::from_seed(42);
+    assert_eq!(a.seed(), 42);
+    assert_eq!(a.size(), 100);
+    for _ in 0..10 {
+        assert_eq!(a.gen::<u64>(), b.gen::<u64>());
+        assert_eq!(a.gen_range(0..10), b.gen_range(0..10));
+    }
+    assert_eq!(Gen::from_seed(1).with_size(5).size(), 5);
+}
+
+#[test]
+fn test_gen_seed_env() {
+    // 只有这个测试设置环境变量
+    std::env::set_var(SEED_ENV, "7");
+    let mut gen = Gen::new(5);
+    std::env::remove_var(SEED_ENV);
+
+    assert_eq!(gen.seed(), 7);
+    assert_eq!(gen.size(), 5);
+    assert_eq!(gen.gen::<u64>(), Gen::from_seed(7).gen::<u64>());
+}
+
+#[test]
+fn test_gen_record_replay() {
+    let mut gen = Gen::from_seed(3);
+    gen.record();
+    let values: Vec<u32> = (0..5).map(|_| gen.gen()).collect();
+    let recording = gen.take_recording();
+    assert_eq!(recording.values().len(), 5);
+
+    // 录制的内容可以保存为字符串
+    let recording: Recording = recording.to_string().parse().unwrap();
+    assert_eq!(recording.seed(), 3);
+
+    let mut replay = Gen::replay(recording.clone());
+    let replayed: Vec<u32> = (0..5).map(|_| replay.gen()).collect();
+    assert_eq!(values, replayed);
+    // 回放结束后继续使用种子生成
+    assert_eq!(replay.gen::<u64>(), gen.gen::<u64>());
+
+    // 修改录制的值
+    let mut values = recording.values().to_vec();
+    values[0] = 0;
+    let edited: Recording = format!(
+        "3 {}",
+        values.iter().map(u64::to_string).collect::<Vec<_>>().join(" ")
+    )
+    .parse()
+    .unwrap();
+    assert_eq!(Gen::replay(edited).gen::<u64>(), 0);
+}
+
+#[test]
+fn test_deterministic_utils() {
+    let a = random_string_with(&mut Gen::from_seed(9), 16);
+    let b = random_string_with(&mut Gen::from_seed(9), 16);
+    assert_eq!(a.len(), 16);
+    assert_eq!(a, b);
+
+    let key = get_random_key16_with(&mut Gen::from_seed(9));
+    assert_eq!(key, get_random_key16_with(&mut Gen::from_seed(9)));
+    assert_ne!(key, get_random_key16_with(&mut Gen::from_seed(10)));
+    assert_eq!(get_random_key32_with(&mut Gen::from_seed(9)).len(), 32);
+}
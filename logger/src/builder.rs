@@ -1,14 +1,20 @@
+use crate::sink::{BuiltSink, Sink};
 use crate::{filter, fmt, Env};
 use crate::fmt::Formatter;
-use log::{LevelFilter, Record, SetLoggerError, Log, Metadata};
+use core_utils::kv::KVs;
+use log::{Level, LevelFilter, Record, SetLoggerError, Log, Metadata};
 use std::io;
 use std::cell::RefCell;
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
 
 #[derive(Default)]
 pub struct Builder {
     filter: filter::Builder,
     writer: fmt::writer::WriteBuilder,
     format: fmt::Builder,
+    sinks: Vec<Sink>,
     built: bool,
 }
 
@@ -42,6 +48,10 @@ impl Builder {
             self.parse_write_style(&s);
         }
 
+        if let Some(s) = env.get_log_format() {
+            self.parse_log_format(&s);
+        }
+
         self
     }
 
@@ -67,7 +77,41 @@ impl Builder {
         self
     }
 
-        /// Sets the format function for formatting the log output.
+    /// Parses the output format in the same form as the `RUST_LOG_FORMAT`
+    /// environment variable, one of `text`, `json` or `logfmt`.
+    pub fn parse_log_format(&mut self, log_format: &str) -> &mut Self {
+        self.log_format(fmt::parse_log_format(log_format))
+    }
+
+    /// Selects the built-in output format.
+    ///
+    /// A custom format set with [`Builder::format`] takes precedence.
+    pub fn log_format(&mut self, log_format: fmt::LogFormat) -> &mut Self {
+        self.format.log_format = log_format;
+        self
+    }
+
+    /// Sets the facility, host and application name of the syslog and
+    /// journald formats.
+    pub fn syslog(&mut self, syslog: fmt::Syslog) -> &mut Self {
+        self.format.syslog = Some(syslog);
+        self
+    }
+
+    /// Adds key/values written as top-level fields by the structured formats.
+    pub fn format_key_values<'a, K>(&mut self, kvs: K) -> &mut Self
+    where
+        K: KVs<'a>,
+    {
+        if let Some(kvs) = kvs.into_kvs() {
+            self.format.format_key_values.extend(
+                kvs.iter().map(|(k, v)| (k.to_string(), v.to_string())),
+            );
+        }
+        self
+    }
+
+    /// Sets the format function for formatting the log output.
     pub fn format<F: 'static>(&mut self, format: F) -> &mut Self
     where
         F: Fn(&mut Formatter, &Record) -> io::Result<()> + Sync + Send,
@@ -76,7 +120,7 @@ impl Builder {
         self
     }
 
-        /// Use the default format.
+    /// Use the default format.
     ///
     /// This method will clear any custom format set on the builder.
     pub fn default_format(&mut self) -> &mut Self {
@@ -101,7 +145,7 @@ impl Builder {
         self.format.format_target = write;
         self
     }
-        /// Configures the amount of spaces to use to indent multiline log records.
+    /// Configures the amount of spaces to use to indent multiline log records.
     /// A value of `None` disables any kind of indentation.
     pub fn format_indent(&mut self, indent: Option<usize>) -> &mut Self {
         self.format.format_indent = indent;
@@ -158,6 +202,20 @@ impl Builder {
         self
     }
 
+    /// Writes at most `per_second` records per callsite of the given module,
+    /// see [`filter::Builder::rate_limit`].
+    pub fn rate_limit(&mut self, module: Option<&str>, per_second: u32) -> &mut Self {
+        self.filter.rate_limit(module, per_second);
+        self
+    }
+
+    /// Keeps records of the given module with probability `ratio`, see
+    /// [`filter::Builder::sample`].
+    pub fn sample(&mut self, module: Option<&str>, ratio: f64) -> &mut Self {
+        self.filter.sample(module, ratio);
+        self
+    }
+
     /// Sets the target for the log output.
     pub fn target(&mut self, target: fmt::Target) -> &mut Self {
         self.writer.target(target);
@@ -170,19 +228,59 @@ impl Builder {
         self
     }
 
+    /// Writes records to the target from a background thread.
+    ///
+    /// The logging thread only formats the record and pushes it onto a
+    /// bounded queue, see [`fmt::Overflow`] for what happens when it is full.
+    /// [`Log::flush`] waits until the queue is drained.
+    ///
+    /// Use [`Builder::try_init_with_guard`] so the records queued at exit
+    /// are written.
+    pub fn non_blocking(&mut self, non_blocking: fmt::NonBlocking) -> &mut Self {
+        self.writer.non_blocking(non_blocking);
+        self
+    }
+
+
+    /// Adds an output with its own filter, writer and format.
+    ///
+    /// Records are written to the target of this builder if they match its
+    /// filter, and to every sink whose filter they match.
+    pub fn sink(&mut self, sink: Sink) -> &mut Self {
+        self.sinks.push(sink);
+        self
+    }
 
     /// Initializes the global logger with the built env logger.
     pub fn try_init(&mut self) -> Result<(), SetLoggerError> {
+        self.try_init_with_handle().map(drop)
+    }
+
+    /// Initializes the global logger with the built env logger.
+    ///
+    /// The returned handle can replace the filter directives at runtime,
+    /// see [`filter::FilterHandle`].
+    pub fn try_init_with_handle(
+        &mut self,
+    ) -> Result<filter::FilterHandle, SetLoggerError> {
         let logger = self.build();
 
         let max_level = logger.filter();
-        let r = log::set_boxed_logger(Box::new(logger));
+        let handle = logger.filter_handle().global(logger.sinks_filter());
+        log::set_boxed_logger(Box::new(logger))?;
+        log::set_max_level(max_level);
 
-        if r.is_ok() {
-            log::set_max_level(max_level);
-        }
+        Ok(handle)
+    }
 
-        r
+    /// Initializes the global logger with the built env logger.
+    ///
+    /// The global logger is never dropped, so records still queued by a
+    /// [non-blocking](Builder::non_blocking) writer when the process exits
+    /// are lost unless it is flushed. Keep the returned guard alive in
+    /// `main`, dropping it flushes the global logger.
+    pub fn try_init_with_guard(&mut self) -> Result<FlushGuard, SetLoggerError> {
+        self.try_init().map(|_| FlushGuard { _private: () })
     }
 
     /// Initializes the global logger with the built env logger.
@@ -196,15 +294,38 @@ impl Builder {
         assert!(!self.built, "attempt to re-use consumed builder");
         self.built = true;
 
-        Logger {
+        let outputs = Arc::new(Outputs {
             writer: self.writer.build(),
-            filter: self.filter.build(),
+            filter: filter::FilterHandle::new(self.filter.build()),
             format: self.format.build(),
+            sinks: self.sinks.drain(..).map(Sink::build).collect(),
+        });
+
+        // 限流的汇总日志需要定期输出，即使之后不再有日志
+        if outputs.is_rate_limited() {
+            spawn_summaries(Arc::downgrade(&outputs));
         }
+
+        Logger { outputs }
     }
 
 }
 
+/// Flushes the global logger when dropped.
+///
+/// Returned by [`Builder::try_init_with_guard`].
+#[must_use = "dropping the guard flushes the logger immediately"]
+#[derive(Debug)]
+pub struct FlushGuard {
+    _private: (),
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        log::logger().flush();
+    }
+}
+
 /// Create a new builder with the default environment variables.
 pub fn builder() -> Builder {
     Builder::from_default_env()
@@ -218,6 +339,7 @@ impl std::fmt::Debug for Builder {
             f.debug_struct("Logger")
                 .field("filter", &self.filter)
                 .field("writer", &self.writer)
+                .field("sinks", &self.sinks)
                 .finish()
         }
     }
@@ -238,10 +360,21 @@ where
 }
 
 
+/// How often the summaries of the records dropped by a rate limit are
+/// written.
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Logger {
+    outputs: Arc<Outputs>,
+}
+
+/// The writers of a logger, shared with the thread writing the summaries of
+/// suppressed records.
+struct Outputs {
     writer: fmt::Writer,
-    filter: filter::Filter,
+    filter: filter::FilterHandle,
     format: fmt::FormatFn,
+    sinks: Vec<BuiltSink>,
 }
 
 impl Logger {
@@ -260,93 +393,206 @@ impl Logger {
     /// Returns the maximum `LevelFilter` that this env logger instance is
     /// configured to output.
     pub fn filter(&self) -> LevelFilter {
-        self.filter.filter()
+        self.outputs.filter.filter().max(self.sinks_filter())
     }
 
-    /// Checks if this record matches the configured filter.
+    /// Checks if this record matches the configured filter or the filter of
+    /// any sink.
+    ///
+    /// Sampling and rate limits are only applied when the record is logged.
     pub fn matches(&self, record: &Record) -> bool {
-        self.filter.matches(record)
+        let outputs = &self.outputs;
+        outputs.filter.matches(record)
+            || outputs.sinks.iter().any(|sink| sink.matches(record))
+    }
+
+    /// The maximum `LevelFilter` of the sinks.
+    fn sinks_filter(&self) -> LevelFilter {
+        self.outputs
+            .sinks
+            .iter()
+            .map(|sink| sink.filter.filter())
+            .max()
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    /// Returns a handle to replace the filter of this logger at runtime.
+    pub fn filter_handle(&self) -> filter::FilterHandle {
+        self.outputs.filter.clone()
+    }
+
+    /// The number of records discarded because the non-blocking queue was
+    /// full.
+    pub fn dropped_records(&self) -> u64 {
+        let outputs = &self.outputs;
+        outputs.writer.dropped()
+            + outputs.sinks.iter().map(|sink| sink.writer.dropped()).sum::<u64>()
     }
 }
 
 impl std::fmt::Debug for Logger {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Logger")
-            .field("filter", &self.filter)
+            .field("filter", &self.outputs.filter)
+            .field("sinks", &self.outputs.sinks)
             .finish()
     }
 }
 
 impl Log for Logger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        self.filter.enabled(metadata)
+        let outputs = &self.outputs;
+        outputs.filter.enabled(metadata)
+            || outputs.sinks.iter().any(|sink| sink.enabled(metadata))
     }
 
     fn log(&self, record: &Record) {
-        if self.matches(record) {
-            // Log records are written to a thread-local buffer before being printed
-            // to the terminal. We clear these buffers afterwards, but they aren't shrunk
-            // so will always at least have capacity for the largest log record formatted
-            // on that thread.
-            //
-            // If multiple `Logger`s are used by the same threads then the thread-local
-            // formatter might have different color support. If this is the case the
-            // formatter and its buffer are discarded and recreated.
-
-            thread_local! {
-                static FORMATTER: RefCell<Option<Formatter>> = RefCell::new(None);
+        let outputs = &self.outputs;
+        if outputs.filter.matches(record) && outputs.filter.admit(record) {
+            print(&outputs.writer, &outputs.format, record);
+        }
+
+        for sink in &outputs.sinks {
+            if sink.matches(record) && sink.admit(record) {
+                print(&sink.writer, &sink.format, record);
             }
+        }
+
+        outputs.print_suppressed(false);
+    }
 
-            // 定义日志写方法
-            let print = |formatter: &mut Formatter, record: &Record| {
-                let _ =
-                    (self.format)(formatter, record).and_then(|_| formatter.print(&self.writer));
+    fn flush(&self) {
+        let outputs = &self.outputs;
+        outputs.print_suppressed(true);
 
-                // Always clear the buffer afterwards
-                formatter.clear();
+        let _ = outputs.writer.flush();
+        for sink in &outputs.sinks {
+            let _ = sink.writer.flush();
+        }
+    }
+}
+
+impl Outputs {
+    fn is_rate_limited(&self) -> bool {
+        self.filter.is_rate_limited()
+            || self.sinks.iter().any(|sink| sink.filter.is_rate_limited())
+    }
+
+    /// Writes the summaries of the callsites suppressed by a rate limit,
+    /// the ones whose window is not over yet too if `flush` is set.
+    fn print_suppressed(&self, flush: bool) {
+        let suppressed = if flush {
+            self.filter.flush_suppressed()
+        } else {
+            self.filter.take_suppressed()
+        };
+        print_suppressed(&self.writer, &self.format, suppressed);
+
+        for sink in &self.sinks {
+            let suppressed = if flush {
+                sink.filter.flush_suppressed()
+            } else {
+                sink.filter.take_suppressed()
             };
+            print_suppressed(&sink.writer, &sink.format, suppressed);
+        }
+    }
+}
+
+/// Writes the summaries of suppressed records every [`SUMMARY_INTERVAL`]
+/// until the logger is dropped.
+fn spawn_summaries(outputs: Weak<Outputs>) {
+    let _ = thread::Builder::new()
+        .name("logger-suppressed".to_string())
+        .spawn(move || loop {
+            thread::sleep(SUMMARY_INTERVAL);
+            match outputs.upgrade() {
+                Some(outputs) => outputs.print_suppressed(false),
+                None => break,
+            }
+        });
+}
 
-            let printed = FORMATTER
-                .try_with(|tl_buf| {
-                    match tl_buf.try_borrow_mut() {
-                        // There are no active borrows of the buffer
-                        Ok(mut tl_buf) => match *tl_buf {
-                            // We have a previously set formatter
-                            Some(ref mut formatter) => {
-                                // Check the buffer style. If it's different from the logger's
-                                // style then drop the buffer and recreate it.
-                                if formatter.write_style() != self.writer.write_style() {
-                                    *formatter = Formatter::new(&self.writer);
-                                }
-
-                                print(formatter, record);
-                            }
-                            // We don't have a previously set formatter
-                            None => {
-                                let mut formatter = Formatter::new(&self.writer);
-                                print(&mut formatter, record);
-
-                                *tl_buf = Some(formatter);
-                            }
-                        },
-                        // There's already an active borrow of the buffer (due to re-entrancy)
-                        Err(_) => {
-                            print(&mut Formatter::new(&self.writer), record);
+/// Writes a summary record for each callsite dropped by a rate limit.
+fn print_suppressed(
+    writer: &fmt::Writer,
+    format: &fmt::FormatFn,
+    suppressed: Vec<filter::Suppressed>,
+) {
+    for suppressed in suppressed {
+        print(
+            writer,
+            format,
+            &Record::builder()
+                .args(format_args!("{}", suppressed))
+                .level(Level::Warn)
+                .target(&suppressed.target)
+                .build(),
+        );
+    }
+}
+
+/// Formats the record and writes it to `writer`.
+fn print(writer: &fmt::Writer, format: &fmt::FormatFn, record: &Record) {
+    // Log records are written to a thread-local buffer before being printed
+    // to the terminal. We clear these buffers afterwards, but they aren't shrunk
+    // so will always at least have capacity for the largest log record formatted
+    // on that thread.
+    //
+    // The main writer and every sink of a `Logger`, as well as other `Logger`s
+    // used by the same threads, might have different color support. The
+    // buffer is only valid for the writer it was created for, so when the
+    // writer changes the formatter and its buffer are discarded and recreated.
+
+    thread_local! {
+        static FORMATTER: RefCell<Option<Formatter>> = RefCell::new(None);
+    }
+
+    // 定义日志写方法
+    let write = |formatter: &mut Formatter, record: &Record| {
+        let _ = format(formatter, record).and_then(|_| formatter.print(writer));
+
+        // Always clear the buffer afterwards
+        formatter.clear();
+    };
+
+    let printed = FORMATTER
+        .try_with(|tl_buf| {
+            match tl_buf.try_borrow_mut() {
+                // There are no active borrows of the buffer
+                Ok(mut tl_buf) => match *tl_buf {
+                    // We have a previously set formatter
+                    Some(ref mut formatter) => {
+                        // Check the buffer's writer. If it's a different writer
+                        // then drop the buffer and recreate it.
+                        if formatter.writer_id() != writer.id() {
+                            *formatter = Formatter::new(writer);
                         }
+
+                        write(formatter, record);
                     }
-                })
-                .is_ok();
-
-            if !printed {
-                // The thread-local storage was not available (because its
-                // destructor has already run). Create a new single-use
-                // Formatter on the stack for this call.
-                print(&mut Formatter::new(&self.writer), record);
+                    // We don't have a previously set formatter
+                    None => {
+                        let mut formatter = Formatter::new(writer);
+                        write(&mut formatter, record);
+
+                        *tl_buf = Some(formatter);
+                    }
+                },
+                // There's already an active borrow of the buffer (due to re-entrancy)
+                Err(_) => {
+                    write(&mut Formatter::new(writer), record);
+                }
             }
-        }
-    }
+        })
+        .is_ok();
 
-    fn flush(&self) {}
+    if !printed {
+        // The thread-local storage was not available (because its
+        // destructor has already run). Create a new single-use
+        // Formatter on the stack for this call.
+        write(&mut Formatter::new(writer), record);
+    }
 }
 
 /// Initializes the global logger with an env logger.
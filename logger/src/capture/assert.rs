@@ -0,0 +1,203 @@
+use super::CapturedRecord;
+use core_utils::predicates::core::{Case, CaseTree, CaseTreeExt, Predicate};
+use std::error::Error;
+use std::fmt;
+
+/// The records captured by a [`super::CaptureGuard`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Captured {
+    records: Vec<CapturedRecord>,
+}
+
+/// [`Captured`] assertion result.
+pub type CaptureResult = Result<(), CaptureError>;
+
+impl Captured {
+    pub fn new(records: Vec<CapturedRecord>) -> Self {
+        Captured { records }
+    }
+
+    pub fn records(&self) -> &[CapturedRecord] {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CapturedRecord> {
+        self.records.iter()
+    }
+
+    /// Ensure at least one record matches the predicate.
+    #[track_caller]
+    pub fn assert_any<P>(&self, pred: P) -> &Self
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        self.try_assert_any(pred).unwrap_or_else(CaptureError::panic);
+        self
+    }
+
+    /// `try_` variant of [`Captured::assert_any`].
+    pub fn try_assert_any<P>(&self, pred: P) -> CaptureResult
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        if self.records.iter().any(|record| pred.eval(record)) {
+            return Ok(());
+        }
+
+        let case = Case::new(Some(&pred), false);
+        Err(self.error(CaptureReason::NoMatch { case_tree: case.tree() }))
+    }
+
+    /// Ensure no record matches the predicate.
+    #[track_caller]
+    pub fn assert_none<P>(&self, pred: P) -> &Self
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        self.try_assert_none(pred).unwrap_or_else(CaptureError::panic);
+        self
+    }
+
+    /// `try_` variant of [`Captured::assert_none`].
+    pub fn try_assert_none<P>(&self, pred: P) -> CaptureResult
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        match self
+            .records
+            .iter()
+            .find_map(|record| pred.find_case(true, record))
+        {
+            Some(case) => Err(self.error(CaptureReason::UnexpectedMatch {
+                case_tree: case.tree(),
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensure exactly `expected` records match the predicate.
+    #[track_caller]
+    pub fn assert_count<P>(&self, pred: P, expected: usize) -> &Self
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        self.try_assert_count(pred, expected)
+            .unwrap_or_else(CaptureError::panic);
+        self
+    }
+
+    /// `try_` variant of [`Captured::assert_count`].
+    pub fn try_assert_count<P>(
+        &self,
+        pred: P,
+        expected: usize,
+    ) -> CaptureResult
+    where
+        P: Predicate<CapturedRecord>,
+    {
+        let actual =
+            self.records.iter().filter(|record| pred.eval(record)).count();
+        if actual == expected {
+            return Ok(());
+        }
+
+        let case = Case::new(Some(&pred), true);
+        Err(self.error(CaptureReason::UnexpectedCount {
+            expected,
+            actual,
+            case_tree: case.tree(),
+        }))
+    }
+
+    fn error(&self, reason: CaptureReason) -> CaptureError {
+        CaptureError { captured: self.clone(), reason: Box::new(reason) }
+    }
+}
+
+impl<'a> IntoIterator for &'a Captured {
+    type Item = &'a CapturedRecord;
+    type IntoIter = std::slice::Iter<'a, CapturedRecord>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
+    }
+}
+
+/// Renders the captured records as a tree, with their key/values as leaves.
+impl fmt::Display for Captured {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let leaves = self.records.iter().map(|record| {
+            let key_values = record
+                .key_values
+                .iter()
+                .map(|(k, v)| termtree::Tree::new(format!("{}={}", k, v)));
+            termtree::Tree::new(record.to_string())
+                .with_multiline(true)
+                .with_leaves(key_values)
+        });
+
+        let root = format!("captured logs ({})", self.records.len());
+        write!(f, "{}", termtree::Tree::new(root).with_leaves(leaves))
+    }
+}
+
+enum CaptureReason {
+    NoMatch { case_tree: CaseTree },
+    UnexpectedMatch { case_tree: CaseTree },
+    UnexpectedCount { expected: usize, actual: usize, case_tree: CaseTree },
+}
+
+/// [`Captured`] assertion error (see [`CaptureResult`]).
+pub struct CaptureError {
+    captured: Captured,
+    reason: Box<CaptureReason>,
+}
+
+impl CaptureError {
+    #[track_caller]
+    fn panic<T>(self) -> T {
+        panic!("{}", self)
+    }
+
+    pub fn captured(self) -> Captured {
+        self.captured
+    }
+}
+
+impl Error for CaptureError {}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &*self.reason {
+            CaptureReason::NoMatch { case_tree } => {
+                writeln!(f, "No matching record, failed {}", case_tree)
+            }
+            CaptureReason::UnexpectedMatch { case_tree } => {
+                writeln!(f, "Unexpected record, failed {}", case_tree)
+            }
+            CaptureReason::UnexpectedCount { expected, actual, case_tree } => {
+                writeln!(
+                    f,
+                    "Expected {} matching records, found {}, failed {}",
+                    expected, actual, case_tree
+                )
+            }
+        }?;
+        write!(f, "{}", self.captured)
+    }
+}
+
+// `CaseTree` does not implement `Debug`.
+impl fmt::Debug for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
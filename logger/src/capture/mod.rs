@@ -0,0 +1,32 @@
+//! Capture log records in tests.
+//!
+//! [`start`] installs a global logger that stores the records of the
+//! current thread while the returned guard is alive, the captured records
+//! are checked with [`core_utils::predicates`]:
+//!
+//! ```
+//! use core_utils::predicates::str::is_match;
+//! use logger::capture::{self, record};
+//! use logger::Level;
+//!
+//! let logs = capture::capture(|| {
+//!     logger::warn!(target: "db", "slow query: 1200ms");
+//! });
+//!
+//! logs.assert_any(
+//!     record()
+//!         .level(Level::Warn)
+//!         .target("db")
+//!         .message(is_match("slow query: \\d+ms").unwrap()),
+//! );
+//! logs.assert_none(record().level(Level::Error));
+//! ```
+mod assert;
+mod predicate;
+mod record;
+mod scope;
+
+pub use assert::{CaptureError, CaptureResult, Captured};
+pub use predicate::{record, RecordPredicate};
+pub use record::CapturedRecord;
+pub use scope::{capture, start, CaptureGuard, CaptureLogger};
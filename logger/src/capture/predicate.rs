@@ -0,0 +1,140 @@
+use super::CapturedRecord;
+use crate::Level;
+use core_utils::predicates::core::{
+    Case, Child, Parameter, Predicate, PredicateReflection, Product,
+};
+use core_utils::predicates::BoxPredicate;
+use std::fmt;
+
+/// Matches captured records by level, target, message and key/values.
+///
+/// Every condition that is set must hold, a predicate without conditions
+/// matches any record. Created by [`record`].
+#[derive(Debug, Default)]
+pub struct RecordPredicate {
+    level: Option<Level>,
+    target: Option<String>,
+    message: Option<BoxPredicate<str>>,
+    key_values: Vec<KeyValue>,
+}
+
+#[derive(Debug)]
+struct KeyValue(String, String);
+
+impl fmt::Display for KeyValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.0, self.1)
+    }
+}
+
+/// Creates a new predicate matching any captured record.
+pub fn record() -> RecordPredicate {
+    RecordPredicate::default()
+}
+
+impl RecordPredicate {
+    /// Only match records of this level.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Only match records of this target.
+    pub fn target<T: Into<String>>(mut self, target: T) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Only match records whose message satisfies `message`.
+    pub fn message<P>(mut self, message: P) -> Self
+    where
+        P: Predicate<str> + Send + Sync + 'static,
+    {
+        self.message = Some(BoxPredicate::new(message));
+        self
+    }
+
+    /// Only match records with this key/value pair.
+    pub fn key_value<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: fmt::Display,
+    {
+        self.key_values.push(KeyValue(key.into(), value.to_string()));
+        self
+    }
+}
+
+impl Predicate<CapturedRecord> for RecordPredicate {
+    fn eval(&self, variable: &CapturedRecord) -> bool {
+        self.level.is_none_or(|level| variable.level == level)
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| variable.target == *target)
+            && self
+                .message
+                .as_ref()
+                .is_none_or(|message| message.eval(&variable.message))
+            && self
+                .key_values
+                .iter()
+                .all(|KeyValue(k, v)| variable.get(k) == Some(v.as_str()))
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &CapturedRecord,
+    ) -> Option<Case<'a>> {
+        let result = self.eval(variable);
+        if result != expected {
+            return None;
+        }
+
+        let mut case = Case::new(Some(self), result)
+            .add_product(Product::new("var", variable.to_string()));
+        // 展示消息断言的结果
+        if let Some(message) = &self.message {
+            let matched = message.eval(&variable.message);
+            if let Some(child) = message.find_case(matched, &variable.message)
+            {
+                case = case.add_child(child);
+            }
+        }
+        Some(case)
+    }
+}
+
+impl PredicateReflection for RecordPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = Parameter<'a>> + 'a> {
+        let mut params = vec![];
+        if let Some(level) = &self.level {
+            params.push(Parameter::new("level", level));
+        }
+        if let Some(target) = &self.target {
+            params.push(Parameter::new("target", target));
+        }
+        for key_value in &self.key_values {
+            params.push(Parameter::new("key_value", key_value));
+        }
+        Box::new(params.into_iter())
+    }
+
+    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = Child<'a>> + 'a> {
+        let params = self
+            .message
+            .iter()
+            .map(|message| Child::new("message", message))
+            .collect::<Vec<_>>();
+        Box::new(params.into_iter())
+    }
+}
+
+impl fmt::Display for RecordPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "var.is_record()")
+    }
+}
@@ -0,0 +1,36 @@
+use crate::Level;
+use std::fmt;
+
+/// A record stored by the capture logger.
+///
+/// Keeps the structured fields of the record next to the line written by
+/// the configured format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedRecord {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub module_path: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub key_values: Vec<(String, String)>,
+    pub formatted: String,
+}
+
+impl CapturedRecord {
+    /// Returns the value of the key/value pair with the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.key_values.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for CapturedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted = self.formatted.trim_end();
+        if formatted.is_empty() {
+            write!(f, "[{} {}] {}", self.level, self.target, self.message)
+        } else {
+            f.write_str(formatted)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use super::{Captured, CapturedRecord};
+use crate::fmt::writer::WriteBuilder;
+use crate::fmt::{self, Formatter, Target, WriteStyle};
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::cell::RefCell;
+use std::io;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// 是否已安装全局的 capture logger
+static INSTALLED: Mutex<bool> = Mutex::new(false);
+
+thread_local! {
+    // 当前线程中正在捕获的作用域，每个作用域一个缓存
+    static SCOPES: RefCell<Vec<Vec<CapturedRecord>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A logger storing the records of every thread with an active
+/// [`CaptureGuard`].
+///
+/// Records of threads that are not capturing are discarded.
+pub struct CaptureLogger {
+    writer: fmt::Writer,
+    format: fmt::FormatFn,
+}
+
+impl Default for CaptureLogger {
+    fn default() -> Self {
+        let mut format = fmt::Builder::default();
+        format.format_timestamp = None;
+        CaptureLogger::new(format)
+    }
+}
+
+impl CaptureLogger {
+    /// A capture logger rendering `formatted` with the given format.
+    pub fn new(mut format: fmt::Builder) -> Self {
+        let writer = WriteBuilder::new()
+            .target(Target::Pipe(Box::new(io::sink())))
+            .write_style(WriteStyle::Never)
+            .build();
+
+        CaptureLogger { writer, format: format.build() }
+    }
+
+    /// Installs this logger as the global logger.
+    pub fn try_init(self) -> Result<(), SetLoggerError> {
+        let mut installed =
+            INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+        self.install(&mut installed)
+    }
+
+    fn install(self, installed: &mut bool) -> Result<(), SetLoggerError> {
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(LevelFilter::Trace);
+        *installed = true;
+        Ok(())
+    }
+
+    fn capture(&self, record: &Record) -> CapturedRecord {
+        let mut formatter = Formatter::new(&self.writer);
+        let formatted = match (self.format)(&mut formatter, record) {
+            Ok(()) => String::from_utf8_lossy(formatter.buf.borrow().bytes())
+                .into_owned(),
+            Err(_) => String::new(),
+        };
+
+        let mut key_values = Vec::new();
+        let _ = crate::kv::for_each_with_context(record, |key, value| {
+            key_values.push((key.to_string(), value.to_string()));
+            Ok(())
+        });
+
+        CapturedRecord {
+            level: record.level().into(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            module_path: record.module_path().map(String::from),
+            file: record.file().map(String::from),
+            line: record.line(),
+            key_values,
+            formatted,
+        }
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        SCOPES.try_with(|scopes| !scopes.borrow().is_empty()).unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let captured = self.capture(record);
+        let _ = SCOPES.try_with(|scopes| {
+            // 嵌套的作用域都能看到内层产生的日志
+            for scope in scopes.borrow_mut().iter_mut() {
+                scope.push(captured.clone());
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Captures the records logged on the current thread until it is dropped
+/// or finished.
+///
+/// Created by [`start`], guards are not `Send` as the records are stored
+/// per thread.
+#[derive(Debug)]
+pub struct CaptureGuard {
+    depth: usize,
+    finished: bool,
+    _thread: PhantomData<*const ()>,
+}
+
+impl CaptureGuard {
+    /// The records captured so far.
+    pub fn records(&self) -> Captured {
+        SCOPES
+            .with(|scopes| Captured::new(scopes.borrow()[self.depth].clone()))
+    }
+
+    /// Stops capturing and returns the captured records.
+    pub fn finish(mut self) -> Captured {
+        self.finished = true;
+        Captured::new(self.pop())
+    }
+
+    fn pop(&self) -> Vec<CapturedRecord> {
+        SCOPES.with(|scopes| {
+            let mut scopes = scopes.borrow_mut();
+            assert_eq!(
+                scopes.len(),
+                self.depth + 1,
+                "capture guards must be dropped in reverse order"
+            );
+            scopes.pop().unwrap_or_default()
+        })
+    }
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().pop());
+        }
+    }
+}
+
+/// Starts capturing the records logged on the current thread.
+///
+/// A default [`CaptureLogger`] is installed as the global logger the first
+/// time, unless one was installed with [`CaptureLogger::try_init`].
+///
+/// # Panics
+///
+/// If another global logger was already installed.
+pub fn start() -> CaptureGuard {
+    {
+        let mut installed =
+            INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+        if !*installed
+            && CaptureLogger::default().install(&mut installed).is_err()
+        {
+            panic!("capture::start called after another logger was installed");
+        }
+    }
+
+    let depth = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.push(Vec::new());
+        scopes.len() - 1
+    });
+
+    CaptureGuard { depth, finished: false, _thread: PhantomData }
+}
+
+/// Runs `f` and returns the records it logged on the current thread.
+pub fn capture<F: FnOnce()>(f: F) -> Captured {
+    let guard = start();
+    f();
+    guard.finish()
+}
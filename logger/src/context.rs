@@ -0,0 +1,171 @@
+//! Key/values attached to every record logged within a scope.
+//!
+//! A [`ContextGuard`] pushes key/values onto a per thread stack until it is
+//! dropped, the formatters write them after the key/values of the record:
+//!
+//! ```
+//! let _request = logger::context::scope([("request_id", "r-42")]);
+//! let _user = logger::context::push("user_id", 7);
+//!
+//! // Written as `... handled request_id=r-42 user_id=7`.
+//! logger::info!("handled");
+//! ```
+//!
+//! Futures are polled on whatever thread the executor picks, wrap them with
+//! [`WithContextExt::in_current_context`] to carry the context of the
+//! spawning code across `.await`s.
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+
+type Frame = Arc<Vec<(String, String)>>;
+
+thread_local! {
+    // 当前线程的上下文栈，每个 guard 一帧
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the key/values pushed by [`push`] or [`scope`] when dropped.
+///
+/// Guards should be dropped in reverse order of creation, dropping a guard
+/// also pops the frames pushed after it. Guards are not `Send` as the stack
+/// is per thread, don't hold them across an `.await`, see
+/// [`WithContextExt::in_current_context`].
+#[derive(Debug)]
+#[must_use = "the key/values are popped when the guard is dropped"]
+pub struct ContextGuard {
+    depth: usize,
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let _ =
+            STACK.try_with(|stack| stack.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Attaches a key/value to the records logged until the guard is dropped.
+pub fn push<K, V>(key: K, value: V) -> ContextGuard
+where
+    K: Into<String>,
+    V: Display,
+{
+    scope([(key, value)])
+}
+
+/// Attaches key/values to the records logged until the guard is dropped.
+pub fn scope<I, K, V>(key_values: I) -> ContextGuard
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Display,
+{
+    let frame = key_values
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.to_string()))
+        .collect();
+
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.push(Arc::new(frame));
+        ContextGuard { depth: stack.len() - 1, _thread: PhantomData }
+    })
+}
+
+/// A snapshot of the context stack of a thread.
+#[derive(Clone, Debug, Default)]
+pub struct Context {
+    frames: Vec<Frame>,
+}
+
+impl Context {
+    /// The context of the current thread.
+    pub fn current() -> Self {
+        let frames =
+            STACK.try_with(|stack| stack.borrow().clone()).unwrap_or_default();
+        Context { frames }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.iter().all(|frame| frame.is_empty())
+    }
+
+    /// The key/values of the context, inner frames override outer ones.
+    pub fn key_values(&self) -> Vec<(&str, &str)> {
+        let mut key_values: Vec<(&str, &str)> = Vec::new();
+        for (k, v) in self.frames.iter().flat_map(|frame| frame.iter()) {
+            match key_values.iter_mut().find(|(key, _)| key == k) {
+                Some(pair) => pair.1 = v,
+                None => key_values.push((k, v)),
+            }
+        }
+        key_values
+    }
+
+    /// Runs `f` with this context as the context of the current thread.
+    pub fn enter<F: FnOnce() -> R, R>(&mut self, f: F) -> R {
+        STACK.with(|stack| {
+            mem::swap(&mut *stack.borrow_mut(), &mut self.frames)
+        });
+
+        // 恢复线程原有的上下文，保留 f 中压入的帧
+        struct Restore<'a>(&'a mut Vec<Frame>);
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let _ = STACK.try_with(|stack| {
+                    mem::swap(&mut *stack.borrow_mut(), self.0)
+                });
+            }
+        }
+
+        let _restore = Restore(&mut self.frames);
+        f()
+    }
+}
+
+/// A future polled within a [`Context`].
+///
+/// Key/values pushed while the future is polled stay with the future until
+/// their guard is dropped, whichever thread polls it next.
+#[derive(Debug)]
+pub struct WithContext<F> {
+    inner: F,
+    context: Context,
+}
+
+impl<F: Future> Future for WithContext<F> {
+    type Output = F::Output;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<F::Output> {
+        // SAFETY: `inner` is never moved out of the pinned `WithContext`, and
+        // `context` is not structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+        this.context.enter(|| inner.poll(cx))
+    }
+}
+
+/// Carries a [`Context`] across the `.await`s of a future.
+pub trait WithContextExt: Future + Sized {
+    /// Polls the future within `context`.
+    fn with_context(self, context: Context) -> WithContext<Self> {
+        WithContext { inner: self, context }
+    }
+
+    /// Polls the future within the context of the current thread.
+    fn in_current_context(self) -> WithContext<Self> {
+        self.with_context(Context::current())
+    }
+}
+
+impl<F: Future> WithContextExt for F {}
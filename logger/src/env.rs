@@ -7,11 +7,15 @@ pub const DEFAULT_FILTER_ENV: &str = "RUST_LOG";
 /// The default name for the environment variable to read style preferences from.
 pub const DEFAULT_WRITE_STYLE_ENV: &str = "RUST_LOG_STYLE";
 
+/// The default name for the environment variable to read the output format from.
+pub const DEFAULT_LOG_FORMAT_ENV: &str = "RUST_LOG_FORMAT";
+
 /// Set of environment variables to configure from.
 #[derive(Debug)]
 pub struct Env<'a> {
     filter: Var<'a>, // 日志的过滤级别，从环境变了获取
     write_style: Var<'a>,   // 日志类型，从环境变了获取
+    log_format: Var<'a>,    // 日志格式，从环境变量获取
 }
 
 impl<'a> Env<'a> {
@@ -103,6 +107,45 @@ impl<'a> Env<'a> {
     pub fn get_write_style(&self) -> Option<String> {
         self.write_style.get()
     }
+
+    /// Specify an environment variable to read the output format from.
+    pub fn log_format<E>(mut self, log_format_env: E) -> Self
+    where
+        E: Into<Cow<'a, str>>,
+    {
+        self.log_format = Var::new(log_format_env);
+
+        self
+    }
+
+    /// Specify an environment variable to read the output format from.
+    ///
+    /// If the variable is not set, the default value will be used.
+    pub fn log_format_or<E, V>(mut self, log_format_env: E, default: V) -> Self
+    where
+        E: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.log_format = Var::new_with_default(log_format_env, default);
+
+        self
+    }
+
+    /// Use the default environment variable to read the output format from.
+    ///
+    /// If the variable is not set, the default value will be used.
+    pub fn default_log_format_or<V>(mut self, default: V) -> Self
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        self.log_format = Var::new_with_default(DEFAULT_LOG_FORMAT_ENV, default);
+
+        self
+    }
+
+    pub fn get_log_format(&self) -> Option<String> {
+        self.log_format.get()
+    }
 }
 
 /// 设置默认环境变量
@@ -111,6 +154,7 @@ impl<'a> Default for Env<'a> {
         Env {
             filter: Var::new(DEFAULT_FILTER_ENV),
             write_style: Var::new(DEFAULT_WRITE_STYLE_ENV),
+            log_format: Var::new(DEFAULT_LOG_FORMAT_ENV),
         }
     }
 }
@@ -172,4 +216,14 @@ mod tests {
 
         assert_eq!(Some("from default".to_owned()), env.get_write_style());
     }
+
+    #[test]
+    fn env_get_log_format_reads_from_var_if_set() {
+        env::set_var("env_get_log_format_reads_from_var_if_set", "json");
+
+        let env =
+            Env::new().log_format_or("env_get_log_format_reads_from_var_if_set", "text");
+
+        assert_eq!(Some("json".to_owned()), env.get_log_format());
+    }
 }
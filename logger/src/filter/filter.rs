@@ -1,8 +1,10 @@
+use super::limit::{Limiter, RateLimit, Sample, Suppressed};
 use log::{Level, LevelFilter, Metadata, Record};
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
 use std::mem;
+use std::sync::Arc;
 
 #[cfg(feature = "regex")]
 #[path = "regex.rs"]
@@ -26,6 +28,8 @@ pub struct Directive {
 pub struct Filter {
     pub directives: Vec<Directive>, // 不同target 的日志级别
     pub filter: Option<inner::Filter>,
+    // 重新加载过滤规则时沿用原来的采样和限流
+    limiter: Arc<Limiter>,
 }
 
 impl Filter {
@@ -41,6 +45,8 @@ impl Filter {
     }
 
     /// Checks if this record matches the configured filter.
+    ///
+    /// Sampling and rate limits are not applied, see [`Filter::admit`].
     /// 判断日志过滤器是否可用
     pub fn matches(&self, record: &Record) -> bool {
         if !self.enabled(record.metadata()) {
@@ -57,6 +63,40 @@ impl Filter {
         true
     }
 
+    /// Checks if a record that [matches](Filter::matches) the filter passes
+    /// its sample and rate limit.
+    ///
+    /// Unlike `matches` this counts the record against the budget of its
+    /// callsite, call it once per record that is about to be written.
+    pub fn admit(&self, record: &Record) -> bool {
+        self.limiter.admit(record)
+    }
+
+    /// Returns the callsites whose records were dropped by a rate limit
+    /// since the last call, once their one second window is over.
+    ///
+    /// The logger writes a summary record for each of them.
+    pub fn take_suppressed(&self) -> Vec<Suppressed> {
+        self.limiter.take_suppressed()
+    }
+
+    /// Like [`Filter::take_suppressed`], without waiting for the windows to
+    /// be over. Used when the logger is flushed.
+    pub fn flush_suppressed(&self) -> Vec<Suppressed> {
+        self.limiter.flush_suppressed()
+    }
+
+    /// Whether any rate limit is configured.
+    pub fn is_rate_limited(&self) -> bool {
+        self.limiter.is_rate_limited()
+    }
+
+    /// Uses the samples and rate limits of `other`, keeping their windows.
+    pub(crate) fn with_limiter_of(mut self, other: &Filter) -> Filter {
+        self.limiter = other.limiter.clone();
+        self
+    }
+
     /// Determines if a log message with the specified metadata would be logged.
     pub fn enabled(&self, metadata: &Metadata) -> bool {
         let level = metadata.level();
@@ -93,6 +133,7 @@ impl fmt::Debug for Filter {
         f.debug_struct("Filter")
             .field("filter", &self.filter)
             .field("directives", &self.directives)
+            .field("limiter", &self.limiter)
             .finish()
     }
 }
@@ -104,6 +145,8 @@ impl fmt::Debug for Filter {
 pub struct Builder {
     directives: HashMap<Option<String>, LevelFilter>, //
     filter: Option<inner::Filter>,
+    rate_limits: Vec<RateLimit>,
+    samples: Vec<Sample>,
     built: bool,
 }
 
@@ -111,7 +154,13 @@ pub struct Builder {
 impl Builder {
     /// Initializes the filter builder with defaults.
     pub fn new() -> Builder {
-        Builder { directives: HashMap::new(), filter: None, built: false }
+        Builder {
+            directives: HashMap::new(),
+            filter: None,
+            rate_limits: Vec::new(),
+            samples: Vec::new(),
+            built: false,
+        }
     }
 }
 
@@ -145,6 +194,35 @@ impl Builder {
     }
 }
 
+/// 采样和限流
+impl Builder {
+    /// Writes at most `per_second` records per callsite (`file:line`) of the
+    /// given module, or of all modules if none is provided.
+    ///
+    /// The number of dropped records is reported in a summary record once
+    /// the second is over, by the logger every second and when it is
+    /// flushed.
+    pub fn rate_limit(
+        &mut self,
+        module: Option<&str>,
+        per_second: u32,
+    ) -> &mut Self {
+        let name = module.map(|s| s.to_string());
+        self.rate_limits.retain(|r| r.name != name);
+        self.rate_limits.push(RateLimit { name, per_second });
+        self
+    }
+
+    /// Keeps each record of the given module, or of all modules if none is
+    /// provided, with probability `ratio` between `0.0` and `1.0`.
+    pub fn sample(&mut self, module: Option<&str>, ratio: f64) -> &mut Self {
+        let name = module.map(|s| s.to_string());
+        self.samples.retain(|s| s.name != name);
+        self.samples.push(Sample { name, ratio });
+        self
+    }
+}
+
 /// 根据环境变量添加 directive
 impl Builder {
     /// Parses the directives string.
@@ -207,6 +285,10 @@ impl Builder {
         Filter {
             directives: mem::take(&mut directives),
             filter: mem::replace(&mut self.filter, None),
+            limiter: Arc::new(Limiter::new(
+                mem::take(&mut self.rate_limits),
+                mem::take(&mut self.samples),
+            )),
         }
     }
 }
@@ -225,6 +307,8 @@ impl fmt::Debug for Builder {
             f.debug_struct("Filter")
                 .field("filter", &self.filter)
                 .field("directives", &self.directives)
+                .field("rate_limits", &self.rate_limits)
+                .field("samples", &self.samples)
                 .finish()
         }
     }
@@ -0,0 +1,99 @@
+use super::{Builder, Filter, Suppressed};
+use log::{LevelFilter, Metadata, Record};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A shared, swappable [`Filter`].
+///
+/// The logger built by [`crate::Builder`] reads its filter through a
+/// handle, and [`crate::Builder::try_init_with_handle`] returns a clone of
+/// it, so the directives can be changed while the program is running.
+#[derive(Clone)]
+pub struct FilterHandle {
+    filter: Arc<RwLock<Filter>>,
+    // 全局日志对象中其它输出所需的最大级别，替换时需要同步更新 log::max_level
+    global: Option<LevelFilter>,
+}
+
+impl FilterHandle {
+    pub fn new(filter: Filter) -> Self {
+        FilterHandle { filter: Arc::new(RwLock::new(filter)), global: None }
+    }
+
+    /// A handle that also keeps `log::max_level` in sync with the filter,
+    /// never lowering it below the level needed by the other `sinks`.
+    pub fn global(&self, sinks: LevelFilter) -> Self {
+        FilterHandle { filter: self.filter.clone(), global: Some(sinks) }
+    }
+
+    /// Replace the directives with the ones parsed from `filters`, in the
+    /// same form as the `RUST_LOG` environment variable, including the
+    /// optional `/regex` message filter.
+    ///
+    /// The samples and rate limits of the current filter are kept.
+    pub fn parse(&self, filters: &str) {
+        let filter = Builder::new()
+            .parse(filters)
+            .build()
+            .with_limiter_of(&self.filter.read().unwrap());
+        self.set(filter);
+    }
+
+    /// Replace the filter, including its samples and rate limits.
+    pub fn set(&self, filter: Filter) {
+        let max_level = filter.filter();
+        *self.filter.write().unwrap() = filter;
+
+        if let Some(sinks) = self.global {
+            log::set_max_level(max_level.max(sinks));
+        }
+    }
+
+    /// Returns the maximum `LevelFilter` of the current filter.
+    pub fn filter(&self) -> LevelFilter {
+        self.filter.read().unwrap().filter()
+    }
+
+    /// Checks if this record matches the current filter.
+    pub fn matches(&self, record: &Record) -> bool {
+        self.filter.read().unwrap().matches(record)
+    }
+
+    /// Checks if this record passes the sample and rate limit of the current
+    /// filter, see [`Filter::admit`].
+    pub fn admit(&self, record: &Record) -> bool {
+        self.filter.read().unwrap().admit(record)
+    }
+
+    /// Returns the callsites suppressed by a rate limit of the current
+    /// filter, see [`Filter::take_suppressed`].
+    pub fn take_suppressed(&self) -> Vec<Suppressed> {
+        self.filter.read().unwrap().take_suppressed()
+    }
+
+    /// Returns every callsite suppressed by a rate limit of the current
+    /// filter, see [`Filter::flush_suppressed`].
+    pub fn flush_suppressed(&self) -> Vec<Suppressed> {
+        self.filter.read().unwrap().flush_suppressed()
+    }
+
+    /// Whether the current filter has a rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        self.filter.read().unwrap().is_rate_limited()
+    }
+
+    /// Determines if a log message with the specified metadata would be
+    /// logged by the current filter.
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.read().unwrap().enabled(metadata)
+    }
+}
+
+impl fmt::Debug for FilterHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FilterHandle")
+            .field("filter", &*self.filter.read().unwrap())
+            .field("global", &self.global)
+            .finish()
+    }
+}
@@ -0,0 +1,244 @@
+use log::Record;
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 限流统计窗口
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// At most `per_second` records per callsite of the targets starting with
+/// `name`, or of all targets if `name` is `None`.
+#[derive(Debug)]
+pub struct RateLimit {
+    pub name: Option<String>,
+    pub per_second: u32,
+}
+
+/// Keep records of the targets starting with `name` with probability
+/// `ratio`.
+#[derive(Debug)]
+pub struct Sample {
+    pub name: Option<String>,
+    pub ratio: f64,
+}
+
+/// Records dropped by a rate limit during the last window of a callsite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppressed {
+    pub target: String,
+    pub callsite: String,
+    pub count: u64,
+}
+
+impl fmt::Display for Suppressed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "suppressed {} messages from {}", self.count, self.callsite)
+    }
+}
+
+/// 调用位置，优先使用文件名和行号，否则使用 target
+type Callsite = (Cow<'static, str>, u32);
+
+#[derive(Debug)]
+struct Window {
+    target: String,
+    start: Instant,
+    count: u32,
+    suppressed: u64,
+}
+
+impl Window {
+    fn take_suppressed(&mut self, callsite: &Callsite) -> Option<Suppressed> {
+        if self.suppressed == 0 {
+            return None;
+        }
+
+        let callsite = match callsite {
+            (file, 0) => file.to_string(),
+            (file, line) => format!("{}:{}", file, line),
+        };
+        Some(Suppressed {
+            target: self.target.clone(),
+            callsite,
+            count: mem::take(&mut self.suppressed),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Windows {
+    callsites: HashMap<Callsite, Window>,
+    pending: Vec<Suppressed>,
+    last_sweep: Instant,
+}
+
+/// The rate limits and samples of a filter.
+pub struct Limiter {
+    rate_limits: Vec<RateLimit>,
+    samples: Vec<Sample>,
+    windows: Mutex<Windows>,
+    counter: AtomicU64,
+    random: RandomState,
+}
+
+impl Limiter {
+    pub fn new(
+        mut rate_limits: Vec<RateLimit>,
+        mut samples: Vec<Sample>,
+    ) -> Self {
+        // 与 directives 相同，按名称长度排序以便查找最长匹配
+        rate_limits
+            .sort_by_key(|r| r.name.as_ref().map(|n| n.len()).unwrap_or(0));
+        samples.sort_by_key(|s| s.name.as_ref().map(|n| n.len()).unwrap_or(0));
+
+        Limiter {
+            rate_limits,
+            samples,
+            windows: Mutex::new(Windows {
+                callsites: HashMap::new(),
+                pending: Vec::new(),
+                last_sweep: Instant::now(),
+            }),
+            counter: AtomicU64::new(0),
+            random: RandomState::new(),
+        }
+    }
+
+    /// Checks if the record passes the sample and rate limit of its target.
+    pub fn admit(&self, record: &Record) -> bool {
+        let target = record.target();
+
+        if let Some(sample) = longest_match(&self.samples, |s| &s.name, target)
+        {
+            if !self.sampled(sample.ratio) {
+                return false;
+            }
+        }
+
+        let limit = match longest_match(&self.rate_limits, |r| &r.name, target)
+        {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        let callsite: Callsite = match (record.file_static(), record.file()) {
+            (Some(file), _) => {
+                (Cow::Borrowed(file), record.line().unwrap_or(0))
+            }
+            (None, Some(file)) => {
+                (Cow::Owned(file.to_string()), record.line().unwrap_or(0))
+            }
+            (None, None) => (Cow::Owned(target.to_string()), 0),
+        };
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        let Windows { callsites, pending, .. } = &mut *windows;
+        let window =
+            callsites.entry(callsite.clone()).or_insert_with(|| Window {
+                target: target.to_string(),
+                start: now,
+                count: 0,
+                suppressed: 0,
+            });
+
+        if now.duration_since(window.start) >= WINDOW {
+            // 新窗口开始时汇报上个窗口被丢弃的数量
+            pending.extend(window.take_suppressed(&callsite));
+            window.start = now;
+            window.count = 0;
+        }
+
+        if window.count < limit.per_second {
+            window.count += 1;
+            true
+        } else {
+            window.suppressed += 1;
+            false
+        }
+    }
+
+    /// Returns the callsites whose records were suppressed in a finished
+    /// window, at most once per window.
+    pub fn take_suppressed(&self) -> Vec<Suppressed> {
+        if self.rate_limits.is_empty() {
+            return Vec::new();
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        if now.duration_since(windows.last_sweep) >= WINDOW {
+            windows.last_sweep = now;
+
+            let Windows { callsites, pending, .. } = &mut *windows;
+            for (callsite, window) in callsites.iter_mut() {
+                if now.duration_since(window.start) >= WINDOW {
+                    pending.extend(window.take_suppressed(callsite));
+                }
+            }
+        }
+
+        mem::take(&mut windows.pending)
+    }
+
+    /// Returns every callsite with suppressed records, including the ones
+    /// whose window is not over yet.
+    pub fn flush_suppressed(&self) -> Vec<Suppressed> {
+        let mut windows = self.windows.lock().unwrap();
+        let Windows { callsites, pending, .. } = &mut *windows;
+        for (callsite, window) in callsites.iter_mut() {
+            pending.extend(window.take_suppressed(callsite));
+        }
+
+        mem::take(pending)
+    }
+
+    /// Whether any rate limit is configured.
+    pub fn is_rate_limited(&self) -> bool {
+        !self.rate_limits.is_empty()
+    }
+
+    fn sampled(&self, ratio: f64) -> bool {
+        if ratio >= 1.0 {
+            return true;
+        }
+        if ratio <= 0.0 {
+            return false;
+        }
+
+        let mut hasher = self.random.build_hasher();
+        hasher.write_u64(self.counter.fetch_add(1, Ordering::Relaxed));
+        (hasher.finish() as f64 / u64::MAX as f64) < ratio
+    }
+}
+
+/// Finds the rule with the longest name the target starts with, the rules
+/// are assumed to be sorted by the length of their name.
+fn longest_match<'a, T, F>(
+    rules: &'a [T],
+    name: F,
+    target: &str,
+) -> Option<&'a T>
+where
+    F: Fn(&T) -> &Option<String>,
+{
+    rules.iter().rev().find(|rule| match name(rule) {
+        Some(name) => target.starts_with(&**name),
+        None => true,
+    })
+}
+
+impl fmt::Debug for Limiter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Limiter")
+            .field("rate_limits", &self.rate_limits)
+            .field("samples", &self.samples)
+            .finish()
+    }
+}
@@ -1,3 +1,9 @@
 mod filter;
+mod handle;
+mod limit;
+mod watch;
 
 pub use filter::{enabled, Builder, Directive, Filter};
+pub use handle::FilterHandle;
+pub use limit::Suppressed;
+pub use watch::{parse_filter_file, FilterWatcher};
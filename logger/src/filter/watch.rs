@@ -0,0 +1,185 @@
+use super::FilterHandle;
+use crate::env::DEFAULT_FILTER_ENV;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Keeps a filter in sync with a file until it is dropped.
+///
+/// Created by [`FilterHandle::watch`].
+#[derive(Debug)]
+pub struct FilterWatcher {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for FilterWatcher {
+    fn drop(&mut self) {
+        // 关闭通道后后台线程立即退出
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl FilterHandle {
+    /// Re-parse the directives from `path` whenever it changes.
+    ///
+    /// The file is either an env file, in which case the value of the
+    /// `RUST_LOG=` line is used, or a plain directives file whose
+    /// non-comment lines are joined with `,`. The modification time is
+    /// polled every `interval`.
+    pub fn watch<P: AsRef<Path>>(
+        &self,
+        path: P,
+        interval: Duration,
+    ) -> io::Result<FilterWatcher> {
+        self.watch_var(path, DEFAULT_FILTER_ENV, interval)
+    }
+
+    /// Like [`FilterHandle::watch`], reading the `var=` line of env files.
+    pub fn watch_var<P: AsRef<Path>>(
+        &self,
+        path: P,
+        var: &str,
+        interval: Duration,
+    ) -> io::Result<FilterWatcher> {
+        let handle = self.clone();
+        let path = path.as_ref().to_path_buf();
+        let var = var.to_string();
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        // 启动前先加载一次
+        let mut last = reload(&handle, &path, &var, None);
+
+        let thread = thread::Builder::new()
+            .name("logger-filter-watch".to_string())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(interval)
+                {
+                    last = reload(&handle, &path, &var, last);
+                }
+            })?;
+
+        Ok(FilterWatcher { stop: Some(stop), handle: Some(thread) })
+    }
+}
+
+/// Apply the file if it changed since `last`, returning its new
+/// modification time.
+fn reload(
+    handle: &FilterHandle,
+    path: &PathBuf,
+    var: &str,
+    last: Option<SystemTime>,
+) -> Option<SystemTime> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    if modified.is_none() || modified == last {
+        return last;
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            handle.parse(&parse_filter_file(&content, var));
+            modified
+        }
+        Err(e) => {
+            eprintln!(
+                "warning: failed to read log filters from {} - {}",
+                path.display(),
+                e
+            );
+            last
+        }
+    }
+}
+
+/// Extract the directives from the content of an env or directives file.
+///
+/// `var=value` lines win. Other assignments, i.e. `export` lines and
+/// `UPPER_CASE=value` lines, are skipped; what remains are directives.
+pub fn parse_filter_file(content: &str, var: &str) -> String {
+    let lines = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let mut directives = Vec::new();
+    for line in lines {
+        match assignment(line, var) {
+            Some((key, value)) if key == var => {
+                return unquote(value).to_string();
+            }
+            // 其它环境变量，例如 RUST_LOG_STYLE=always
+            Some(_) => {}
+            None => directives.push(line),
+        }
+    }
+
+    directives.join(",")
+}
+
+/// 把一行当作环境变量赋值，返回变量名和值
+fn assignment<'a>(line: &'a str, var: &str) -> Option<(&'a str, &'a str)> {
+    let exported = line.strip_prefix("export ");
+    let (key, value) = exported.unwrap_or(line).split_once('=')?;
+    let key = key.trim();
+    // 指令 app=debug 也带等号，环境变量名一般是大写
+    let is_var_name = key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key.chars().all(|c| {
+            c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'
+        });
+    if exported.is_some() || is_var_name || key == var {
+        Some((key, value.trim()))
+    } else {
+        None
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_env_file() {
+        let content =
+            "# logging\nOTHER=1\nexport RUST_LOG=\"info,app=debug\"\n";
+        assert_eq!(parse_filter_file(content, "RUST_LOG"), "info,app=debug");
+    }
+
+    #[test]
+    fn parse_directives_file() {
+        let content = "warn\n# noisy module\napp::db=trace\n\n";
+        assert_eq!(
+            parse_filter_file(content, "RUST_LOG"),
+            "warn,app::db=trace"
+        );
+    }
+
+    #[test]
+    fn parse_skips_other_variables() {
+        let content = "RUST_LOG_STYLE=always\nexport OTHER=1\napp=debug\n";
+        assert_eq!(parse_filter_file(content, "RUST_LOG"), "app=debug");
+
+        let content = "RUST_LOG_STYLE=always\nRUST_LOG=info\n";
+        assert_eq!(parse_filter_file(content, "RUST_LOG"), "info");
+        assert_eq!(parse_filter_file("my_log=warn\n", "my_log"), "warn");
+    }
+}
@@ -1,4 +1,8 @@
 use super::formatter::{FormatFn, Formatter};
+use super::json::JsonFormat;
+use super::log_format::LogFormat;
+use super::logfmt::{Key, LogfmtFormat, Quoted};
+use super::syslog::{JournaldFormat, Rfc3164Format, Rfc5424Format, Syslog};
 use super::time::TimestampPrecision;
 use crate::fmt::SubtleStyle;
 use std::fmt::Display;
@@ -18,6 +22,9 @@ pub struct Builder {
     pub format_indent: Option<usize>,
     pub custom_format: Option<FormatFn>,
     pub format_suffix: &'static str,
+    pub log_format: LogFormat,
+    pub format_key_values: Vec<(String, String)>,
+    pub syslog: Option<Syslog>,
     built: bool,
 }
 
@@ -31,6 +38,9 @@ impl Default for Builder {
             format_indent: Some(4),
             custom_format: None,
             format_suffix: "\n",
+            log_format: Default::default(),
+            format_key_values: Vec::new(),
+            syslog: None,
             built: false,
         }
     }
@@ -40,30 +50,88 @@ impl Builder {
     /// Convert the format into a callable function.
     ///
     /// If the `custom_format` is `Some`, then any `default_format` switches are ignored.
-    /// If the `custom_format` is `None`, then a default format is returned.
-    /// Any `default_format` switches set to `false` won't be written by the format.
+    /// If the `custom_format` is `None`, then the format selected by
+    /// `log_format` is returned.
+    /// Any `default_format` switches set to `false` won't be written by the
+    /// text format, the structured formats only honor the timestamp switch.
     pub fn build(&mut self) -> FormatFn {
         assert!(!self.built, "attempt to re-use consumed builder");
 
-        let built =
+        let mut built =
             mem::replace(self, Builder { built: true, ..Default::default() });
 
         if let Some(fmt) = built.custom_format {
             fmt
         } else {
-            Box::new(move |buf, record| {
-                let fmt = DefaultFormat {
-                    timestamp: built.format_timestamp,
-                    module_path: built.format_module_path,
-                    target: built.format_target,
-                    level: built.format_level,
-                    written_header_value: false,
-                    indent: built.format_indent,
-                    suffix: built.format_suffix,
-                    buf,
-                };
-
-                fmt.write(record)
+            // 只有 syslog 和 journald 格式需要查询主机名和程序名
+            let syslog = matches!(
+                built.log_format,
+                LogFormat::Rfc5424 | LogFormat::Rfc3164 | LogFormat::Journald
+            )
+            .then(|| built.syslog.take().unwrap_or_default());
+
+            Box::new(move |buf, record| match built.log_format {
+                LogFormat::Text => {
+                    let fmt = DefaultFormat {
+                        timestamp: built.format_timestamp,
+                        module_path: built.format_module_path,
+                        target: built.format_target,
+                        level: built.format_level,
+                        written_header_value: false,
+                        indent: built.format_indent,
+                        suffix: built.format_suffix,
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
+                LogFormat::Json => {
+                    let fmt = JsonFormat {
+                        timestamp: built.format_timestamp,
+                        key_values: &built.format_key_values,
+                        suffix: built.format_suffix,
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
+                LogFormat::Logfmt => {
+                    let fmt = LogfmtFormat {
+                        timestamp: built.format_timestamp,
+                        key_values: &built.format_key_values,
+                        suffix: built.format_suffix,
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
+                LogFormat::Rfc5424 => {
+                    let fmt = Rfc5424Format {
+                        timestamp: built.format_timestamp,
+                        syslog: syslog.as_ref().unwrap(),
+                        key_values: &built.format_key_values,
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
+                LogFormat::Rfc3164 => {
+                    let fmt = Rfc3164Format {
+                        syslog: syslog.as_ref().unwrap(),
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
+                LogFormat::Journald => {
+                    let fmt = JournaldFormat {
+                        syslog: syslog.as_ref().unwrap(),
+                        key_values: &built.format_key_values,
+                        buf,
+                    };
+
+                    fmt.write(record)
+                }
             })
         }
     }
@@ -200,10 +268,23 @@ impl<'a> DefaultFormat<'a> {
         }
     }
 
+    /// Write the structured fields of the record as ` key=value` pairs,
+    /// keys and values are escaped like logfmt.
+    pub fn write_key_values(&mut self, record: &Record) -> io::Result<()> {
+        let buf = &mut *self.buf;
+        crate::kv::for_each_with_context(record, |key, value| {
+            write!(buf, " {}={}", Key(key), Quoted(value))
+        })
+    }
+
     pub fn write_args(&mut self, record: &Record) -> io::Result<()> {
         match self.indent {
             // Fast path for no indentation
-            None => write!(self.buf, "{}{}", record.args(), self.suffix),
+            None => {
+                write!(self.buf, "{}", record.args())?;
+                self.write_key_values(record)?;
+                write!(self.buf, "{}", self.suffix)
+            }
 
             Some(indent_count) => {
                 // Create a wrapper around the buffer only if we have to actually indent the message
@@ -245,6 +326,7 @@ impl<'a> DefaultFormat<'a> {
                     write!(wrapper, "{}", record.args())?;
                 }
 
+                self.write_key_values(record)?;
                 write!(self.buf, "{}", self.suffix)?;
 
                 Ok(())
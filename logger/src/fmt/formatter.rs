@@ -11,6 +11,8 @@ use std::rc::Rc;
 pub struct Formatter {
     pub buf: Rc<RefCell<Buffer>>,
     pub write_style: WriteStyle,
+    // 创建缓存的 Writer，缓存的颜色设置只对该 Writer 有效
+    pub writer_id: usize,
 }
 
 impl Formatter {
@@ -18,6 +20,7 @@ impl Formatter {
         Formatter {
             buf: Rc::new(RefCell::new(writer.buffer())),
             write_style: writer.write_style(),
+            writer_id: writer.id(),
         }
     }
 
@@ -25,6 +28,11 @@ impl Formatter {
         self.write_style
     }
 
+    /// The id of the writer the buffer was created for.
+    pub fn writer_id(&self) -> usize {
+        self.writer_id
+    }
+
     pub fn print(&self, writer: &Writer) -> io::Result<()> {
         writer.print(&self.buf.borrow())
     }
@@ -0,0 +1,176 @@
+use super::formatter::Formatter;
+use super::time::{timestamp, TimestampPrecision};
+use log::kv;
+use log::Record;
+use std::borrow::Cow;
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+
+/// One JSON object per record.
+///
+/// Every object carries `timestamp`, `level`, `target`, `module_path`,
+/// `file`, `line` and `message`, followed by the key/values of the record and
+/// the configured key/values as top-level fields. Missing locations are
+/// written as `null` so the shape of the object never changes.
+///
+/// Keys are unique within an object: key/values named like a built-in field
+/// are written as `fields.<key>`, e.g. `fields.level`, and a key is only
+/// written once, the record wins over the context and the configured
+/// key/values.
+pub struct JsonFormat<'a> {
+    pub timestamp: Option<TimestampPrecision>,
+    pub key_values: &'a [(String, String)],
+    pub suffix: &'a str,
+    pub buf: &'a mut Formatter,
+}
+
+impl<'a> JsonFormat<'a> {
+    pub fn write(mut self, record: &Record) -> io::Result<()> {
+        write!(self.buf, "{{")?;
+        match timestamp(self.buf, self.timestamp) {
+            Some(ts) => self.write_field("timestamp", ts, false)?,
+            None => self.write_null("timestamp", false)?,
+        }
+        self.write_field("level", record.level(), true)?;
+        self.write_field("target", record.target(), true)?;
+        match record.module_path() {
+            Some(module_path) => {
+                self.write_field("module_path", module_path, true)?
+            }
+            None => self.write_null("module_path", true)?,
+        }
+        match record.file() {
+            Some(file) => self.write_field("file", file, true)?,
+            None => self.write_null("file", true)?,
+        }
+        match record.line() {
+            Some(line) => write!(self.buf, ",\"line\":{}", line)?,
+            None => self.write_null("line", true)?,
+        }
+        self.write_field("message", record.args(), true)?;
+
+        let mut keys = Vec::new();
+        let buf = &mut *self.buf;
+        crate::kv::for_each_with_context(record, |key, value| {
+            if let Some(key) = unique_key(&mut keys, key) {
+                write!(buf, ",{}:", Escaped(key))?;
+                write_value(buf, value)?;
+            }
+            Ok(())
+        })?;
+
+        for (key, value) in self.key_values {
+            if let Some(key) = unique_key(&mut keys, key) {
+                self.write_field(&key, value, true)?;
+            }
+        }
+
+        write!(self.buf, "}}{}", self.suffix)
+    }
+
+    fn write_key(&mut self, key: &str, comma: bool) -> io::Result<()> {
+        if comma {
+            write!(self.buf, ",")?;
+        }
+        write!(self.buf, "{}:", Escaped(key))
+    }
+
+    fn write_field<T>(
+        &mut self,
+        key: &str,
+        value: T,
+        comma: bool,
+    ) -> io::Result<()>
+    where
+        T: fmt::Display,
+    {
+        self.write_key(key, comma)?;
+        write!(self.buf, "{}", Escaped(value))
+    }
+
+    fn write_null(&mut self, key: &str, comma: bool) -> io::Result<()> {
+        self.write_key(key, comma)?;
+        write!(self.buf, "null")
+    }
+}
+
+/// The fields every object starts with.
+const BUILT_IN_KEYS: &[&str] =
+    &["timestamp", "level", "target", "module_path", "file", "line", "message"];
+
+/// 与内置字段同名的键加上 `fields.` 前缀，已经写过的键返回 None
+fn unique_key<'k>(keys: &mut Vec<String>, key: &'k str) -> Option<Cow<'k, str>> {
+    let key = if BUILT_IN_KEYS.contains(&key) {
+        Cow::Owned(format!("fields.{}", key))
+    } else {
+        Cow::Borrowed(key)
+    };
+    if keys.iter().any(|k| *k == key) {
+        return None;
+    }
+    keys.push(key.to_string());
+    Some(key)
+}
+
+/// Numbers and booleans are written as JSON literals, everything else as a
+/// string.
+fn write_value(buf: &mut Formatter, value: &kv::Value) -> io::Result<()> {
+    if let Some(v) = value.to_bool() {
+        write!(buf, "{}", v)
+    } else if let Some(v) = value.to_i64() {
+        write!(buf, "{}", v)
+    } else if let Some(v) = value.to_u64() {
+        write!(buf, "{}", v)
+    } else if let Some(v) = value.to_f64().filter(|v| v.is_finite()) {
+        write!(buf, "{}", v)
+    } else {
+        write!(buf, "{}", Escaped(value))
+    }
+}
+
+/// A `Display` value written as a quoted and escaped JSON string.
+pub struct Escaped<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Escaper<'a, 'b>(&'a mut fmt::Formatter<'b>);
+
+        impl<'a, 'b> fmt::Write for Escaper<'a, 'b> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                for c in s.chars() {
+                    match c {
+                        '"' => self.0.write_str("\\\"")?,
+                        '\\' => self.0.write_str("\\\\")?,
+                        '\n' => self.0.write_str("\\n")?,
+                        '\r' => self.0.write_str("\\r")?,
+                        '\t' => self.0.write_str("\\t")?,
+                        // 其他控制字符使用 unicode 转义
+                        c if c.is_control() => {
+                            write!(self.0, "\\u{:04x}", c as u32)?
+                        }
+                        c => self.0.write_char(c)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        f.write_char('"')?;
+        write!(Escaper(f), "{}", self.0)?;
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_json_string() {
+        assert_eq!(Escaped("plain").to_string(), "\"plain\"");
+        assert_eq!(
+            Escaped("a \"quote\"\n\\ \u{1}").to_string(),
+            "\"a \\\"quote\\\"\\n\\\\ \\u0001\""
+        );
+    }
+}
@@ -0,0 +1,60 @@
+/// The layout of every log line written by the built-in formats.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum LogFormat {
+    /// The human readable `[ts LEVEL target] msg` layout.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// One line of `key=value` pairs, as used by logfmt.
+    Logfmt,
+    /// RFC 5424 syslog messages.
+    Rfc5424,
+    /// RFC 3164 (BSD) syslog messages.
+    Rfc3164,
+    /// The journald native protocol.
+    Journald,
+}
+
+/// 将字符串转换为 LogFormat
+pub fn parse_log_format(spec: &str) -> LogFormat {
+    match spec {
+        "text" => LogFormat::Text,
+        "json" => LogFormat::Json,
+        "logfmt" => LogFormat::Logfmt,
+        "rfc5424" => LogFormat::Rfc5424,
+        "rfc3164" => LogFormat::Rfc3164,
+        "journald" => LogFormat::Journald,
+        _ => Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_log_format_valid() {
+        let inputs = vec![
+            ("text", LogFormat::Text),
+            ("json", LogFormat::Json),
+            ("logfmt", LogFormat::Logfmt),
+            ("rfc5424", LogFormat::Rfc5424),
+            ("rfc3164", LogFormat::Rfc3164),
+            ("journald", LogFormat::Journald),
+        ];
+
+        for (input, expected) in inputs {
+            assert_eq!(expected, parse_log_format(input));
+        }
+    }
+
+    #[test]
+    fn parse_log_format_invalid() {
+        let inputs = vec!["", "JSON", "yaml"];
+
+        for input in inputs {
+            assert_eq!(LogFormat::Text, parse_log_format(input));
+        }
+    }
+}
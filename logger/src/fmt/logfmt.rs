@@ -0,0 +1,127 @@
+use super::formatter::Formatter;
+use super::time::{timestamp, TimestampPrecision};
+use log::Record;
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+
+/// One line of space separated `key=value` pairs per record.
+///
+/// The line starts with `ts`, `level`, `target`, `module_path`, `file`,
+/// `line` and `msg`, followed by the key/values of the record and the
+/// configured key/values. Values that contain spaces, quotes, `=` or
+/// control characters are quoted and escaped. Keys cannot be quoted, so
+/// those characters are replaced with `_` in keys.
+pub struct LogfmtFormat<'a> {
+    pub timestamp: Option<TimestampPrecision>,
+    pub key_values: &'a [(String, String)],
+    pub suffix: &'a str,
+    pub buf: &'a mut Formatter,
+}
+
+impl<'a> LogfmtFormat<'a> {
+    pub fn write(mut self, record: &Record) -> io::Result<()> {
+        if let Some(ts) = timestamp(self.buf, self.timestamp) {
+            write!(self.buf, "ts={} ", ts)?;
+        }
+        write!(self.buf, "level={}", record.level())?;
+        self.write_pair("target", record.target())?;
+        if let Some(module_path) = record.module_path() {
+            self.write_pair("module_path", module_path)?;
+        }
+        if let Some(file) = record.file() {
+            self.write_pair("file", file)?;
+        }
+        if let Some(line) = record.line() {
+            self.write_pair("line", line)?;
+        }
+        self.write_pair("msg", record.args())?;
+
+        let buf = &mut *self.buf;
+        crate::kv::for_each_with_context(record, |key, value| {
+            write!(buf, " {}={}", Key(key), Quoted(value))
+        })?;
+
+        for (key, value) in self.key_values {
+            self.write_pair(key, value)?;
+        }
+
+        write!(self.buf, "{}", self.suffix)
+    }
+
+    fn write_pair<T>(&mut self, key: &str, value: T) -> io::Result<()>
+    where
+        T: fmt::Display,
+    {
+        write!(self.buf, " {}={}", Key(key), Quoted(value))
+    }
+}
+
+/// A key with the characters logfmt does not allow in keys replaced by `_`.
+pub struct Key<'a>(pub &'a str);
+
+impl fmt::Display for Key<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_char('_');
+        }
+        for c in self.0.chars() {
+            if matches!(c, ' ' | '=' | '"' | '\\') || c.is_control() {
+                f.write_char('_')?;
+            } else {
+                f.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `Display` value quoted only when logfmt requires it.
+pub struct Quoted<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Quoted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.0.to_string();
+        let needs_quotes = value.is_empty()
+            || value.chars().any(|c| {
+                c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control()
+            });
+        if !needs_quotes {
+            return f.write_str(&value);
+        }
+
+        f.write_char('"')?;
+        for c in value.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_only_when_needed() {
+        assert_eq!(Quoted("plain").to_string(), "plain");
+        assert_eq!(Quoted("").to_string(), "\"\"");
+        assert_eq!(Quoted("a b").to_string(), "\"a b\"");
+        assert_eq!(Quoted("k=\"v\"\n").to_string(), "\"k=\\\"v\\\"\\n\"");
+        assert_eq!(Quoted("a\x1bb").to_string(), "\"a\\u001bb\"");
+    }
+
+    #[test]
+    fn sanitize_keys() {
+        assert_eq!(Key("user_id").to_string(), "user_id");
+        assert_eq!(Key("a b=\"c\"\n").to_string(), "a_b__c__");
+        assert_eq!(Key("").to_string(), "_");
+    }
+}
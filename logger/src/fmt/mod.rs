@@ -1,10 +1,20 @@
 mod builder;
 mod formatter;
 mod humantime;
+mod json;
+mod log_format;
+mod logfmt;
+mod syslog;
 mod time;
 pub mod writer;
 
 pub use builder::{Builder, DefaultFormat};
 pub use formatter::{FormatFn, Formatter};
+pub use json::JsonFormat;
+pub use log_format::{parse_log_format, LogFormat};
+pub use logfmt::LogfmtFormat;
+pub use syslog::{
+    Facility, JournaldFormat, Rfc3164Format, Rfc5424Format, Severity, Syslog,
+};
 pub use time::TimestampPrecision;
 pub use writer::*;
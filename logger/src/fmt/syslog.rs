@@ -0,0 +1,386 @@
+use super::formatter::Formatter;
+use super::time::TimestampPrecision;
+use chrono::{Local, SecondsFormat, Utc};
+use log::Record;
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+
+/// Syslog facility of the messages, see RFC 5424 section 6.2.1.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Facility {
+    Kern = 0,
+    #[default]
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// Syslog severity of a message, also used as the journald `PRIORITY`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    Emergency = 0,
+    Alert = 1,
+    Critical = 2,
+    Error = 3,
+    Warning = 4,
+    Notice = 5,
+    Informational = 6,
+    Debug = 7,
+}
+
+impl From<log::Level> for Severity {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => Severity::Error,
+            log::Level::Warn => Severity::Warning,
+            log::Level::Info => Severity::Informational,
+            log::Level::Debug | log::Level::Trace => Severity::Debug,
+        }
+    }
+}
+
+impl From<crate::Level> for Severity {
+    fn from(level: crate::Level) -> Self {
+        log::Level::from(level).into()
+    }
+}
+
+/// The header fields of syslog and journald messages.
+///
+/// The host name and the application name default to the name of this
+/// machine and of the running executable.
+#[derive(Clone, Debug)]
+pub struct Syslog {
+    pub facility: Facility,
+    pub hostname: String,
+    pub app_name: String,
+    pub pid: u32,
+}
+
+impl Default for Syslog {
+    fn default() -> Self {
+        Syslog {
+            facility: Default::default(),
+            hostname: hostname(),
+            app_name: app_name(),
+            pid: std::process::id(),
+        }
+    }
+}
+
+impl Syslog {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    pub fn hostname<T: Into<String>>(mut self, hostname: T) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
+    pub fn app_name<T: Into<String>>(mut self, app_name: T) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// The `PRI` part of a message.
+    fn priority(&self, record: &Record) -> u8 {
+        (self.facility as u8) * 8 + Severity::from(record.level()) as u8
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> String {
+    nix::unistd::gethostname()
+        .ok()
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+#[cfg(not(unix))]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "localhost".to_string())
+}
+
+fn app_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem().map(|s| s.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// An RFC 5424 message per record.
+///
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG`, the target is
+/// used as `MSGID` and the key/values are written as the structured data
+/// element `fields@32473`. The suffix is not written, every record is sent as
+/// one message.
+pub struct Rfc5424Format<'a> {
+    pub timestamp: Option<TimestampPrecision>,
+    pub syslog: &'a Syslog,
+    pub key_values: &'a [(String, String)],
+    pub buf: &'a mut Formatter,
+}
+
+impl<'a> Rfc5424Format<'a> {
+    pub fn write(self, record: &Record) -> io::Result<()> {
+        let syslog = self.syslog;
+        write!(self.buf, "<{}>1 ", syslog.priority(record))?;
+        match self.timestamp {
+            Some(precision) => {
+                let format = match precision {
+                    TimestampPrecision::Seconds => SecondsFormat::Secs,
+                    TimestampPrecision::Millis => SecondsFormat::Millis,
+                    TimestampPrecision::Micros => SecondsFormat::Micros,
+                    TimestampPrecision::Nanos => SecondsFormat::Nanos,
+                };
+                write!(
+                    self.buf,
+                    "{}",
+                    Utc::now().to_rfc3339_opts(format, true)
+                )?
+            }
+            None => write!(self.buf, "-")?,
+        }
+        write!(
+            self.buf,
+            " {} {} {} {} ",
+            Header(&syslog.hostname, 255),
+            Header(&syslog.app_name, 48),
+            syslog.pid,
+            Header(record.target(), 32)
+        )?;
+
+        let mut params = Vec::new();
+        crate::kv::for_each_with_context(record, |key, value| {
+            params.push((key.to_string(), value.to_string()));
+            Ok(())
+        })?;
+        params.extend(self.key_values.iter().cloned());
+
+        if params.is_empty() {
+            write!(self.buf, "-")?;
+        } else {
+            write!(self.buf, "[fields@32473")?;
+            for (key, value) in &params {
+                write!(
+                    self.buf,
+                    " {}=\"{}\"",
+                    ParamName(key),
+                    ParamValue(value)
+                )?;
+            }
+            write!(self.buf, "]")?;
+        }
+
+        write!(self.buf, " {}", record.args())
+    }
+}
+
+/// An RFC 3164 message per record.
+///
+/// `<PRI>Mmm dd hh:mm:ss HOSTNAME APP-NAME[PID]: MSG`, in local time as the
+/// format has no time zone. The suffix is not written.
+pub struct Rfc3164Format<'a> {
+    pub syslog: &'a Syslog,
+    pub buf: &'a mut Formatter,
+}
+
+impl<'a> Rfc3164Format<'a> {
+    pub fn write(self, record: &Record) -> io::Result<()> {
+        let syslog = self.syslog;
+        write!(
+            self.buf,
+            "<{}>{} {} {}[{}]: {}",
+            syslog.priority(record),
+            Local::now().format("%b %e %H:%M:%S"),
+            Header(&syslog.hostname, 255),
+            Header(&syslog.app_name, 32),
+            syslog.pid,
+            record.args()
+        )
+    }
+}
+
+/// The journald native protocol, one datagram of `KEY=value` fields per
+/// record.
+///
+/// Writes `PRIORITY`, `SYSLOG_FACILITY`, `SYSLOG_IDENTIFIER`, `SYSLOG_PID`,
+/// `MESSAGE`, `TARGET`, `CODE_MODULE`, `CODE_FILE` and `CODE_LINE`, then the
+/// key/values with upper cased names. Values containing a newline use the
+/// length prefixed binary form.
+pub struct JournaldFormat<'a> {
+    pub syslog: &'a Syslog,
+    pub key_values: &'a [(String, String)],
+    pub buf: &'a mut Formatter,
+}
+
+impl<'a> JournaldFormat<'a> {
+    pub fn write(mut self, record: &Record) -> io::Result<()> {
+        let syslog = self.syslog;
+        self.write_field("PRIORITY", Severity::from(record.level()) as u8)?;
+        self.write_field("SYSLOG_FACILITY", syslog.facility as u8)?;
+        self.write_field("SYSLOG_IDENTIFIER", &syslog.app_name)?;
+        self.write_field("SYSLOG_PID", syslog.pid)?;
+        self.write_field("MESSAGE", record.args())?;
+        self.write_field("TARGET", record.target())?;
+        if let Some(module_path) = record.module_path() {
+            self.write_field("CODE_MODULE", module_path)?;
+        }
+        if let Some(file) = record.file() {
+            self.write_field("CODE_FILE", file)?;
+        }
+        if let Some(line) = record.line() {
+            self.write_field("CODE_LINE", line)?;
+        }
+
+        let mut fields = Vec::new();
+        crate::kv::for_each_with_context(record, |key, value| {
+            fields.push((key.to_string(), value.to_string()));
+            Ok(())
+        })?;
+        for (key, value) in fields.iter().chain(self.key_values) {
+            if let Some(name) = field_name(key) {
+                self.write_field(&name, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_field<T: fmt::Display>(
+        &mut self,
+        name: &str,
+        value: T,
+    ) -> io::Result<()> {
+        let value = value.to_string();
+        if value.contains('\n') {
+            writeln!(self.buf, "{}", name)?;
+            self.buf.write_all(&(value.len() as u64).to_le_bytes())?;
+            writeln!(self.buf, "{}", value)
+        } else {
+            writeln!(self.buf, "{}={}", name, value)
+        }
+    }
+}
+
+/// Journald field names only contain `A-Z`, `0-9` and `_`, and must not
+/// start with `_` or a digit.
+fn field_name(key: &str) -> Option<String> {
+    let name: String = key
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ ('A'..='Z' | '0'..='9') => c,
+            _ => '_',
+        })
+        .collect();
+    let name =
+        name.trim_start_matches(|c: char| c == '_' || c.is_ascii_digit());
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// A header field, printable ASCII without spaces truncated to `max` bytes,
+/// `-` if empty.
+struct Header<'a>(&'a str, usize);
+
+impl<'a> fmt::Display for Header<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut written = 0;
+        for c in self.0.chars().filter(|c| c.is_ascii_graphic()).take(self.1) {
+            f.write_char(c)?;
+            written += 1;
+        }
+        if written == 0 {
+            f.write_char('-')?;
+        }
+        Ok(())
+    }
+}
+
+/// A structured data parameter name, printable ASCII truncated to 32 bytes
+/// with `=`, `]` and `"` replaced by `_`, `-` if empty.
+struct ParamName<'a>(&'a str);
+
+impl<'a> fmt::Display for ParamName<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut written = 0;
+        for c in self.0.chars().filter(|c| c.is_ascii_graphic()).take(32) {
+            match c {
+                '=' | ']' | '"' => f.write_char('_')?,
+                c => f.write_char(c)?,
+            }
+            written += 1;
+        }
+        if written == 0 {
+            f.write_char('-')?;
+        }
+        Ok(())
+    }
+}
+
+/// A structured data parameter value, escaping `"`, `\` and `]`.
+struct ParamValue<'a>(&'a str);
+
+impl<'a> fmt::Display for ParamValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            if matches!(c, '"' | '\\' | ']') {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journald_field_name() {
+        assert_eq!(field_name("user_id").as_deref(), Some("USER_ID"));
+        assert_eq!(
+            field_name("_1http.status").as_deref(),
+            Some("HTTP_STATUS")
+        );
+        assert_eq!(field_name("__"), None);
+    }
+
+    #[test]
+    fn header_field() {
+        assert_eq!(Header("my app", 48).to_string(), "myapp");
+        assert_eq!(Header("", 48).to_string(), "-");
+        assert_eq!(Header("abcdef", 3).to_string(), "abc");
+    }
+
+    #[test]
+    fn param_name() {
+        assert_eq!(ParamName("user_id").to_string(), "user_id");
+        assert_eq!(ParamName("a=b ]\"c").to_string(), "a_b__c");
+        assert_eq!(ParamName(" ").to_string(), "-");
+        assert_eq!(ParamName(&"k".repeat(40)).to_string(), "k".repeat(32));
+    }
+}
@@ -1,3 +1,6 @@
+use super::Formatter;
+use std::fmt;
+
 /// Formatting precision of timestamps.
 ///
 /// Seconds give precision of full seconds, milliseconds give thousands of a
@@ -21,3 +24,25 @@ impl Default for TimestampPrecision {
         TimestampPrecision::Seconds
     }
 }
+
+/// The current time in the given precision, if timestamps are enabled.
+pub fn timestamp(
+    buf: &Formatter,
+    precision: Option<TimestampPrecision>,
+) -> Option<impl fmt::Display> {
+    #[cfg(feature = "humantime")]
+    {
+        use TimestampPrecision::*;
+        precision.map(|precision| match precision {
+            Seconds => buf.timestamp_seconds(),
+            Millis => buf.timestamp_millis(),
+            Micros => buf.timestamp_micros(),
+            Nanos => buf.timestamp_nanos(),
+        })
+    }
+    #[cfg(not(feature = "humantime"))]
+    {
+        let _ = (buf, precision);
+        None::<&str>
+    }
+}
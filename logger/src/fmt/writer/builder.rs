@@ -1,6 +1,9 @@
-use super::{is_stderr, is_stdout, BufferWriter, Writer};
+use super::non_blocking::Worker;
+use super::{is_stderr, is_stdout, BufferWriter, NonBlocking, Writer};
 use super::{parse_write_style, Target, WritableTarget, WriteStyle};
 use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// A builder for a terminal writer.
 ///
@@ -9,6 +12,7 @@ use std::mem;
 pub struct Builder {
     target: WritableTarget,
     write_style: WriteStyle,
+    non_blocking: Option<NonBlocking>,
     is_test: bool,
     built: bool,
 }
@@ -19,6 +23,7 @@ impl Builder {
         Builder {
             target: Default::default(),
             write_style: Default::default(),
+            non_blocking: None,
             is_test: false,
             built: false,
         }
@@ -51,6 +56,12 @@ impl Builder {
         self
     }
 
+    /// Write records from a background thread instead of the logging thread.
+    pub fn non_blocking(&mut self, non_blocking: NonBlocking) -> &mut Self {
+        self.non_blocking = Some(non_blocking);
+        self
+    }
+
     /// Build a terminal writer.
     pub fn build(&mut self) -> Writer {
         assert!(!self.built, "attempt to re-use consumed builder");
@@ -61,7 +72,9 @@ impl Builder {
                 if match &self.target {
                     WritableTarget::Stderr => is_stderr(),
                     WritableTarget::Stdout => is_stdout(),
-                    WritableTarget::Pipe(_) => false,
+                    WritableTarget::Pipe(_)
+                    | WritableTarget::File(_)
+                    | WritableTarget::Socket(_) => false,
                 } {
                     WriteStyle::Auto
                 } else {
@@ -81,9 +94,27 @@ impl Builder {
             WritableTarget::Pipe(pipe) => {
                 BufferWriter::pipe(color_choice, pipe)
             }
+            WritableTarget::File(file) => {
+                BufferWriter::file(color_choice, file)
+            }
+            WritableTarget::Socket(socket) => {
+                BufferWriter::socket(color_choice, socket)
+            }
         };
 
-        Writer { inner: writer, write_style: self.write_style }
+        let inner = Arc::new(writer);
+        let worker = self
+            .non_blocking
+            .map(|config| Worker::spawn(config, inner.clone()));
+
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        Writer {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            inner,
+            write_style: self.write_style,
+            worker,
+        }
     }
 }
 
@@ -0,0 +1,29 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Gzip `path` into `<path>.gz` and remove the original.
+#[cfg(feature = "gzip")]
+pub fn compress(path: &Path) -> io::Result<PathBuf> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs::{self, File};
+
+    let mut target = path.as_os_str().to_owned();
+    target.push(".gz");
+    let target = PathBuf::from(target);
+
+    let mut input = File::open(path)?;
+    let mut encoder =
+        GzEncoder::new(File::create(&target)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)?;
+
+    Ok(target)
+}
+
+/// Without the `gzip` feature rotated segments are kept uncompressed.
+#[cfg(not(feature = "gzip"))]
+pub fn compress(path: &Path) -> io::Result<PathBuf> {
+    Ok(path.to_path_buf())
+}
@@ -0,0 +1,11 @@
+// 滚动日志文件
+mod compress;
+mod rolling;
+mod rotation;
+mod sighup;
+mod target;
+
+pub use rolling::RollingFile;
+pub use rotation::Rotation;
+pub use sighup::request_reopen;
+pub use target::FileTarget;
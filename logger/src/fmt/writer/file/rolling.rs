@@ -0,0 +1,256 @@
+use super::compress::compress;
+use super::rotation::is_segment_suffix;
+use super::sighup;
+use super::FileTarget;
+use chrono::{DateTime, Local};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+/// A log file that rotates itself according to its [`FileTarget`].
+///
+/// Rotated segments are renamed to `<file name>.<period>` next to the active
+/// file, e.g. `app.log.2023-05-01` for daily rotation, and gzipped to
+/// `app.log.2023-05-01.gz` when compression is enabled.
+///
+/// Compression runs on a background thread so that writers are not blocked
+/// while a large segment is gzipped; its errors are printed to stderr.
+/// `flush` and dropping the file wait for it to finish.
+pub struct RollingFile {
+    target: FileTarget,
+    file: Option<File>,
+    size: u64,                           // 当前文件的大小
+    opened: DateTime<Local>,             // 当前文件的创建时间
+    generation: usize,                   // 已处理的 SIGHUP 次数
+    compressing: Option<JoinHandle<()>>, // 正在压缩上一个分段的线程
+}
+
+impl RollingFile {
+    /// Create the writer, the file itself is opened on the first write.
+    pub fn new(target: FileTarget) -> Self {
+        if target.reopen_on_sighup {
+            if let Err(e) = sighup::install() {
+                eprintln!("warning: failed to install SIGHUP handler - {}", e);
+            }
+        }
+
+        RollingFile {
+            target,
+            file: None,
+            size: 0,
+            opened: Local::now(),
+            generation: sighup::generation(),
+            compressing: None,
+        }
+    }
+
+    /// The path of the active log file.
+    pub fn path(&self) -> &Path {
+        self.target.path()
+    }
+
+    /// Close the active file, it is opened again on the next write.
+    pub fn reopen(&mut self) {
+        self.file = None;
+    }
+
+    /// Close the active file and move it aside as a rotated segment.
+    pub fn rotate(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            file.sync_all()?;
+        }
+
+        if fs::metadata(self.path()).map(|m| m.len() == 0).unwrap_or(true) {
+            return Ok(());
+        }
+
+        let segment = self.segment_path();
+        fs::rename(self.path(), &segment)?;
+        if !self.target.compress {
+            return prune(self.path(), self.target.max_files);
+        }
+
+        // 一次只压缩一个分段，之后再清理，保证 max_files 计入压缩后的文件
+        self.wait_compression();
+        let path = self.path().to_path_buf();
+        let max_files = self.target.max_files;
+        let handle = thread::Builder::new()
+            .name("logger-compress".to_string())
+            .spawn(move || {
+                if let Err(e) =
+                    compress(&segment).and_then(|_| prune(&path, max_files))
+                {
+                    eprintln!(
+                        "warning: failed to compress {} - {}",
+                        segment.display(),
+                        e
+                    );
+                }
+            })?;
+        self.compressing = Some(handle);
+        Ok(())
+    }
+
+    /// Wait until the segment being compressed in the background is done.
+    fn wait_compression(&mut self) {
+        if let Some(handle) = self.compressing.take() {
+            let _ = handle.join();
+        }
+    }
+
+    fn open(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            let path = self.target.path.clone();
+            if let Some(parent) = path.parent() {
+                if !parent.as_os_str().is_empty() {
+                    fs::create_dir_all(parent)?;
+                }
+            }
+
+            let file =
+                OpenOptions::new().create(true).append(true).open(&path)?;
+            let metadata = file.metadata()?;
+            self.size = metadata.len();
+            // 已有内容的文件以最后修改时间作为所属周期，进程重启后也能按时滚动
+            self.opened = match metadata.modified() {
+                Ok(modified) if self.size > 0 => modified.into(),
+                _ => Local::now(),
+            };
+            self.file = Some(file);
+        }
+
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    /// Whether the active file has to be rotated before `incoming` bytes are
+    /// written to it.
+    fn needs_rotation(&self, now: &DateTime<Local>, incoming: u64) -> bool {
+        let rotation = self.target.rotation;
+        rotation.period(now) != rotation.period(&self.opened)
+            || rotation.exceeds(self.size, incoming)
+    }
+
+    /// A free path for the segment that is being rotated out.
+    fn segment_path(&self) -> PathBuf {
+        let base = self.sibling(&self.target.rotation.suffix(&self.opened));
+
+        let mut candidate = base.clone();
+        let mut index = 0;
+        while candidate.exists() || with_extension(&candidate, ".gz").exists()
+        {
+            index += 1;
+            candidate = with_extension(&base, &format!(".{}", index));
+        }
+        candidate
+    }
+
+    /// `<dir>/<file name>.<suffix>`
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self.file_name();
+        name.push(".");
+        name.push(suffix);
+        self.path().with_file_name(name)
+    }
+
+    fn file_name(&self) -> OsString {
+        self.path().file_name().map(|n| n.to_os_string()).unwrap_or_default()
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.target.reopen_on_sighup {
+            let generation = sighup::generation();
+            if generation != self.generation {
+                self.generation = generation;
+                self.reopen();
+            }
+        }
+
+        // 打开文件后才知道文件大小和所属周期
+        self.open()?;
+
+        let now = Local::now();
+        if self.needs_rotation(&now, buf.len() as u64) {
+            self.rotate()?;
+            self.open()?;
+        }
+
+        let file = self.open()?;
+        file.write_all(buf)?;
+        self.size += buf.len() as u64;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.wait_compression();
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RollingFile {
+    fn drop(&mut self) {
+        self.wait_compression();
+    }
+}
+
+impl fmt::Debug for RollingFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RollingFile")
+            .field("target", &self.target)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// Remove the oldest rotated segments of `path` beyond `max_files`, other
+/// files next to the active one are left alone.
+fn prune(path: &Path, max_files: Option<usize>) -> io::Result<()> {
+    let max_files = match max_files {
+        Some(max_files) => max_files,
+        None => return Ok(()),
+    };
+
+    let mut prefix =
+        path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    prefix.push(".");
+    let prefix = prefix.to_string_lossy().into_owned();
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        match name.strip_prefix(&prefix) {
+            Some(suffix) if is_segment_suffix(suffix) => {}
+            _ => continue,
+        }
+        let modified = entry.metadata()?.modified()?;
+        segments.push((modified, entry.path()));
+    }
+
+    // 按修改时间从新到旧排序，删除多余的旧文件
+    segments.sort_by(|a, b| b.cmp(a));
+    for (_, path) in segments.into_iter().skip(max_files) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(extension);
+    PathBuf::from(path)
+}
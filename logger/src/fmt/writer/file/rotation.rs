@@ -0,0 +1,120 @@
+use chrono::{DateTime, Local};
+
+/// When the active log file is closed and a new segment is started.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rotation {
+    /// Never rotate, the file grows forever.
+    #[default]
+    Never,
+    /// Rotate once the active file reaches the given size in bytes.
+    Size(u64),
+    /// Rotate at the start of every local hour.
+    Hourly,
+    /// Rotate at local midnight.
+    Daily,
+}
+
+impl Rotation {
+    /// The calendar period `time` belongs to.
+    ///
+    /// Two timestamps in the same period yield the same key, so a change
+    /// of key means the active file has to be rotated.
+    pub fn period(&self, time: &DateTime<Local>) -> Option<String> {
+        match self {
+            Rotation::Hourly => Some(time.format("%Y-%m-%d-%H").to_string()),
+            Rotation::Daily => Some(time.format("%Y-%m-%d").to_string()),
+            Rotation::Never | Rotation::Size(_) => None,
+        }
+    }
+
+    /// Whether a file of `size` bytes must be rotated before writing
+    /// `incoming` more bytes to it.
+    pub fn exceeds(&self, size: u64, incoming: u64) -> bool {
+        match *self {
+            // 空文件总是允许写入，避免单条日志超过上限时不断滚动
+            Rotation::Size(limit) => size > 0 && size + incoming > limit,
+            _ => false,
+        }
+    }
+
+    /// The suffix appended to the file name of a rotated segment that was
+    /// opened at `opened`.
+    pub fn suffix(&self, opened: &DateTime<Local>) -> String {
+        match self.period(opened) {
+            Some(period) => period,
+            None => opened.format("%Y-%m-%d-%H-%M-%S").to_string(),
+        }
+    }
+}
+
+/// Whether `suffix` is one that a rotated segment gets: the period or
+/// timestamp, then an optional `.<index>` and an optional `.gz`.
+pub(super) fn is_segment_suffix(suffix: &str) -> bool {
+    let suffix = suffix.strip_suffix(".gz").unwrap_or(suffix);
+    let (stamp, index) = match suffix.split_once('.') {
+        Some((stamp, index)) => (stamp, Some(index)),
+        None => (suffix, None),
+    };
+    if index.is_some_and(|index| !is_number(index)) {
+        return false;
+    }
+    // %Y-%m-%d、%Y-%m-%d-%H 或 %Y-%m-%d-%H-%M-%S
+    let widths: Vec<usize> = stamp
+        .split('-')
+        .map(|part| if is_number(part) { part.len() } else { 0 })
+        .collect();
+    matches!(widths.as_slice(), [4, 2, 2] | [4, 2, 2, 2] | [4, 2, 2, 2, 2, 2])
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn period_changes_at_boundaries() {
+        let before = Local.with_ymd_and_hms(2023, 5, 1, 23, 59, 59).unwrap();
+        let after = Local.with_ymd_and_hms(2023, 5, 2, 0, 0, 0).unwrap();
+
+        assert_ne!(
+            Rotation::Daily.period(&before),
+            Rotation::Daily.period(&after)
+        );
+        assert_ne!(
+            Rotation::Hourly.period(&before),
+            Rotation::Hourly.period(&after)
+        );
+        assert_eq!(Rotation::Size(10).period(&before), None);
+    }
+
+    #[test]
+    fn size_limit_allows_first_write() {
+        let rotation = Rotation::Size(10);
+
+        assert!(!rotation.exceeds(0, 100));
+        assert!(!rotation.exceeds(4, 6));
+        assert!(rotation.exceeds(5, 6));
+        assert!(!Rotation::Daily.exceeds(100, 100));
+    }
+
+    #[test]
+    fn segment_suffixes() {
+        let opened = Local.with_ymd_and_hms(2023, 5, 1, 8, 30, 0).unwrap();
+        for rotation in [Rotation::Daily, Rotation::Hourly, Rotation::Never] {
+            assert!(is_segment_suffix(&rotation.suffix(&opened)));
+        }
+        assert!(is_segment_suffix("2023-05-01.2"));
+        assert!(is_segment_suffix("2023-05-01-08.gz"));
+        assert!(is_segment_suffix("2023-05-01-08-30-00.1.gz"));
+
+        assert!(!is_segment_suffix("bak"));
+        assert!(!is_segment_suffix("old.gz"));
+        assert!(!is_segment_suffix("2023-05"));
+        assert!(!is_segment_suffix("2023-05-01.bak"));
+        assert!(!is_segment_suffix("2023-05-01."));
+    }
+}
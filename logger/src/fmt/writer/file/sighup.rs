@@ -0,0 +1,86 @@
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 每收到一次 SIGHUP 信号加一
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of `SIGHUP` signals received so far.
+pub fn generation() -> usize {
+    GENERATION.load(Ordering::Acquire)
+}
+
+/// Ask every open log file configured with
+/// [`FileTarget::reopen_on_sighup`](super::FileTarget::reopen_on_sighup) to
+/// reopen its path, as if `SIGHUP` was received.
+pub fn request_reopen() {
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+#[cfg(unix)]
+mod imp {
+    use nix::libc;
+    use nix::sys::signal::{self, SigHandler};
+    use std::io;
+    use std::sync::{Once, OnceLock};
+
+    static INSTALL: Once = Once::new();
+
+    /// 安装之前的信号处理函数，收到信号时一并调用
+    static PREVIOUS: OnceLock<SigHandler> = OnceLock::new();
+
+    /// 信号处理函数只修改原子变量，保证异步信号安全
+    extern "C" fn on_sighup(
+        signum: libc::c_int,
+        info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        super::request_reopen();
+
+        match PREVIOUS.get() {
+            Some(SigHandler::Handler(handler)) => handler(signum),
+            Some(SigHandler::SigAction(handler)) => {
+                handler(signum, info, context)
+            }
+            // 默认处理是结束进程，不再调用
+            Some(SigHandler::SigDfl | SigHandler::SigIgn) | None => {}
+        }
+    }
+
+    pub fn install() -> io::Result<()> {
+        let mut result = Ok(());
+        INSTALL.call_once(|| {
+            let action = signal::SigAction::new(
+                SigHandler::SigAction(on_sighup),
+                signal::SaFlags::SA_RESTART | signal::SaFlags::SA_SIGINFO,
+                signal::SigSet::empty(),
+            );
+            // Safety: the handler only touches an atomic counter and calls
+            // the handler that was installed before.
+            match unsafe { signal::sigaction(signal::Signal::SIGHUP, &action) }
+            {
+                Ok(previous) => {
+                    let _ = PREVIOUS.set(previous.handler());
+                }
+                Err(e) => result = Err(io::Error::from(e)),
+            }
+        });
+        result
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::io;
+
+    pub fn install() -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Install the process wide `SIGHUP` handler, only the first call does
+/// anything.
+///
+/// A handler installed before is still called when the signal is received.
+pub fn install() -> io::Result<()> {
+    imp::install()
+}
@@ -0,0 +1,77 @@
+use super::Rotation;
+use std::path::{Path, PathBuf};
+
+/// Configuration of a log file target.
+///
+/// The file is opened lazily on the first write, so building the logger
+/// never fails because of the file system.
+///
+/// # Example
+///
+/// ```no_run
+/// use logger::fmt::{FileTarget, Rotation, Target};
+///
+/// let target = FileTarget::new("/var/log/app.log")
+///     .rotation(Rotation::Daily)
+///     .max_files(7)
+///     .compress(true);
+///
+/// logger::Builder::new().target(Target::File(target)).init();
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileTarget {
+    pub(crate) path: PathBuf,
+    pub(crate) rotation: Rotation,
+    pub(crate) max_files: Option<usize>,
+    pub(crate) compress: bool,
+    pub(crate) reopen_on_sighup: bool,
+}
+
+impl FileTarget {
+    /// Log to `path`, appending to it if it already exists.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileTarget {
+            path: path.as_ref().to_path_buf(),
+            rotation: Rotation::Never,
+            max_files: None,
+            compress: false,
+            reopen_on_sighup: false,
+        }
+    }
+
+    /// Set when the active file is rotated.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Keep at most `count` rotated segments, the oldest ones are removed.
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = Some(count);
+        self
+    }
+
+    /// Gzip rotated segments, on a background thread so that logging is not
+    /// blocked while a segment is compressed.
+    ///
+    /// Requires the `gzip` feature, otherwise segments are kept as is.
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Reopen the active file when the process receives `SIGHUP`.
+    ///
+    /// This lets external tools such as `logrotate` move the file away.
+    /// A `SIGHUP` handler installed before is still called. On platforms
+    /// without signals use [`request_reopen`](super::request_reopen).
+    pub fn reopen_on_sighup(mut self, reopen: bool) -> Self {
+        self.reopen_on_sighup = reopen;
+        self
+    }
+
+    /// The path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -1,6 +1,9 @@
 mod atty;
 // 用于构造 Writer
 mod builder;
+mod file;
+mod non_blocking;
+mod socket;
 mod target;
 pub mod termcolor;
 mod write_style;
@@ -10,6 +13,9 @@ mod writer;
 pub use atty::{is_stderr, is_stdout};
 
 pub use builder::Builder as WriteBuilder;
+pub use file::{request_reopen, FileTarget, RollingFile, Rotation};
+pub use non_blocking::{NonBlocking, Overflow};
+pub use socket::{SocketWriter, Transport};
 pub use target::{Target, WritableTarget};
 pub use termcolor::{Buffer, BufferWriter, SubtleStyle};
 pub use write_style::{parse_write_style, WriteStyle};
@@ -0,0 +1,186 @@
+use super::{Buffer, BufferWriter};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// What happens to a record when the queue of a non-blocking writer is full.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overflow {
+    /// Wait until the background thread makes room, nothing is lost.
+    #[default]
+    Block,
+    /// Discard the record that is being logged.
+    DropNewest,
+    /// Discard the oldest queued record to make room for the new one.
+    DropOldest,
+}
+
+/// Configuration of a non-blocking writer.
+///
+/// Records are still formatted on the logging thread, only the write to the
+/// target happens on a background thread, which drains a bounded queue.
+#[derive(Clone, Copy, Debug)]
+pub struct NonBlocking {
+    capacity: usize,
+    overflow: Overflow,
+}
+
+impl NonBlocking {
+    /// Queue at most `capacity` formatted records.
+    pub fn new(capacity: usize) -> Self {
+        NonBlocking { capacity: capacity.max(1), overflow: Overflow::Block }
+    }
+
+    /// Set what happens when the queue is full.
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+impl Default for NonBlocking {
+    fn default() -> Self {
+        NonBlocking::new(1024)
+    }
+}
+
+struct State {
+    queue: VecDeque<Buffer>,
+    accepted: u64, // 进入队列的记录数
+    handled: u64,  // 已写入或被丢弃的记录数
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    handled: Condvar,
+    dropped: AtomicU64,
+}
+
+/// The logging side of a non-blocking writer.
+///
+/// Dropping it drains the queue and joins the background thread, so every
+/// accepted record is written. The global logger is never dropped, see
+/// [`crate::Builder::try_init_with_guard`].
+pub struct Worker {
+    config: NonBlocking,
+    shared: Arc<Shared>,
+    inner: Arc<BufferWriter>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    /// Spawn the background thread writing to `inner`.
+    pub fn spawn(config: NonBlocking, inner: Arc<BufferWriter>) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::with_capacity(config.capacity),
+                accepted: 0,
+                handled: 0,
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            handled: Condvar::new(),
+            dropped: AtomicU64::new(0),
+        });
+
+        let handle = {
+            let shared = shared.clone();
+            let inner = inner.clone();
+            thread::Builder::new()
+                .name("logger-writer".to_string())
+                .spawn(move || run(&shared, &inner))
+                .expect("failed to spawn the log writer thread")
+        };
+
+        Worker { config, shared, inner, handle: Some(handle) }
+    }
+
+    /// Queue a formatted record according to the overflow policy.
+    pub fn send(&self, buf: &Buffer) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.queue.len() >= self.config.capacity {
+            match self.config.overflow {
+                Overflow::Block => {
+                    while state.queue.len() >= self.config.capacity
+                        && !state.closed
+                    {
+                        state = self.shared.not_full.wait(state).unwrap();
+                    }
+                }
+                Overflow::DropNewest => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Overflow::DropOldest => {
+                    state.queue.pop_front();
+                    state.handled += 1;
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        state.queue.push_back(buf.clone());
+        state.accepted += 1;
+        self.shared.not_empty.notify_one();
+    }
+
+    /// Wait until every record queued so far is written, then flush the
+    /// target.
+    pub fn flush(&self) -> io::Result<()> {
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            let accepted = state.accepted;
+            while state.handled < accepted && !state.closed {
+                state = self.shared.handled.wait(state).unwrap();
+            }
+        }
+        self.inner.flush()
+    }
+
+    /// The number of records discarded because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().closed = true;
+        self.shared.not_empty.notify_all();
+        self.shared.not_full.notify_all();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 后台线程：不断从队列中取出记录并写入目标，关闭后写完剩余记录再退出
+fn run(shared: &Shared, inner: &BufferWriter) {
+    loop {
+        let buf = {
+            let mut state = shared.state.lock().unwrap();
+            while state.queue.is_empty() && !state.closed {
+                state = shared.not_empty.wait(state).unwrap();
+            }
+            match state.queue.pop_front() {
+                Some(buf) => buf,
+                None => break,
+            }
+        };
+        shared.not_full.notify_one();
+
+        let _ = inner.print(&buf);
+
+        shared.state.lock().unwrap().handled += 1;
+        shared.handled.notify_all();
+    }
+
+    let _ = inner.flush();
+}
@@ -0,0 +1,120 @@
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(unix)]
+use std::path::PathBuf;
+
+/// Where socket targets send their messages.
+///
+/// Every record is sent as one message: a datagram for Unix and UDP
+/// sockets, an octet counted frame (RFC 6587) on TCP connections.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Transport {
+    /// A Unix datagram socket, such as `/dev/log`.
+    #[cfg(unix)]
+    Unix(PathBuf),
+    /// A UDP socket, usually port 514.
+    Udp(SocketAddr),
+    /// A TCP connection, usually port 601.
+    Tcp(SocketAddr),
+}
+
+impl Transport {
+    /// The local syslog daemon.
+    #[cfg(unix)]
+    pub fn syslog() -> Self {
+        Transport::Unix(PathBuf::from("/dev/log"))
+    }
+
+    /// The native socket of systemd-journald.
+    #[cfg(unix)]
+    pub fn journald() -> Self {
+        Transport::Unix(PathBuf::from("/run/systemd/journal/socket"))
+    }
+}
+
+enum Connection {
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+/// Sends every write as one message over a [`Transport`].
+///
+/// The socket is opened on the first write and reopened after an error, so
+/// messages are lost rather than blocking the logger while the receiver is
+/// restarting.
+pub struct SocketWriter {
+    transport: Transport,
+    connection: Option<Connection>,
+}
+
+impl SocketWriter {
+    pub fn new(transport: Transport) -> Self {
+        SocketWriter { transport, connection: None }
+    }
+
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    fn connect(&self) -> io::Result<Connection> {
+        match &self.transport {
+            #[cfg(unix)]
+            Transport::Unix(_) => {
+                UnixDatagram::unbound().map(Connection::Unix)
+            }
+            Transport::Udp(addr) => {
+                let local: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+                UdpSocket::bind(local).map(Connection::Udp)
+            }
+            Transport::Tcp(addr) => {
+                TcpStream::connect(addr).map(Connection::Tcp)
+            }
+        }
+    }
+
+    fn send(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect()?);
+        }
+
+        match (self.connection.as_mut().unwrap(), &self.transport) {
+            #[cfg(unix)]
+            (Connection::Unix(socket), Transport::Unix(path)) => {
+                socket.send_to(buf, path).map(|_| ())
+            }
+            (Connection::Udp(socket), Transport::Udp(addr)) => {
+                socket.send_to(buf, addr).map(|_| ())
+            }
+            (Connection::Tcp(stream), _) => {
+                write!(stream, "{} ", buf.len())?;
+                stream.write_all(buf)
+            }
+            _ => unreachable!("connection does not match the transport"),
+        }
+    }
+}
+
+impl Write for SocketWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let r = self.send(buf);
+        if r.is_err() {
+            self.connection = None;
+        }
+        r.map(|_| buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.connection {
+            Some(Connection::Tcp(stream)) => stream.flush(),
+            _ => Ok(()),
+        }
+    }
+}
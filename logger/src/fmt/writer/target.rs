@@ -1,9 +1,11 @@
+use super::file::{FileTarget, RollingFile};
+use super::socket::{SocketWriter, Transport};
 use std::sync::Mutex;
 use std::{fmt, io};
 
 // 日志输出的目标地址
 // non_exhaustive属性表示类型或变体将来可能会添加更多字段或变体。
-/// Log target, either `stdout`, `stderr` or a custom pipe.
+/// Log target, either `stdout`, `stderr`, a custom pipe, a file or a socket.
 #[non_exhaustive]
 pub enum Target {
     /// Logs will be sent to standard output.
@@ -12,6 +14,11 @@ pub enum Target {
     Stderr,
     /// Logs will be sent to a custom pipe.
     Pipe(Box<dyn io::Write + Send + 'static>),
+    /// Logs will be appended to a file, rotated as configured.
+    File(FileTarget),
+    /// Logs will be sent as one message per record over a socket, use with
+    /// one of the syslog or journald formats.
+    Socket(Transport),
 }
 
 impl Default for Target {
@@ -30,14 +37,17 @@ impl fmt::Debug for Target {
                 Self::Stdout => "stdout",
                 Self::Stderr => "stderr",
                 Self::Pipe(_) => "pipe",
+                Self::File(_) => "file",
+                Self::Socket(_) => "socket",
             }
         )
     }
 }
 
-/// Log target, either `stdout`, `stderr` or a custom pipe.
+/// Log target, either `stdout`, `stderr`, a custom pipe, a file or a socket.
 ///
-/// Same as `Target`, except the pipe is wrapped in a mutex for interior mutability.
+/// Same as `Target`, except the pipe, the file and the socket are wrapped in
+/// a mutex for interior mutability.
 pub enum WritableTarget {
     /// Logs will be sent to standard output.
     Stdout,
@@ -46,6 +56,10 @@ pub enum WritableTarget {
     /// Logs will be sent to a custom pipe.
     /// 支持并发写
     Pipe(Box<Mutex<dyn io::Write + Send + 'static>>),
+    /// Logs will be appended to a rolling file.
+    File(Box<Mutex<RollingFile>>),
+    /// Logs will be sent as one message per record over a socket.
+    Socket(Box<Mutex<SocketWriter>>),
 }
 
 /// WritableTarget 构造函数
@@ -62,6 +76,13 @@ impl From<Target> for WritableTarget {
             Target::Stdout => Self::Stdout,
             Target::Stderr => Self::Stderr,
             Target::Pipe(pipe) => Self::Pipe(Box::new(Mutex::new(pipe))),
+            Target::File(file) => {
+                Self::File(Box::new(Mutex::new(RollingFile::new(file))))
+            }
+            // 每次写入发送一条消息，按原始字节发送
+            Target::Socket(transport) => {
+                Self::Socket(Box::new(Mutex::new(SocketWriter::new(transport))))
+            }
         }
     }
 }
@@ -75,6 +96,8 @@ impl fmt::Debug for WritableTarget {
                 Self::Stdout => "stdout",
                 Self::Stderr => "stderr",
                 Self::Pipe(_) => "pipe",
+                Self::File(_) => "file",
+                Self::Socket(_) => "socket",
             }
         )
     }
@@ -2,6 +2,7 @@ use std::io;
 use std::io::Write;
 use termcolor::{self, ColorSpec, WriteColor};
 
+#[derive(Clone)]
 pub struct Buffer {
     pub inner: termcolor::Buffer,
     pub has_uncolored_target: bool,
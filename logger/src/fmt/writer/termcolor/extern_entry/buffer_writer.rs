@@ -1,6 +1,6 @@
 use super::Buffer;
-use crate::fmt::writer::{WritableTarget, WriteStyle};
-use std::io;
+use crate::fmt::writer::{RollingFile, SocketWriter, WritableTarget, WriteStyle};
+use std::io::{self, Write};
 use std::sync::Mutex;
 use termcolor;
 
@@ -44,6 +44,28 @@ impl BufferWriter {
         }
     }
 
+    pub fn file(
+        _write_style: WriteStyle,
+        file: Box<Mutex<RollingFile>>,
+    ) -> Self {
+        BufferWriter {
+            // Files never get colors, the inner Buffer only handles formatting
+            inner: termcolor::BufferWriter::stderr(termcolor::ColorChoice::Never),
+            uncolored_target: Some(WritableTarget::File(file)),
+        }
+    }
+
+    pub fn socket(
+        _write_style: WriteStyle,
+        socket: Box<Mutex<SocketWriter>>,
+    ) -> Self {
+        BufferWriter {
+            // Messages are sent as raw bytes and never get colors
+            inner: termcolor::BufferWriter::stderr(termcolor::ColorChoice::Never),
+            uncolored_target: Some(WritableTarget::Socket(socket)),
+        }
+    }
+
     /// 创建默认缓存
     pub fn buffer(&self) -> Buffer {
         Buffer {
@@ -65,6 +87,13 @@ impl BufferWriter {
                 WritableTarget::Pipe(pipe) => {
                     write!(pipe.lock().unwrap(), "{}", log)?
                 }
+                WritableTarget::File(file) => {
+                    file.lock().unwrap().write_all(buf.bytes())?
+                }
+                // 日志格式可能包含二进制数据，例如 journald 的字段长度
+                WritableTarget::Socket(socket) => {
+                    socket.lock().unwrap().write_all(buf.bytes())?
+                }
             }
 
             Ok(())
@@ -72,4 +101,17 @@ impl BufferWriter {
             self.inner.print(&buf.inner)
         }
     }
+
+    /// Flush the underlying target.
+    pub fn flush(&self) -> io::Result<()> {
+        match &self.uncolored_target {
+            Some(WritableTarget::Stderr) => io::stderr().flush(),
+            Some(WritableTarget::Stdout) => io::stdout().flush(),
+            Some(WritableTarget::Pipe(pipe)) => pipe.lock().unwrap().flush(),
+            Some(WritableTarget::File(file)) => file.lock().unwrap().flush(),
+            Some(WritableTarget::Socket(socket)) => socket.lock().unwrap().flush(),
+            // termcolor 直接写标准输出或标准错误输出
+            None => io::stdout().flush().and_then(|_| io::stderr().flush()),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::io;
 
+#[derive(Clone)]
 pub struct Buffer(pub Vec<u8>);
 
 impl Buffer {
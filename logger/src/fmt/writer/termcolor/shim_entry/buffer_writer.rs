@@ -1,6 +1,7 @@
 use super::buffer::Buffer;
-use crate::fmt::{WritableTarget, WriteStyle};
-use std::{io, sync::Mutex};
+use crate::fmt::{RollingFile, SocketWriter, WritableTarget, WriteStyle};
+use std::io::{self, Write};
+use std::sync::Mutex;
 
 pub struct BufferWriter {
     pub target: WritableTarget,
@@ -22,6 +23,20 @@ impl BufferWriter {
         BufferWriter { target: WritableTarget::Pipe(pipe) }
     }
 
+    pub fn file(
+        _write_style: WriteStyle,
+        file: Box<Mutex<RollingFile>>,
+    ) -> Self {
+        BufferWriter { target: WritableTarget::File(file) }
+    }
+
+    pub fn socket(
+        _write_style: WriteStyle,
+        socket: Box<Mutex<SocketWriter>>,
+    ) -> Self {
+        BufferWriter { target: WritableTarget::Socket(socket) }
+    }
+
     pub fn buffer(&self) -> Buffer {
         Buffer(Vec::new())
     }
@@ -35,6 +50,12 @@ impl BufferWriter {
             WritableTarget::Pipe(pipe) => {
                 pipe.lock().unwrap().write_all(&buf.0)?
             }
+            WritableTarget::File(file) => {
+                file.lock().unwrap().write_all(&buf.0)?
+            }
+            WritableTarget::Socket(socket) => {
+                socket.lock().unwrap().write_all(&buf.0)?
+            }
             WritableTarget::Stdout => {
                 print!("{}", String::from_utf8_lossy(&buf.0))
             }
@@ -45,4 +66,15 @@ impl BufferWriter {
 
         Ok(())
     }
+
+    /// Flush the underlying target.
+    pub fn flush(&self) -> io::Result<()> {
+        match &self.target {
+            WritableTarget::Pipe(pipe) => pipe.lock().unwrap().flush(),
+            WritableTarget::File(file) => file.lock().unwrap().flush(),
+            WritableTarget::Socket(socket) => socket.lock().unwrap().flush(),
+            WritableTarget::Stdout => io::stdout().flush(),
+            WritableTarget::Stderr => io::stderr().flush(),
+        }
+    }
 }
@@ -1,14 +1,25 @@
+use crate::fmt::writer::non_blocking::Worker;
 use crate::fmt::writer::{Buffer, BufferWriter, WriteStyle};
 use std::fmt;
 use std::io;
+use std::sync::Arc;
 
 /// A terminal target with color awareness.
 pub struct Writer {
-    pub inner: BufferWriter,
+    // 每个 Writer 唯一的标识，线程本地的格式化器缓存以此区分
+    pub id: usize,
+    pub inner: Arc<BufferWriter>,
     pub write_style: WriteStyle,
+    // 非阻塞模式下由后台线程写入目标
+    pub worker: Option<Worker>,
 }
 
 impl Writer {
+    /// Identifies this writer among all writers of the process.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     pub fn write_style(&self) -> WriteStyle {
         self.write_style
     }
@@ -18,7 +29,26 @@ impl Writer {
     }
 
     pub fn print(&self, buf: &Buffer) -> io::Result<()> {
-        self.inner.print(buf)
+        match &self.worker {
+            Some(worker) => {
+                worker.send(buf);
+                Ok(())
+            }
+            None => self.inner.print(buf),
+        }
+    }
+
+    /// Write out every pending record and flush the target.
+    pub fn flush(&self) -> io::Result<()> {
+        match &self.worker {
+            Some(worker) => worker.flush(),
+            None => self.inner.flush(),
+        }
+    }
+
+    /// The number of records discarded by a full non-blocking queue.
+    pub fn dropped(&self) -> u64 {
+        self.worker.as_ref().map(Worker::dropped).unwrap_or(0)
     }
 }
 
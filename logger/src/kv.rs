@@ -0,0 +1,252 @@
+use core_utils::kv::KVs;
+use log::kv::{self, Key, Source, VisitSource};
+use std::fmt;
+use std::io;
+
+/// A typed value attached to a [`Record`](crate::Record).
+#[derive(Clone, Copy)]
+pub enum Value<'a> {
+    Str(&'a str),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bool(bool),
+    Display(&'a dyn fmt::Display),
+}
+
+impl<'a> Value<'a> {
+    /// Capture any `Display` value, it is formatted only when written.
+    pub fn from_display(value: &'a dyn fmt::Display) -> Self {
+        Value::Display(value)
+    }
+
+    /// 转换为 log 库的 Value 类型
+    pub fn to_log_value(&self) -> kv::Value<'a> {
+        match *self {
+            Value::Str(v) => kv::Value::from(v),
+            Value::Int(v) => kv::Value::from(v),
+            Value::UInt(v) => kv::Value::from(v),
+            Value::Float(v) => kv::Value::from(v),
+            Value::Bool(v) => kv::Value::from(v),
+            Value::Display(v) => kv::Value::from_dyn_display(v),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(v: &'a str) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl<'a> From<&'a String> for Value<'a> {
+    fn from(v: &'a String) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl<'a> From<bool> for Value<'a> {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+macro_rules! impl_from_primitive {
+    ($variant:ident => $cast:ty: $($ty:ty),*) => {
+        $(
+            impl<'a> From<$ty> for Value<'a> {
+                fn from(v: $ty) -> Self {
+                    Value::$variant(v as $cast)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_primitive!(Int => i64: i8, i16, i32, i64, isize);
+impl_from_primitive!(UInt => u64: u8, u16, u32, u64, usize);
+impl_from_primitive!(Float => f64: f32, f64);
+
+impl<'a> fmt::Display for Value<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Str(v) => v.fmt(f),
+            Value::Int(v) => v.fmt(f),
+            Value::UInt(v) => v.fmt(f),
+            Value::Float(v) => v.fmt(f),
+            Value::Bool(v) => v.fmt(f),
+            Value::Display(v) => v.fmt(f),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for Value<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Str(v) => f.debug_tuple("Str").field(v).finish(),
+            Value::Int(v) => f.debug_tuple("Int").field(v).finish(),
+            Value::UInt(v) => f.debug_tuple("UInt").field(v).finish(),
+            Value::Float(v) => f.debug_tuple("Float").field(v).finish(),
+            Value::Bool(v) => f.debug_tuple("Bool").field(v).finish(),
+            Value::Display(v) => {
+                f.debug_tuple("Display").field(&format_args!("{}", v)).finish()
+            }
+        }
+    }
+}
+
+/// The key/value pairs of a [`Record`](crate::Record).
+///
+/// Either typed pairs or the plain string pairs of
+/// [`core_utils::kv::KVs`].
+#[derive(Clone, Copy, Debug)]
+pub enum KeyValues<'a> {
+    Typed(&'a [(&'a str, Value<'a>)]),
+    Str(&'a [(&'a str, &'a str)]),
+}
+
+impl<'a> Default for KeyValues<'a> {
+    fn default() -> Self {
+        KeyValues::Typed(&[])
+    }
+}
+
+impl<'a> KeyValues<'a> {
+    /// Connect the string pairs of any [`KVs`] source.
+    pub fn from_kvs<K: KVs<'a>>(kvs: K) -> Self {
+        match kvs.into_kvs() {
+            Some(kvs) => KeyValues::Str(kvs),
+            None => KeyValues::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            KeyValues::Typed(kvs) => kvs.len(),
+            KeyValues::Str(kvs) => kvs.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, Value<'a>)> + 'a {
+        let (typed, str): (&'a [_], &'a [_]) = match *self {
+            KeyValues::Typed(kvs) => (kvs, &[]),
+            KeyValues::Str(kvs) => (&[], kvs),
+        };
+        typed
+            .iter()
+            .copied()
+            .chain(str.iter().map(|&(k, v)| (k, Value::Str(v))))
+    }
+
+    /// The value of the first pair with the given key.
+    pub fn get(&self, key: &str) -> Option<Value<'a>> {
+        self.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+}
+
+impl<'a> From<&'a [(&'a str, Value<'a>)]> for KeyValues<'a> {
+    fn from(kvs: &'a [(&'a str, Value<'a>)]) -> Self {
+        KeyValues::Typed(kvs)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [(&'a str, Value<'a>); N]>
+    for KeyValues<'a>
+{
+    fn from(kvs: &'a [(&'a str, Value<'a>); N]) -> Self {
+        KeyValues::Typed(kvs)
+    }
+}
+
+impl<'a> From<&'a [(&'a str, &'a str)]> for KeyValues<'a> {
+    fn from(kvs: &'a [(&'a str, &'a str)]) -> Self {
+        KeyValues::Str(kvs)
+    }
+}
+
+impl<'a> Source for KeyValues<'a> {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn VisitSource<'kvs>,
+    ) -> Result<(), kv::Error> {
+        for (key, value) in self.iter() {
+            visitor.visit_pair(Key::from_str(key), value.to_log_value())?;
+        }
+        Ok(())
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Like [`for_each`] over the key/values of `record`, followed by the
+/// key/values of the current [`crate::context`] the record does not set.
+pub fn for_each_with_context<F>(
+    record: &log::Record,
+    mut f: F,
+) -> io::Result<()>
+where
+    F: FnMut(&str, &kv::Value) -> io::Result<()>,
+{
+    let context = crate::context::Context::current();
+    if context.is_empty() {
+        return for_each(record.key_values(), f);
+    }
+
+    let mut keys = Vec::new();
+    for_each(record.key_values(), |key, value| {
+        keys.push(key.to_string());
+        f(key, value)
+    })?;
+
+    // 记录中已有的键优先
+    for (key, value) in context.key_values() {
+        if !keys.iter().any(|k| k == key) {
+            f(key, &kv::Value::from(value))?;
+        }
+    }
+    Ok(())
+}
+
+/// Call `f` with every key/value pair of `source`, stopping at the first
+/// error.
+pub fn for_each<F>(source: &dyn Source, f: F) -> io::Result<()>
+where
+    F: FnMut(&str, &kv::Value) -> io::Result<()>,
+{
+    struct Visitor<F> {
+        f: F,
+        error: Option<io::Error>,
+    }
+
+    impl<'kvs, F> VisitSource<'kvs> for Visitor<F>
+    where
+        F: FnMut(&str, &kv::Value) -> io::Result<()>,
+    {
+        fn visit_pair(
+            &mut self,
+            key: Key<'kvs>,
+            value: kv::Value<'kvs>,
+        ) -> Result<(), kv::Error> {
+            // log 的错误类型无法携带 io::Error，先保存起来
+            (self.f)(key.as_str(), &value).map_err(|e| {
+                self.error = Some(e);
+                kv::Error::msg("failed to write key/value pair")
+            })
+        }
+    }
+
+    let mut visitor = Visitor { f, error: None };
+    match source.visit(&mut visitor) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(visitor
+            .error
+            .unwrap_or_else(|| io::Error::other(e.to_string()))),
+    }
+}
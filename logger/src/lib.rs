@@ -1,16 +1,20 @@
 #[macro_use]
 mod messages;
 mod builder;
+pub mod capture;
+pub mod context;
 mod env;
 mod error;
 pub mod filter;
 pub mod fmt;
+pub mod kv;
 mod level;
 pub mod logger;
 mod meta_data;
 mod record;
+mod sink;
 
-pub use builder::Builder;
+pub use builder::{Builder, FlushGuard};
 pub use env::Env;
 pub use error::{ParseLevelError, SetLoggerError};
 pub use level::{Level, LevelFilter, STATIC_MAX_LEVEL};
@@ -18,3 +22,11 @@ pub use logger::*;
 pub use messages::*;
 pub use meta_data::{Metadata, MetadataBuilder};
 pub use record::{Record, RecordBuilder};
+pub use sink::Sink;
+
+/// The logging macros of the `log` crate, re-exported as they are.
+///
+/// Their `key = value` syntax attaches structured fields to the record, e.g.
+/// `info!(user_id = 42, admin = false; "logged in")`, which every formatter
+/// of this crate renders.
+pub use log::{debug, error, info, log, log_enabled, trace, warn};
@@ -1,3 +1,4 @@
+use crate::kv::KeyValues;
 use crate::Level;
 use crate::Metadata;
 use core_utils::maybe_static::MaybeStaticStr;
@@ -22,6 +23,7 @@ pub struct Record<'a> {
     module_path: Option<MaybeStaticStr<'a>>,
     file: Option<MaybeStaticStr<'a>>,
     line: Option<u32>,
+    key_values: KeyValues<'a>,
 }
 
 impl<'a> Record<'a> {
@@ -101,6 +103,47 @@ impl<'a> Record<'a> {
     }
 }
 
+impl<'a> Record<'a> {
+    /// The structured key/value pairs attached to the message.
+    #[inline]
+    pub fn key_values(&self) -> KeyValues<'a> {
+        self.key_values
+    }
+}
+
+impl<'a> Record<'a> {
+    /// Call `f` with the equivalent `log::Record`, key/values included.
+    ///
+    /// This is how a `Record` reaches the formatters of a
+    /// [`Logger`](crate::Logger).
+    pub fn with_log_record<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&log::Record) -> R,
+    {
+        let key_values = self.key_values;
+        let mut builder = log::Record::builder();
+        builder
+            .args(self.args)
+            .level(self.level().into())
+            .target(self.target())
+            .line(self.line)
+            .key_values(&key_values);
+        // 尽量保留 'static 字符串
+        match self.module_path {
+            Some(MaybeStaticStr::Static(s)) => {
+                builder.module_path_static(Some(s))
+            }
+            _ => builder.module_path(self.module_path()),
+        };
+        match self.file {
+            Some(MaybeStaticStr::Static(s)) => builder.file_static(Some(s)),
+            _ => builder.file(self.file()),
+        };
+
+        f(&builder.build())
+    }
+}
+
 #[derive(Debug)]
 pub struct RecordBuilder<'a> {
     record: Record<'a>,
@@ -116,6 +159,7 @@ impl<'a> RecordBuilder<'a> {
     /// - `module_path`: `None`
     /// - `file`: `None`
     /// - `line`: `None`
+    /// - `key_values`: no pairs
     ///
     /// [`format_args!("")`]: https://doc.rust-lang.org/std/macro.format_args.html
     /// [`Metadata::builder().build()`]: struct.MetadataBuilder.html#method.build
@@ -128,6 +172,7 @@ impl<'a> RecordBuilder<'a> {
                 module_path: None,
                 file: None,
                 line: None,
+                key_values: KeyValues::default(),
             },
         }
     }
@@ -219,6 +264,19 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Set [`key_values`](struct.Record.html#method.key_values)
+    ///
+    /// Accepts typed pairs as well as the string pairs of
+    /// `core_utils::kv::KVs`.
+    #[inline]
+    pub fn key_values<K>(&mut self, key_values: K) -> &mut RecordBuilder<'a>
+    where
+        K: Into<KeyValues<'a>>,
+    {
+        self.record.key_values = key_values.into();
+        self
+    }
+
     /// Invoke the builder and return a `Record`
     #[inline]
     pub fn build(&self) -> Record<'a> {
@@ -0,0 +1,152 @@
+use crate::{filter, fmt};
+use log::{LevelFilter, Metadata, Record};
+
+/// An additional output of the logger.
+///
+/// Every sink has its own filter directives, writer and format, records
+/// are written to each sink whose filter matches. Add sinks to a logger
+/// with [`crate::Builder::sink`].
+///
+/// ```no_run
+/// use logger::fmt::{FileTarget, LogFormat, Rotation, Target};
+/// use logger::Sink;
+///
+/// logger::Builder::new()
+///     .parse_filters("error")
+///     .sink(
+///         Sink::new(Target::File(FileTarget::new("audit.log")))
+///             .parse_filters("off,audit=info")
+///             .log_format(LogFormat::Json),
+///     )
+///     .sink(
+///         Sink::new(Target::File(
+///             FileTarget::new("app.log").rotation(Rotation::Daily),
+///         ))
+///         .parse_filters("trace"),
+///     )
+///     .init();
+/// ```
+pub struct Sink {
+    filter: filter::Builder,
+    writer: fmt::writer::WriteBuilder,
+    format: fmt::Builder,
+}
+
+impl Sink {
+    /// A sink writing to `target`, like the logger itself only errors are
+    /// written until directives are added.
+    pub fn new(target: fmt::Target) -> Self {
+        let mut writer = fmt::writer::WriteBuilder::new();
+        writer.target(target);
+
+        Sink {
+            filter: filter::Builder::new(),
+            writer,
+            format: Default::default(),
+        }
+    }
+
+    /// Parses the directives string in the same form as the `RUST_LOG`
+    /// environment variable.
+    pub fn parse_filters(mut self, filters: &str) -> Self {
+        self.filter.parse(filters);
+        self
+    }
+
+    /// Adds a directive to the filter for a specific module.
+    pub fn filter_module(mut self, module: &str, level: LevelFilter) -> Self {
+        self.filter.filter_module(module, level);
+        self
+    }
+
+    /// Adds a directive to the filter for all modules.
+    pub fn filter_level(mut self, level: LevelFilter) -> Self {
+        self.filter.filter_level(level);
+        self
+    }
+
+    /// Writes at most `per_second` records per callsite of the given module,
+    /// see [`filter::Builder::rate_limit`].
+    pub fn rate_limit(
+        mut self,
+        module: Option<&str>,
+        per_second: u32,
+    ) -> Self {
+        self.filter.rate_limit(module, per_second);
+        self
+    }
+
+    /// Keeps records of the given module with probability `ratio`, see
+    /// [`filter::Builder::sample`].
+    pub fn sample(mut self, module: Option<&str>, ratio: f64) -> Self {
+        self.filter.sample(module, ratio);
+        self
+    }
+
+    /// Sets the format of the records written to this sink.
+    pub fn format(mut self, format: fmt::Builder) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Selects the built-in output format.
+    pub fn log_format(mut self, log_format: fmt::LogFormat) -> Self {
+        self.format.log_format = log_format;
+        self
+    }
+
+    /// Sets whether or not styles will be written.
+    pub fn write_style(mut self, write_style: fmt::WriteStyle) -> Self {
+        self.writer.write_style(write_style);
+        self
+    }
+
+    /// Writes records to the target from a background thread.
+    pub fn non_blocking(mut self, non_blocking: fmt::NonBlocking) -> Self {
+        self.writer.non_blocking(non_blocking);
+        self
+    }
+
+    pub(crate) fn build(mut self) -> BuiltSink {
+        BuiltSink {
+            filter: self.filter.build(),
+            writer: self.writer.build(),
+            format: self.format.build(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Sink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Sink")
+            .field("filter", &self.filter)
+            .field("writer", &self.writer)
+            .finish()
+    }
+}
+
+pub(crate) struct BuiltSink {
+    pub(crate) filter: filter::Filter,
+    pub(crate) writer: fmt::Writer,
+    pub(crate) format: fmt::FormatFn,
+}
+
+impl BuiltSink {
+    pub(crate) fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    pub(crate) fn matches(&self, record: &Record) -> bool {
+        self.filter.matches(record)
+    }
+
+    pub(crate) fn admit(&self, record: &Record) -> bool {
+        self.filter.admit(record)
+    }
+}
+
+impl std::fmt::Debug for BuiltSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Sink").field("filter", &self.filter).finish()
+    }
+}
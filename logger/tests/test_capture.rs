@@ -0,0 +1,93 @@
+use core_utils::predicates::str::{contains, is_match};
+use logger::capture::{self, record};
+use logger::{error, info, warn, Level};
+use std::thread;
+
+#[test]
+fn test_capture_assertions() {
+    let logs = capture::capture(|| {
+        info!(target: "http", "GET /health");
+        warn!(target: "db", "slow query: 1200ms");
+    });
+
+    assert_eq!(logs.len(), 2);
+    logs.assert_any(
+        record()
+            .level(Level::Warn)
+            .target("db")
+            .message(is_match(r"slow query: \d+ms").unwrap()),
+    )
+    .assert_none(record().level(Level::Error))
+    .assert_count(record().target("http"), 1);
+}
+
+#[test]
+fn test_capture_key_values() {
+    let logs = capture::capture(|| {
+        info!(target: "auth", user_id = 42, admin = false; "logged in");
+    });
+
+    let captured = &logs.records()[0];
+    assert_eq!(captured.get("user_id"), Some("42"));
+    assert_eq!(
+        captured.formatted,
+        "[INFO  auth] logged in user_id=42 admin=false\n"
+    );
+    logs.assert_any(
+        record().key_value("user_id", 42).key_value("admin", false),
+    );
+    logs.assert_none(record().key_value("user_id", 7));
+}
+
+#[test]
+fn test_capture_failure_shows_tree() {
+    let logs = capture::capture(|| {
+        info!(target: "app", request_id = "r1"; "started");
+        error!(target: "app", "disk full");
+    });
+
+    let err = logs.try_assert_none(record().level(Level::Error)).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with("Unexpected record, failed var.is_record()"),
+        "{}",
+        message
+    );
+    assert!(message.contains("level: ERROR"), "{}", message);
+    assert!(
+        message.contains(
+            "captured logs (2)\n\
+             ├── [INFO  app] started request_id=r1\n\
+             │   └── request_id=r1\n\
+             └── [ERROR app] disk full\n"
+        ),
+        "{}",
+        message
+    );
+
+    let err = logs
+        .try_assert_any(record().target("app").message(contains("finished")))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("No matching record"), "{}", err);
+}
+
+#[test]
+fn test_capture_is_per_thread_and_nested() {
+    let outer = capture::start();
+    info!("outer");
+
+    let inner = capture::capture(|| {
+        info!("inner");
+        thread::spawn(|| info!("other thread")).join().unwrap();
+    });
+
+    let outer = outer.finish();
+    assert_eq!(inner.len(), 1);
+    inner.assert_any(record().message(contains("inner")));
+    assert_eq!(outer.len(), 2);
+    outer.assert_none(record().message(contains("other thread")));
+
+    // 作用域结束后不再捕获
+    info!("after");
+    assert!(capture::capture(|| {}).is_empty());
+}
@@ -0,0 +1,188 @@
+use log::{Level, Log, Record};
+use logger::context::{self, Context, WithContextExt};
+use logger::fmt::Target;
+use std::future::Future;
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+use std::thread;
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn take(&self) -> String {
+        String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap()))
+            .unwrap()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn builder(buf: &SharedBuf) -> logger::Builder {
+    let mut builder = logger::Builder::new();
+    builder
+        .filter_level(log::LevelFilter::Info)
+        .format_timestamp(None)
+        .target(Target::Pipe(Box::new(buf.clone())));
+    builder
+}
+
+fn log(logger: &impl Log, msg: &str) {
+    logger.log(
+        &Record::builder()
+            .args(format_args!("{}", msg))
+            .level(Level::Info)
+            .target("app")
+            .build(),
+    );
+}
+
+#[test]
+fn test_scoped_key_values() {
+    let buf = SharedBuf::default();
+    let logger = builder(&buf).build();
+
+    let request = context::scope([("request_id", "r-1"), ("user_id", "7")]);
+    {
+        let _user = context::push("user_id", 8);
+        log(&logger, "inner");
+    }
+    log(&logger, "outer");
+    drop(request);
+    log(&logger, "done");
+
+    assert_eq!(
+        buf.take(),
+        "[INFO  app] inner request_id=r-1 user_id=8\n\
+         [INFO  app] outer request_id=r-1 user_id=7\n\
+         [INFO  app] done\n"
+    );
+}
+
+#[test]
+fn test_record_key_values_override_context() {
+    let buf = SharedBuf::default();
+    let logger =
+        builder(&buf).log_format(logger::fmt::LogFormat::Json).build();
+    let _guard = context::push("user_id", 7);
+
+    let kvs: &[(&str, i64)] = &[("user_id", 9)];
+    logger.log(
+        &Record::builder()
+            .args(format_args!("login"))
+            .level(Level::Info)
+            .target("app")
+            .key_values(&kvs)
+            .build(),
+    );
+
+    let output = buf.take();
+    assert!(output.contains(",\"user_id\":9}"), "{}", output);
+    assert!(!output.contains("\"user_id\":\"7\""), "{}", output);
+}
+
+/// Returns `Pending` once, so the future is polled twice.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls the future once on each of two new threads.
+fn poll_on_threads<F: Future + Send + 'static>(future: F) {
+    let future = Arc::new(Mutex::new(Box::pin(future)));
+    for _ in 0..2 {
+        let future = future.clone();
+        thread::spawn(move || {
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let _ = future
+                .lock()
+                .unwrap()
+                .as_mut()
+                .poll(&mut TaskContext::from_waker(&waker));
+        })
+        .join()
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_future_carries_context() {
+    let buf = SharedBuf::default();
+    let logger = Arc::new(builder(&buf).build());
+
+    let task = {
+        let _guard = context::push("request_id", "r-2");
+        let logger = logger.clone();
+        async move {
+            {
+                // guard 不是 Send，不能跨过 await
+                let _user = context::push("user_id", 3);
+                log(&*logger, "before");
+            }
+            YieldOnce(false).await;
+            log(&*logger, "after");
+        }
+        .in_current_context()
+    };
+    poll_on_threads(task);
+
+    // 任务中压入的键值不会泄漏到当前线程
+    assert!(Context::current().is_empty());
+    assert_eq!(
+        buf.take(),
+        "[INFO  app] before request_id=r-2 user_id=3\n\
+         [INFO  app] after request_id=r-2\n"
+    );
+}
+
+#[test]
+fn test_guard_across_await_stays_with_future() {
+    let buf = SharedBuf::default();
+    let logger = builder(&buf).build();
+
+    let mut task = Box::pin(
+        async {
+            let _user = context::push("user_id", 3);
+            YieldOnce(false).await;
+            log(&logger, "after");
+        }
+        .with_context(Context::default()),
+    );
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = TaskContext::from_waker(&waker);
+
+    assert!(task.as_mut().poll(&mut cx).is_pending());
+    // 两次 poll 之间，线程的上下文不包含任务中压入的键值
+    assert!(Context::current().is_empty());
+    let _request = context::push("request_id", "r-3");
+    assert!(task.as_mut().poll(&mut cx).is_ready());
+
+    assert_eq!(buf.take(), "[INFO  app] after user_id=3\n");
+}
@@ -0,0 +1,42 @@
+use logger::fmt::{NonBlocking, Target};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 写入缓慢的输出目标，退出前队列中仍有未写入的记录
+#[derive(Clone, Default)]
+struct SlowBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SlowBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        thread::sleep(Duration::from_millis(5));
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// 全局日志对象只能初始化一次，本文件只包含一个测试
+#[test]
+fn test_guard_flushes_global_logger() {
+    let out = SlowBuf::default();
+    let guard = logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .target(Target::Pipe(Box::new(out.clone())))
+        .non_blocking(NonBlocking::default())
+        .try_init_with_guard()
+        .unwrap();
+
+    for i in 0..50 {
+        log::info!("record {}", i);
+    }
+    drop(guard);
+
+    let written = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(written.lines().count(), 50);
+    assert_eq!(written.lines().last(), Some("record 49"));
+}
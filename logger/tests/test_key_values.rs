@@ -0,0 +1,105 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use logger::fmt::{LogFormat, Target, WriteStyle};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 同一条记录交给每种格式的日志对象
+struct Fanout(Vec<Box<dyn Log>>);
+
+impl Log for Fanout {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.iter().any(|logger| logger.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        for logger in &self.0 {
+            logger.log(record);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn build(log_format: LogFormat, buf: &SharedBuf) -> Box<dyn Log> {
+    Box::new(
+        logger::Builder::new()
+            .filter_level(LevelFilter::Info)
+            .log_format(log_format)
+            .format_timestamp(None)
+            .write_style(WriteStyle::Never)
+            .target(Target::Pipe(Box::new(buf.clone())))
+            .build(),
+    )
+}
+
+fn lines(buf: &SharedBuf) -> Vec<String> {
+    let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    output.lines().map(str::to_owned).collect()
+}
+
+/// 全局日志对象只能初始化一次，所以这个测试文件只有一个测试用例
+#[test]
+fn test_macros_with_key_values() {
+    let (text, json, logfmt) =
+        (SharedBuf::default(), SharedBuf::default(), SharedBuf::default());
+    log::set_boxed_logger(Box::new(Fanout(vec![
+        build(LogFormat::Text, &text),
+        build(LogFormat::Json, &json),
+        build(LogFormat::Logfmt, &logfmt),
+    ])))
+    .unwrap();
+    log::set_max_level(LevelFilter::Info);
+
+    let user = "bob smith";
+    logger::info!(target: "auth", user_id = 42, admin = false, user = user; "logged in");
+    logger::warn!(target: "auth", peer:% = std::net::Ipv4Addr::LOCALHOST; "denied");
+    logger::debug!(target: "auth", hidden = true; "filtered out");
+
+    // 数字和布尔值保持类型，带空格的字符串被引用
+    let text = lines(&text);
+    assert_eq!(text.len(), 2, "{:?}", text);
+    assert!(
+        text[0]
+            .ends_with("logged in user_id=42 admin=false user=\"bob smith\""),
+        "{}",
+        text[0]
+    );
+    assert!(text[1].ends_with("denied peer=127.0.0.1"), "{}", text[1]);
+
+    let json = lines(&json);
+    assert_eq!(json.len(), 2, "{:?}", json);
+    assert!(
+        json[0].ends_with(
+            "\"message\":\"logged in\",\"user_id\":42,\"admin\":false,\
+             \"user\":\"bob smith\"}"
+        ),
+        "{}",
+        json[0]
+    );
+    assert!(json[1].ends_with("\"peer\":\"127.0.0.1\"}"), "{}", json[1]);
+
+    let logfmt = lines(&logfmt);
+    assert_eq!(logfmt.len(), 2, "{:?}", logfmt);
+    assert!(logfmt[0].starts_with("level=INFO target=auth"), "{}", logfmt[0]);
+    assert!(
+        logfmt[0].ends_with(
+            "msg=\"logged in\" user_id=42 admin=false user=\"bob smith\""
+        ),
+        "{}",
+        logfmt[0]
+    );
+    assert!(logfmt[1].ends_with("msg=denied peer=127.0.0.1"), "{}", logfmt[1]);
+}
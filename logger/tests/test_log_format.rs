@@ -0,0 +1,246 @@
+use log::{Level, Log, Record};
+use logger::fmt::{
+    writer::WriteBuilder, Formatter, JsonFormat, LogfmtFormat,
+    Target, WriteStyle,
+};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+fn record_args<'a>(args: std::fmt::Arguments<'a>) -> Record<'a> {
+    Record::builder()
+        .args(args)
+        .level(Level::Warn)
+        .file(Some("test.rs"))
+        .line(Some(144))
+        .module_path(Some("test::path"))
+        .target("target")
+        .build()
+}
+
+/// 读取 Formatter 缓存中的内容
+fn written(f: &Formatter) -> String {
+    String::from_utf8(f.buf.borrow().bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn test_json_format() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let key_values = vec![("service".to_string(), "api".to_string())];
+
+    JsonFormat {
+        timestamp: None,
+        key_values: &key_values,
+        suffix: "\n",
+        buf: &mut f,
+    }
+    .write(&record_args(format_args!("log \"quoted\"\nmessage")))
+    .unwrap();
+
+    assert_eq!(
+        "{\"timestamp\":null,\"level\":\"WARN\",\"target\":\"target\",\
+         \"module_path\":\"test::path\",\"file\":\"test.rs\",\"line\":144,\
+         \"message\":\"log \\\"quoted\\\"\\nmessage\",\"service\":\"api\"}\n",
+        written(&f)
+    );
+}
+
+#[test]
+fn test_json_format_unique_keys() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let key_values = vec![
+        ("level".to_string(), "configured".to_string()),
+        ("service".to_string(), "api".to_string()),
+        ("service".to_string(), "other".to_string()),
+    ];
+
+    JsonFormat {
+        timestamp: None,
+        key_values: &key_values,
+        suffix: "\n",
+        buf: &mut f,
+    }
+    .write(&record_args(format_args!("ready")))
+    .unwrap();
+
+    assert!(
+        written(&f).ends_with(
+            "\"message\":\"ready\",\"fields.level\":\"configured\",\
+             \"service\":\"api\"}\n"
+        ),
+        "{}",
+        written(&f)
+    );
+}
+
+#[test]
+fn test_json_format_without_location() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let record = Record::builder().args(format_args!("hello")).build();
+
+    JsonFormat { timestamp: None, key_values: &[], suffix: "\n", buf: &mut f }
+        .write(&record)
+        .unwrap();
+
+    assert_eq!(
+        "{\"timestamp\":null,\"level\":\"INFO\",\"target\":\"\",\
+         \"module_path\":null,\"file\":null,\"line\":null,\"message\":\"hello\"}\n",
+        written(&f)
+    );
+}
+
+#[test]
+fn test_logfmt_format() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let key_values = vec![("service".to_string(), "api gateway".to_string())];
+
+    LogfmtFormat {
+        timestamp: None,
+        key_values: &key_values,
+        suffix: "\n",
+        buf: &mut f,
+    }
+    .write(&record_args(format_args!("hello")))
+    .unwrap();
+
+    assert_eq!(
+        "level=WARN target=target module_path=test::path file=test.rs \
+         line=144 msg=hello service=\"api gateway\"\n",
+        written(&f)
+    );
+}
+
+/// 共享的内存缓存，作为 Pipe 输出目标
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_builder_log_format() {
+    let buf = SharedBuf::default();
+    let kvs: &[(&str, &str)] = &[("host", "node-1")];
+    let logger = logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .parse_log_format("logfmt")
+        .format_key_values(kvs)
+        .format_timestamp(None)
+        .target(Target::Pipe(Box::new(buf.clone())))
+        .build();
+
+    logger.log(&record_args(format_args!("ready")));
+
+    let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        "level=WARN target=target module_path=test::path file=test.rs \
+         line=144 msg=ready host=node-1\n",
+        output
+    );
+}
+
+#[test]
+fn test_builder_log_format_from_env() {
+    std::env::set_var("test_builder_log_format_from_env", "json");
+    let env = logger::Env::new()
+        .filter_or("test_builder_log_format_from_env_filter", "info")
+        .log_format("test_builder_log_format_from_env");
+
+    let buf = SharedBuf::default();
+    let logger = logger::Builder::from_env(env)
+        .format_timestamp(None)
+        .target(Target::Pipe(Box::new(buf.clone())))
+        .build();
+
+    logger.log(&record_args(format_args!("ready")));
+
+    let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    assert!(output.starts_with("{\"timestamp\":null,\"level\":\"WARN\""));
+}
+
+fn record_with_key_values<'a>(
+    args: std::fmt::Arguments<'a>,
+    kvs: &'a [(&'a str, logger::kv::Value<'a>)],
+    f: impl FnOnce(&Record),
+) {
+    logger::Record::builder()
+        .args(args)
+        .level(logger::Level::Info)
+        .target("target")
+        .key_values(kvs)
+        .build()
+        .with_log_record(f)
+}
+
+#[test]
+fn test_formats_render_key_values() {
+    use logger::fmt::DefaultFormat;
+    use logger::kv::Value;
+
+    let kvs = [
+        ("user", Value::from("bob smith")),
+        ("attempt", Value::from(3)),
+        ("ratio", Value::from(0.5)),
+        ("admin", Value::from(true)),
+    ];
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+
+    record_with_key_values(format_args!("login"), &kvs, |record| {
+        let mut f = Formatter::new(&writer);
+        DefaultFormat {
+            timestamp: None,
+            module_path: false,
+            target: false,
+            level: true,
+            written_header_value: false,
+            indent: Some(4),
+            suffix: "\n",
+            buf: &mut f,
+        }
+        .write(record)
+        .unwrap();
+        assert_eq!(
+            "[INFO ] login user=\"bob smith\" attempt=3 ratio=0.5 admin=true\n",
+            written(&f)
+        );
+
+        let mut f = Formatter::new(&writer);
+        JsonFormat {
+            timestamp: None,
+            key_values: &[],
+            suffix: "\n",
+            buf: &mut f,
+        }
+        .write(record)
+        .unwrap();
+        assert!(written(&f).ends_with(
+            "\"message\":\"login\",\"user\":\"bob smith\",\"attempt\":3,\
+             \"ratio\":0.5,\"admin\":true}\n"
+        ));
+
+        let mut f = Formatter::new(&writer);
+        LogfmtFormat {
+            timestamp: None,
+            key_values: &[],
+            suffix: "\n",
+            buf: &mut f,
+        }
+        .write(record)
+        .unwrap();
+        assert_eq!(
+            "level=INFO target=target msg=login user=\"bob smith\" attempt=3 \
+             ratio=0.5 admin=true\n",
+            written(&f)
+        );
+    });
+}
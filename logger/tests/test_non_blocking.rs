@@ -0,0 +1,128 @@
+use log::{Level, Log, Record};
+use logger::fmt::{NonBlocking, Overflow, Target};
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// 写入前需要等待 gate 打开的输出目标，用于模拟缓慢的磁盘
+#[derive(Clone)]
+struct SlowBuf {
+    buf: Arc<Mutex<Vec<u8>>>,
+    gate: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl SlowBuf {
+    fn new(open: bool) -> Self {
+        SlowBuf {
+            buf: Default::default(),
+            gate: Arc::new((Mutex::new(open), Condvar::new())),
+        }
+    }
+
+    fn open(&self) {
+        *self.gate.0.lock().unwrap() = true;
+        self.gate.1.notify_all();
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let buf = self.buf.lock().unwrap();
+        String::from_utf8_lossy(&buf).lines().map(String::from).collect()
+    }
+}
+
+impl Write for SlowBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut open = self.gate.0.lock().unwrap();
+        while !*open {
+            open = self.gate.1.wait(open).unwrap();
+        }
+        self.buf.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn builder(out: &SlowBuf, non_blocking: NonBlocking) -> logger::Builder {
+    let mut builder = logger::Builder::new();
+    builder
+        .filter_level(log::LevelFilter::Info)
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .target(Target::Pipe(Box::new(out.clone())))
+        .non_blocking(non_blocking);
+    builder
+}
+
+fn log_n(logger: &dyn Log, n: usize) {
+    for i in 0..n {
+        logger.log(
+            &Record::builder()
+                .args(format_args!("record {}", i))
+                .level(Level::Info)
+                .build(),
+        );
+    }
+}
+
+#[test]
+fn test_block_keeps_every_record() {
+    let out = SlowBuf::new(true);
+    let logger = builder(&out, NonBlocking::new(2)).build();
+
+    log_n(&logger, 100);
+    logger.flush();
+
+    let lines = out.lines();
+    assert_eq!(lines.len(), 100);
+    assert_eq!(lines[0], "record 0");
+    assert_eq!(lines[99], "record 99");
+    assert_eq!(logger.dropped_records(), 0);
+}
+
+#[test]
+fn test_drop_newest_counts_dropped() {
+    let out = SlowBuf::new(false);
+    let logger =
+        builder(&out, NonBlocking::new(2).overflow(Overflow::DropNewest))
+            .build();
+
+    // 后台线程阻塞在第一条记录上，队列很快被填满
+    log_n(&logger, 10);
+    assert!(logger.dropped_records() >= 7, "{}", logger.dropped_records());
+
+    out.open();
+    logger.flush();
+
+    let lines = out.lines();
+    assert_eq!(lines.len() as u64 + logger.dropped_records(), 10);
+    assert_eq!(lines[0], "record 0");
+}
+
+#[test]
+fn test_drop_oldest_keeps_latest() {
+    let out = SlowBuf::new(false);
+    let logger =
+        builder(&out, NonBlocking::new(2).overflow(Overflow::DropOldest))
+            .build();
+
+    log_n(&logger, 10);
+    assert!(logger.dropped_records() >= 7, "{}", logger.dropped_records());
+
+    out.open();
+    logger.flush();
+
+    let lines = out.lines();
+    assert_eq!(lines.len() as u64 + logger.dropped_records(), 10);
+    assert_eq!(lines.last().unwrap(), "record 9");
+}
+
+#[test]
+fn test_drop_flushes_pending_records() {
+    let out = SlowBuf::new(true);
+    let logger = builder(&out, NonBlocking::default()).build();
+
+    log_n(&logger, 50);
+    drop(logger);
+
+    assert_eq!(out.lines().len(), 50);
+}
@@ -0,0 +1,220 @@
+use log::{Level, LevelFilter, Log, Record};
+use logger::filter::{Builder, Filter, Suppressed};
+use logger::fmt::Target;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Whether the filter would write the record, counting it against the
+/// budget of its callsite.
+fn admits(filter: &Filter, record: &Record) -> bool {
+    filter.matches(record) && filter.admit(record)
+}
+
+fn record(target: &'static str, line: u32) -> Record<'static> {
+    Record::builder()
+        .args(format_args!("hot loop"))
+        .level(Level::Warn)
+        .target(target)
+        .file_static(Some("src/hot.rs"))
+        .line(Some(line))
+        .build()
+}
+
+#[test]
+fn test_rate_limit_per_callsite() {
+    let filter = Builder::new()
+        .filter_level(LevelFilter::Info)
+        .rate_limit(None, 3)
+        .build();
+
+    let admitted =
+        (0..10).filter(|_| admits(&filter, &record("app", 10))).count();
+    assert_eq!(admitted, 3);
+    // 其它调用位置有独立的配额
+    assert!(admits(&filter, &record("app", 11)));
+    // 在窗口结束前不汇报
+    assert!(filter.take_suppressed().is_empty());
+
+    thread::sleep(Duration::from_millis(1100));
+    assert_eq!(
+        filter.take_suppressed(),
+        vec![Suppressed {
+            target: "app".to_string(),
+            callsite: "src/hot.rs:10".to_string(),
+            count: 7,
+        }]
+    );
+    assert!(filter.take_suppressed().is_empty());
+    assert!(admits(&filter, &record("app", 10)));
+}
+
+#[test]
+fn test_rate_limit_longest_module_match() {
+    let filter = Builder::new()
+        .filter_level(LevelFilter::Info)
+        .rate_limit(Some("app"), 1)
+        .rate_limit(Some("app::db"), 2)
+        .build();
+
+    let admitted = |target, line| {
+        (0..5).filter(|_| admits(&filter, &record(target, line))).count()
+    };
+    assert_eq!(admitted("app::http", 1), 1);
+    assert_eq!(admitted("app::db", 2), 2);
+    assert_eq!(admitted("other", 3), 5);
+}
+
+#[test]
+fn test_sample_per_target() {
+    let filter = Builder::new()
+        .filter_level(LevelFilter::Info)
+        .sample(Some("noisy"), 0.5)
+        .sample(Some("muted"), 0.0)
+        .build();
+
+    let admitted = |target| {
+        (0..10_000).filter(|_| admits(&filter, &record(target, 1))).count()
+    };
+    let noisy = admitted("noisy");
+    assert!((4_000..6_000).contains(&noisy), "{}", noisy);
+    assert_eq!(admitted("muted"), 0);
+    assert_eq!(admitted("other"), 10_000);
+}
+
+#[test]
+fn test_matches_does_not_use_budget() {
+    let filter = Builder::new()
+        .filter_level(LevelFilter::Info)
+        .rate_limit(None, 1)
+        .build();
+
+    for _ in 0..5 {
+        assert!(filter.matches(&record("app", 10)));
+    }
+    assert!(filter.admit(&record("app", 10)));
+    assert!(!filter.admit(&record("app", 10)));
+}
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_logger_writes_summary() {
+    let buf = SharedBuf::default();
+    let logger = rate_limited_logger(&buf).build();
+
+    for _ in 0..5 {
+        logger.log(&record("app", 10));
+    }
+    thread::sleep(Duration::from_millis(1100));
+    logger.log(&record("app", 20));
+
+    // 汇总由下一条日志或后台线程输出，先后顺序不确定
+    let mut lines: Vec<String> =
+        buf.output().lines().map(String::from).collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec![
+            "[WARN  app] hot loop",
+            "[WARN  app] hot loop",
+            "[WARN  app] hot loop",
+            "[WARN  app] suppressed 3 messages from src/hot.rs:10",
+        ]
+    );
+}
+
+fn rate_limited_logger(buf: &SharedBuf) -> logger::Builder {
+    let mut builder = logger::Builder::new();
+    builder
+        .filter_level(LevelFilter::Info)
+        .rate_limit(None, 2)
+        .format_timestamp(None)
+        .target(Target::Pipe(Box::new(buf.clone())));
+    builder
+}
+
+#[test]
+fn test_logger_writes_summary_without_further_records() {
+    let buf = SharedBuf::default();
+    let logger = rate_limited_logger(&buf).build();
+
+    for _ in 0..5 {
+        logger.log(&record("app", 10));
+    }
+    // 不再写日志，由后台线程定期输出汇总
+    thread::sleep(Duration::from_millis(2500));
+
+    assert!(
+        buf.output()
+            .ends_with("[WARN  app] suppressed 3 messages from src/hot.rs:10\n"),
+        "{}",
+        buf.output()
+    );
+    drop(logger);
+}
+
+#[test]
+fn test_logger_flush_writes_summary() {
+    let buf = SharedBuf::default();
+    let logger = rate_limited_logger(&buf).build();
+
+    for _ in 0..5 {
+        logger.log(&record("app", 10));
+    }
+    logger.flush();
+
+    assert_eq!(
+        buf.output(),
+        "[WARN  app] hot loop\n\
+         [WARN  app] hot loop\n\
+         [WARN  app] suppressed 3 messages from src/hot.rs:10\n"
+    );
+}
+
+#[test]
+fn test_logger_matches_does_not_use_budget() {
+    let buf = SharedBuf::default();
+    let logger = rate_limited_logger(&buf).build();
+
+    for _ in 0..5 {
+        assert!(logger.matches(&record("app", 10)));
+    }
+    logger.log(&record("app", 10));
+
+    assert_eq!(buf.output(), "[WARN  app] hot loop\n");
+}
+
+#[test]
+fn test_reload_keeps_rate_limits() {
+    let buf = SharedBuf::default();
+    let logger = rate_limited_logger(&buf).build();
+
+    logger.filter_handle().parse("warn");
+    for _ in 0..5 {
+        logger.log(&record("app", 10));
+    }
+
+    assert_eq!(
+        buf.output(),
+        "[WARN  app] hot loop\n[WARN  app] hot loop\n"
+    );
+}
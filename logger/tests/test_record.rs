@@ -1,3 +1,4 @@
+use logger::kv::{KeyValues, Value};
 use logger::{Level, Metadata, MetadataBuilder, Record, RecordBuilder};
 
 #[test]
@@ -52,3 +53,67 @@ fn test_record_complete_builder() {
     assert_eq!(record_test.file(), Some("bar"));
     assert_eq!(record_test.line(), Some(30));
 }
+
+#[test]
+fn test_record_key_values() {
+    let user = String::from("alice");
+    let addr = std::net::Ipv4Addr::LOCALHOST;
+    let kvs = [
+        ("user", Value::from(&user)),
+        ("attempt", Value::from(3)),
+        ("ratio", Value::from(0.5)),
+        ("admin", Value::from(false)),
+        ("addr", Value::from_display(&addr)),
+    ];
+    let record_test = Record::builder().key_values(&kvs).build();
+
+    let kvs = record_test.key_values();
+    assert_eq!(kvs.len(), 5);
+    assert_eq!(kvs.get("user").unwrap().to_string(), "alice");
+    assert_eq!(kvs.get("attempt").unwrap().to_string(), "3");
+    assert_eq!(kvs.get("ratio").unwrap().to_string(), "0.5");
+    assert_eq!(kvs.get("admin").unwrap().to_string(), "false");
+    assert_eq!(kvs.get("addr").unwrap().to_string(), "127.0.0.1");
+    assert!(kvs.get("missing").is_none());
+}
+
+#[test]
+fn test_record_key_values_from_kvs() {
+    let pairs: &[(&str, &str)] = &[("service", "api")];
+    let record_test =
+        Record::builder().key_values(KeyValues::from_kvs(pairs)).build();
+
+    assert_eq!(
+        record_test.key_values().get("service").unwrap().to_string(),
+        "api"
+    );
+    assert!(Record::builder()
+        .key_values(KeyValues::from_kvs(()))
+        .build()
+        .key_values()
+        .is_empty());
+}
+
+#[test]
+fn test_record_with_log_record() {
+    let kvs = [("attempt", Value::from(3))];
+    let record_test = Record::builder()
+        .args(format_args!("hello"))
+        .level(Level::Warn)
+        .target("myApp")
+        .file_static(Some("bar"))
+        .line(Some(30))
+        .key_values(&kvs)
+        .build();
+
+    record_test.with_log_record(|record| {
+        assert_eq!(record.level(), log::Level::Warn);
+        assert_eq!(record.target(), "myApp");
+        assert_eq!(record.file_static(), Some("bar"));
+        assert_eq!(record.args().to_string(), "hello");
+        assert_eq!(
+            record.key_values().get("attempt".into()).unwrap().to_i64(),
+            Some(3)
+        );
+    });
+}
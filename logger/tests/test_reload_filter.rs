@@ -0,0 +1,103 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn builder() -> logger::Builder {
+    let mut builder = logger::Builder::new();
+    builder.parse_filters("info");
+    builder
+}
+
+fn metadata(level: Level, target: &str) -> Metadata<'_> {
+    Metadata::builder().level(level).target(target).build()
+}
+
+/// 等待后台线程重新加载过滤器
+fn wait_until<F: Fn() -> bool>(f: F) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if f() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn test_reload_directives() {
+    let logger = builder().build();
+    let handle = logger.filter_handle();
+    assert!(!logger.enabled(&metadata(Level::Debug, "app::db")));
+
+    handle.parse("warn,app::db=trace");
+    assert_eq!(logger.filter(), LevelFilter::Trace);
+    assert!(logger.enabled(&metadata(Level::Trace, "app::db")));
+    assert!(!logger.enabled(&metadata(Level::Info, "app::http")));
+}
+
+#[test]
+fn test_reload_regex_filter() {
+    let logger = builder().build();
+    let handle = logger.filter_handle();
+    let matches = |msg: &str| {
+        logger.matches(
+            &Record::builder()
+                .args(format_args!("{}", msg))
+                .level(Level::Info)
+                .target("app")
+                .build(),
+        )
+    };
+    assert!(matches("request done"));
+    assert!(matches("cache miss"));
+
+    handle.parse("info/request");
+    assert!(matches("request done"));
+    assert!(!matches("cache miss"));
+}
+
+#[test]
+fn test_watch_env_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    fs::write(&path, "RUST_LOG=warn\n").unwrap();
+
+    let logger = builder().build();
+    let watcher = logger
+        .filter_handle()
+        .watch(&path, Duration::from_millis(10))
+        .unwrap();
+    // 启动时立即加载
+    assert_eq!(logger.filter(), LevelFilter::Warn);
+
+    // 保证修改时间发生变化
+    thread::sleep(Duration::from_millis(20));
+    fs::write(&path, "export RUST_LOG=\"debug\"\n").unwrap();
+    assert!(wait_until(|| logger.filter() == LevelFilter::Debug));
+
+    // 停止监听后不再重新加载
+    drop(watcher);
+    thread::sleep(Duration::from_millis(20));
+    fs::write(&path, "RUST_LOG=error\n").unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(logger.filter(), LevelFilter::Debug);
+}
+
+#[test]
+fn test_watch_missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("log.conf");
+
+    let logger = builder().build();
+    let _watcher = logger
+        .filter_handle()
+        .watch(&path, Duration::from_millis(10))
+        .unwrap();
+    assert_eq!(logger.filter(), LevelFilter::Info);
+
+    fs::write(&path, "error\napp=trace\n").unwrap();
+    assert!(wait_until(|| logger.filter() == LevelFilter::Trace));
+    assert!(!logger.enabled(&metadata(Level::Warn, "other")));
+}
@@ -0,0 +1,148 @@
+use logger::fmt::{FileTarget, RollingFile, Rotation, Target, WriteStyle};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// 获得目录下按名称排序的文件名
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_file_target_appends() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("logs").join("app.log");
+
+    let mut file = RollingFile::new(FileTarget::new(&path));
+    file.write_all(b"first\n").unwrap();
+    file.write_all(b"second\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+}
+
+#[test]
+fn test_rotation_by_size_keeps_max_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+
+    let mut file = RollingFile::new(
+        FileTarget::new(&path).rotation(Rotation::Size(10)).max_files(2),
+    );
+    for i in 0..5 {
+        file.write_all(format!("record {}\n", i).as_bytes()).unwrap();
+    }
+
+    let names = file_names(dir.path());
+    // 当前文件 + 最多 2 个历史文件
+    assert_eq!(names.len(), 3, "{:?}", names);
+    assert!(names.contains(&"app.log".to_string()));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "record 4\n");
+}
+
+#[test]
+fn test_prune_keeps_unrelated_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    fs::write(dir.path().join("app.log.bak"), "backup").unwrap();
+    fs::write(dir.path().join("app.log.old.gz"), "old").unwrap();
+
+    let mut file = RollingFile::new(
+        FileTarget::new(&path).rotation(Rotation::Size(10)).max_files(1),
+    );
+    for i in 0..3 {
+        file.write_all(format!("record {}\n", i).as_bytes()).unwrap();
+    }
+
+    let names = file_names(dir.path());
+    // 当前文件 + 1 个历史文件 + 用户自己的文件
+    assert_eq!(names.len(), 4, "{:?}", names);
+    assert!(names.contains(&"app.log.bak".to_string()));
+    assert!(names.contains(&"app.log.old.gz".to_string()));
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_rotation_compresses_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+
+    let mut file = RollingFile::new(
+        FileTarget::new(&path).rotation(Rotation::Size(4)).compress(true),
+    );
+    file.write_all(b"aaaa").unwrap();
+    file.write_all(b"bbbb").unwrap();
+    file.write_all(b"cccc").unwrap();
+    // 压缩在后台线程中进行，flush 等待它完成
+    file.flush().unwrap();
+
+    let names = file_names(dir.path());
+    assert_eq!(names.len(), 3, "{:?}", names);
+    assert_eq!(names.iter().filter(|name| name.ends_with(".gz")).count(), 2);
+}
+
+#[test]
+fn test_reopen_after_move() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let moved = dir.path().join("app.log.old");
+
+    let mut file =
+        RollingFile::new(FileTarget::new(&path).reopen_on_sighup(true));
+    file.write_all(b"before\n").unwrap();
+
+    // 模拟 logrotate 移走文件后发送 SIGHUP
+    fs::rename(&path, &moved).unwrap();
+    logger::fmt::request_reopen();
+    file.write_all(b"after\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "before\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "after\n");
+}
+
+#[test]
+fn test_no_reopen_without_sighup_option() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let moved = dir.path().join("app.log.old");
+
+    let mut file = RollingFile::new(FileTarget::new(&path));
+    file.write_all(b"before\n").unwrap();
+
+    fs::rename(&path, &moved).unwrap();
+    logger::fmt::request_reopen();
+    file.write_all(b"after\n").unwrap();
+
+    // 继续写入已经移走的文件
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "before\nafter\n");
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_builder_file_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+
+    let writer = logger::fmt::WriteBuilder::new()
+        .target(Target::File(FileTarget::new(&path)))
+        .write_style(WriteStyle::Always)
+        .build();
+    let mut formatter = logger::fmt::Formatter::new(&writer);
+    write!(formatter, "hello").unwrap();
+    #[cfg(feature = "color")]
+    {
+        let mut style = formatter.style();
+        style.set_color(logger::fmt::termcolor::Color::Red);
+        write!(formatter, " {}", style.value("red")).unwrap();
+    }
+    formatter.print(&writer).unwrap();
+
+    // 文件里不写颜色
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(!content.contains('\x1b'), "{:?}", content);
+    assert!(content.starts_with("hello"));
+}
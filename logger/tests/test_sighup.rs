@@ -0,0 +1,39 @@
+#![cfg(unix)]
+
+use logger::fmt::{FileTarget, RollingFile};
+use nix::sys::signal::{self, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use std::fs;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PREVIOUS_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn previous_handler(_: nix::libc::c_int) {
+    PREVIOUS_CALLS.fetch_add(1, Ordering::SeqCst);
+}
+
+// 信号处理函数是进程级的，本文件只包含一个测试
+#[test]
+fn test_sighup_reopens_and_chains_previous_handler() {
+    let action = SigAction::new(
+        SigHandler::Handler(previous_handler),
+        SaFlags::SA_RESTART,
+        SigSet::empty(),
+    );
+    unsafe { signal::sigaction(Signal::SIGHUP, &action) }.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let moved = dir.path().join("app.log.old");
+    let mut file =
+        RollingFile::new(FileTarget::new(&path).reopen_on_sighup(true));
+    file.write_all(b"before\n").unwrap();
+
+    fs::rename(&path, &moved).unwrap();
+    signal::raise(Signal::SIGHUP).unwrap();
+    file.write_all(b"after\n").unwrap();
+
+    assert_eq!(PREVIOUS_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "before\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "after\n");
+}
@@ -0,0 +1,118 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use logger::fmt::{FileTarget, LogFormat, Target, WriteStyle};
+use logger::Sink;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn target(&self) -> Target {
+        Target::Pipe(Box::new(self.clone()))
+    }
+
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn log(logger: &impl Log, level: Level, target: &str, msg: &str) {
+    logger.log(
+        &Record::builder()
+            .args(format_args!("{}", msg))
+            .level(level)
+            .target(target)
+            .build(),
+    );
+}
+
+#[test]
+fn test_route_by_target_and_level() {
+    let (errors, audit, all) =
+        (SharedBuf::default(), SharedBuf::default(), SharedBuf::default());
+    let logger = logger::Builder::new()
+        .filter_level(LevelFilter::Error)
+        .format_timestamp(None)
+        .target(errors.target())
+        .sink(
+            Sink::new(audit.target())
+                .parse_filters("off,audit=info")
+                .log_format(LogFormat::Logfmt),
+        )
+        .sink(Sink::new(all.target()).filter_level(LevelFilter::Trace))
+        .build();
+
+    log(&logger, Level::Info, "audit::login", "user logged in");
+    log(&logger, Level::Error, "app", "disk full");
+    log(&logger, Level::Debug, "app", "cache miss");
+
+    assert_eq!(errors.lines(), vec!["[ERROR app] disk full"]);
+
+    let audit = audit.lines();
+    assert_eq!(audit.len(), 1);
+    assert!(audit[0].contains("level=INFO target=audit::login"));
+    assert!(audit[0].contains("msg=\"user logged in\""));
+
+    assert_eq!(all.lines().len(), 3);
+}
+
+#[test]
+fn test_sinks_extend_enabled_levels() {
+    let audit = SharedBuf::default();
+    let logger = logger::Builder::new()
+        .filter_level(LevelFilter::Error)
+        .target(SharedBuf::default().target())
+        .sink(
+            Sink::new(audit.target())
+                .filter_module("audit", LevelFilter::Debug),
+        )
+        .build();
+
+    let metadata = |level, target| {
+        Metadata::builder().level(level).target(target).build()
+    };
+    assert_eq!(logger.filter(), LevelFilter::Debug);
+    assert!(logger.enabled(&metadata(Level::Debug, "audit")));
+    assert!(!logger.enabled(&metadata(Level::Debug, "app")));
+    assert!(logger.enabled(&metadata(Level::Error, "app")));
+}
+
+#[test]
+fn test_colored_writer_does_not_leak_into_file_sink() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let logger = logger::Builder::new()
+        .filter_level(LevelFilter::Error)
+        .target(Target::Stderr)
+        .write_style(WriteStyle::Always)
+        .sink(
+            Sink::new(Target::File(FileTarget::new(&path)))
+                .filter_level(LevelFilter::Error)
+                .write_style(WriteStyle::Always),
+        )
+        .build();
+
+    log(&logger, Level::Error, "app", "disk full");
+    log(&logger, Level::Error, "app", "disk still full");
+    logger.flush();
+
+    // 文件里不写颜色，即使与终端共用线程本地的格式化缓存
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("\x1b["), "{:?}", content);
+    assert_eq!(content.lines().count(), 2);
+}
@@ -0,0 +1,217 @@
+use log::{Level, Log, Record};
+use logger::fmt::{
+    writer::WriteBuilder, Facility, Formatter, JournaldFormat, LogFormat,
+    Rfc3164Format, Rfc5424Format, Severity, Syslog, Target, Transport,
+    WriteStyle,
+};
+use std::io::Read;
+use std::net::{TcpListener, UdpSocket};
+use std::thread;
+
+fn record_args<'a>(args: std::fmt::Arguments<'a>) -> Record<'a> {
+    Record::builder()
+        .args(args)
+        .level(Level::Warn)
+        .file(Some("test.rs"))
+        .line(Some(144))
+        .module_path(Some("test::path"))
+        .target("target")
+        .build()
+}
+
+fn syslog() -> Syslog {
+    Syslog { pid: 42, ..Syslog::new().hostname("host").app_name("app") }
+}
+
+fn written(f: &Formatter) -> Vec<u8> {
+    f.buf.borrow().bytes().to_vec()
+}
+
+#[test]
+fn test_level_to_severity() {
+    assert_eq!(Severity::from(logger::Level::Error), Severity::Error);
+    assert_eq!(Severity::from(logger::Level::Warn), Severity::Warning);
+    assert_eq!(Severity::from(logger::Level::Info), Severity::Informational);
+    assert_eq!(Severity::from(logger::Level::Debug), Severity::Debug);
+    assert_eq!(Severity::from(Level::Trace), Severity::Debug);
+}
+
+#[test]
+fn test_rfc5424_format() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let key_values = vec![("service".to_string(), "a \"b\"]".to_string())];
+    let syslog = syslog().facility(Facility::Daemon);
+
+    Rfc5424Format {
+        timestamp: None,
+        syslog: &syslog,
+        key_values: &key_values,
+        buf: &mut f,
+    }
+    .write(&record_args(format_args!("disk almost full")))
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(written(&f)).unwrap(),
+        "<28>1 - host app 42 target \
+         [fields@32473 service=\"a \\\"b\\\"\\]\"] disk almost full"
+    );
+}
+
+#[test]
+fn test_rfc3164_format() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let syslog = syslog();
+
+    Rfc3164Format { syslog: &syslog, buf: &mut f }
+        .write(&record_args(format_args!("disk almost full")))
+        .unwrap();
+
+    let written = String::from_utf8(written(&f)).unwrap();
+    assert!(written.starts_with("<12>"), "{}", written);
+    assert!(
+        written.ends_with(" host app[42]: disk almost full"),
+        "{}",
+        written
+    );
+}
+
+#[test]
+fn test_journald_format() {
+    let writer = WriteBuilder::new().write_style(WriteStyle::Never).build();
+    let mut f = Formatter::new(&writer);
+    let key_values = vec![("request-id".to_string(), "7".to_string())];
+    let syslog = syslog();
+
+    JournaldFormat { syslog: &syslog, key_values: &key_values, buf: &mut f }
+        .write(&record_args(format_args!("two\nlines")))
+        .unwrap();
+
+    let mut expected =
+        b"PRIORITY=4\nSYSLOG_FACILITY=1\nSYSLOG_IDENTIFIER=app\n\
+                         SYSLOG_PID=42\nMESSAGE\n"
+            .to_vec();
+    expected.extend_from_slice(&9u64.to_le_bytes());
+    expected.extend_from_slice(
+        b"two\nlines\nTARGET=target\nCODE_MODULE=test::path\n\
+          CODE_FILE=test.rs\nCODE_LINE=144\nREQUEST_ID=7\n",
+    );
+    assert_eq!(written(&f), expected);
+}
+
+fn socket_logger(
+    transport: Transport,
+    log_format: LogFormat,
+) -> logger::Builder {
+    let mut builder = logger::Builder::new();
+    builder
+        .filter_level(log::LevelFilter::Info)
+        .format_timestamp(None)
+        .log_format(log_format)
+        .syslog(syslog())
+        .target(Target::Socket(transport));
+    builder
+}
+
+#[test]
+fn test_udp_target() {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let logger =
+        socket_logger(Transport::Udp(addr), LogFormat::Rfc5424).build();
+
+    logger.log(&record_args(format_args!("first")));
+    logger.log(&record_args(format_args!("second")));
+
+    let mut buf = [0; 1024];
+    for msg in ["first", "second"] {
+        let n = listener.recv(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            format!("<12>1 - host app 42 target - {}", msg)
+        );
+    }
+}
+
+#[test]
+fn test_tcp_target_octet_counting() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let reader = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        received
+    });
+
+    let logger =
+        socket_logger(Transport::Tcp(addr), LogFormat::Rfc5424).build();
+    logger.log(&record_args(format_args!("first")));
+    logger.log(&record_args(format_args!("second")));
+    drop(logger);
+
+    assert_eq!(
+        reader.join().unwrap(),
+        "34 <12>1 - host app 42 target - first\
+         35 <12>1 - host app 42 target - second"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_datagram_target() {
+    use std::os::unix::net::UnixDatagram;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal.socket");
+    let listener = UnixDatagram::bind(&path).unwrap();
+    let logger =
+        socket_logger(Transport::Unix(path), LogFormat::Journald).build();
+
+    logger.log(&record_args(format_args!("ready")));
+
+    let mut buf = [0; 1024];
+    let n = listener.recv(&mut buf).unwrap();
+    let received = std::str::from_utf8(&buf[..n]).unwrap();
+    assert!(received.starts_with("PRIORITY=4\n"), "{}", received);
+    assert!(received.contains("\nMESSAGE=ready\n"), "{}", received);
+    assert!(
+        received.contains("\nCODE_FILE=test.rs\nCODE_LINE=144\n"),
+        "{}",
+        received
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_datagram_target_sends_binary_fields() {
+    use std::os::unix::net::UnixDatagram;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal.socket");
+    let listener = UnixDatagram::bind(&path).unwrap();
+    let logger =
+        socket_logger(Transport::Unix(path), LogFormat::Journald).build();
+
+    // 长度在 128 到 255 之间，小端编码的首字节不是合法的 UTF-8
+    let message = format!("{}\n{}", "a".repeat(100), "b".repeat(100));
+    logger.log(&record_args(format_args!("{}", message)));
+
+    let mut buf = [0; 1024];
+    let n = listener.recv(&mut buf).unwrap();
+    let received = &buf[..n];
+
+    let field = b"\nMESSAGE\n";
+    let start = received
+        .windows(field.len())
+        .position(|w| w == field)
+        .expect("binary MESSAGE field")
+        + field.len();
+    let len = u64::from_le_bytes(received[start..start + 8].try_into().unwrap());
+    assert_eq!(len, message.len() as u64);
+    let value = &received[start + 8..start + 8 + message.len()];
+    assert_eq!(value, message.as_bytes());
+    assert_eq!(received[start + 8 + message.len()], b'\n');
+}
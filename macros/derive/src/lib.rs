@@ -0,0 +1,296 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument,
+    PathArguments, Type,
+};
+
+/// Derive a `<Struct>Predicate` builder with one method per field.
+///
+/// ```ignore
+/// #[derive(FieldPredicate)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let predicate = UserPredicate::new()
+///     .name(predicates::str::starts_with("a"))
+///     .age(predicates::gt(18));
+/// assert!(predicate.eval(&user));
+/// ```
+///
+/// `String`, `PathBuf`, `Vec<T>` and `Box<T>` fields take a predicate over
+/// `str`, `Path`, `[T]` and `T`. Use `#[predicate(target = "Type")]` to pick
+/// another borrowed type, and `#[predicate(skip)]` to leave a field out.
+#[proc_macro_derive(FieldPredicate, attributes(predicate))]
+pub fn derive_field_predicate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// 一个字段的定义
+struct Field {
+    ident: syn::Ident,
+    target: Type,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "FieldPredicate does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "FieldPredicate requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "FieldPredicate can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut parsed = Vec::new();
+    for field in fields {
+        let mut skip = false;
+        let mut target = None;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("predicate") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("target") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    target = Some(value.parse::<Type>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip` or `target = \"Type\"`"))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
+        let ident = field.ident.clone().expect("named field");
+        let target = target.unwrap_or_else(|| borrowed_type(&field.ty));
+        parsed.push(Field { ident, target });
+    }
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let predicate = format_ident!("{}Predicate", name);
+    let doc = format!(
+        "Predicate over the fields of [`{}`], created with `{}::new()`.",
+        name, predicate
+    );
+    let core = quote!(::core_utils::predicates::core);
+
+    // 直接保存 trait 对象，find_case 才能返回字段断言自己的子用例
+    let members = parsed.iter().map(|Field { ident, target }| {
+        quote! {
+            #ident: ::std::option::Option<
+                ::std::boxed::Box<
+                    dyn #core::Predicate<#target> + Send + Sync,
+                >,
+            >
+        }
+    });
+    let setters = parsed.iter().map(|Field { ident, target }| {
+        let doc = format!("Check the `{}` field with `predicate`.", ident);
+        let key = ident.to_string();
+        quote! {
+            #[doc = #doc]
+            pub fn #ident<P>(mut self, predicate: P) -> Self
+            where
+                P: #core::Predicate<#target> + Send + Sync + 'static,
+            {
+                self.#ident = ::std::option::Option::Some(
+                    ::std::boxed::Box::new(
+                        ::core_utils::predicates::PredicateNameExt::name(
+                            predicate, #key,
+                        ),
+                    ),
+                );
+                self
+            }
+        }
+    });
+    // 字段的值，借用为断言的类型
+    let value = |Field { ident, target }: &Field| {
+        quote! {
+            ::std::borrow::Borrow::<#target>::borrow(&variable.#ident)
+        }
+    };
+    let evals = parsed.iter().map(|field| {
+        let ident = &field.ident;
+        let value = value(field);
+        quote! {
+            if let ::std::option::Option::Some(predicate) = &self.#ident {
+                if !#core::Predicate::eval(&**predicate, #value) {
+                    return false;
+                }
+            }
+        }
+    });
+    let cases = parsed.iter().map(|field| {
+        let ident = &field.ident;
+        let value = value(field);
+        quote! {
+            if let ::std::option::Option::Some(predicate) = &self.#ident {
+                match #core::Predicate::find_case(&**predicate, expected, #value)
+                {
+                    ::std::option::Option::Some(child) => {
+                        children.push(child)
+                    }
+                    ::std::option::Option::None => failed = true,
+                }
+            }
+        }
+    });
+    let children = parsed.iter().map(|Field { ident, .. }| {
+        let key = ident.to_string();
+        quote! {
+            if let ::std::option::Option::Some(predicate) = &self.#ident {
+                children.push(#core::Child::new(#key, &**predicate));
+            }
+        }
+    });
+    let debugs = parsed.iter().map(|Field { ident, .. }| {
+        let key = ident.to_string();
+        quote! {
+            .field(
+                #key,
+                &self.#ident.as_ref().map(|predicate| predicate.to_string()),
+            )
+        }
+    });
+    let names = parsed.iter().map(|Field { ident, .. }| {
+        let key = ident.to_string();
+        quote! {
+            if self.#ident.is_some() {
+                names.push(#key);
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Default)]
+        #vis struct #predicate {
+            #(#members,)*
+        }
+
+        impl #predicate {
+            /// Create a predicate that checks no field yet.
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            #(#setters)*
+        }
+
+        impl #core::Predicate<#name> for #predicate {
+            fn eval(&self, variable: &#name) -> bool {
+                #(#evals)*
+                true
+            }
+
+            fn find_case<'a>(
+                &'a self,
+                expected: bool,
+                variable: &#name,
+            ) -> ::std::option::Option<#core::Case<'a>> {
+                // 期望成功时所有字段都要成功，期望失败时报告所有失败的字段
+                let mut children = ::std::vec::Vec::new();
+                let mut failed = false;
+                #(#cases)*
+                if (expected && failed) || (!expected && children.is_empty()) {
+                    return ::std::option::Option::None;
+                }
+                let mut case = #core::Case::new(
+                    ::std::option::Option::Some(self),
+                    expected,
+                );
+                for child in children {
+                    case = case.add_child(child);
+                }
+                ::std::option::Option::Some(case)
+            }
+        }
+
+        impl #core::PredicateReflection for #predicate {
+            fn children<'a>(
+                &'a self,
+            ) -> ::std::boxed::Box<
+                dyn ::std::iter::Iterator<Item = #core::Child<'a>> + 'a,
+            > {
+                let mut children = ::std::vec::Vec::new();
+                #(#children)*
+                ::std::boxed::Box::new(children.into_iter())
+            }
+        }
+
+        impl ::std::fmt::Debug for #predicate {
+            fn fmt(
+                &self,
+                f: &mut ::std::fmt::Formatter<'_>,
+            ) -> ::std::fmt::Result {
+                f.debug_struct(stringify!(#predicate))
+                    #(#debugs)*
+                    .finish()
+            }
+        }
+
+        impl ::std::fmt::Display for #predicate {
+            fn fmt(
+                &self,
+                f: &mut ::std::fmt::Formatter<'_>,
+            ) -> ::std::fmt::Result {
+                let mut names: ::std::vec::Vec<&str> = ::std::vec::Vec::new();
+                #(#names)*
+                write!(f, "{} {{ {} }}", stringify!(#name), names.join(", "))
+            }
+        }
+    })
+}
+
+/// 常见的拥有所有权的类型，断言它们借用的类型
+fn borrowed_type(ty: &Type) -> Type {
+    let Type::Path(path) = ty else {
+        return ty.clone();
+    };
+    let Some(last) = path.path.segments.last() else {
+        return ty.clone();
+    };
+    let argument = match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(GenericArgument::Type(argument)) => Some(argument),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    match (last.ident.to_string().as_str(), argument) {
+        ("String", None) => syn::parse_quote!(str),
+        ("PathBuf", None) => syn::parse_quote!(::std::path::Path),
+        ("OsString", None) => syn::parse_quote!(::std::ffi::OsStr),
+        ("Vec", Some(argument)) => syn::parse_quote!([#argument]),
+        ("Box", Some(argument)) => argument.clone(),
+        _ => ty.clone(),
+    }
+}
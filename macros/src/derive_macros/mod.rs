@@ -0,0 +1 @@
+pub use macros_derive::FieldPredicate;
@@ -1,4 +1,6 @@
 #[macro_use]
 mod declarative_macros;
+mod derive_macros;
 
 pub use declarative_macros::*;
+pub use derive_macros::*;
@@ -0,0 +1,107 @@
+use core_utils::predicates::{
+    self,
+    core::{CaseTreeExt, Predicate, PredicateReflection},
+    PredicateBooleanExt,
+};
+use macros::FieldPredicate;
+use std::path::PathBuf;
+
+#[derive(FieldPredicate)]
+struct User {
+    name: String,
+    age: u32,
+    tags: Vec<String>,
+    home: PathBuf,
+    #[predicate(skip)]
+    #[allow(dead_code)]
+    password: String,
+}
+
+fn user() -> User {
+    User {
+        name: "alice".to_owned(),
+        age: 30,
+        tags: vec!["admin".to_owned()],
+        home: PathBuf::from("/home/alice"),
+        password: "secret".to_owned(),
+    }
+}
+
+#[test]
+fn test_field_predicate_eval() {
+    let predicate_fn = UserPredicate::new()
+        .name(predicates::str::starts_with("a"))
+        .age(predicates::gt(18));
+    assert!(predicate_fn.eval(&user()));
+
+    let predicate_fn = predicate_fn.age(predicates::lt(18));
+    assert!(!predicate_fn.eval(&user()));
+
+    // 没有设置任何字段时总是成功
+    assert!(UserPredicate::new().eval(&user()));
+}
+
+#[test]
+fn test_field_predicate_borrowed_types() {
+    let predicate_fn = UserPredicate::new()
+        .tags(predicates::len(predicates::eq(1)))
+        .home(predicates::function(|path: &std::path::Path| {
+            path.ends_with("alice")
+        }));
+    assert!(predicate_fn.eval(&user()));
+}
+
+#[test]
+fn test_field_predicate_find_case() {
+    let predicate_fn = UserPredicate::new()
+        .name(predicates::str::starts_with("b"))
+        .age(predicates::gt(18))
+        .tags(predicates::any(predicates::eq("guest".to_owned())));
+
+    let case = predicate_fn.find_case(false, &user()).unwrap();
+    // 报告所有失败的字段
+    let fields: Vec<String> = case
+        .children()
+        .map(|child| child.predicate().unwrap().to_string())
+        .collect();
+    assert_eq!(fields, ["name", "tags"]);
+    let tree = case.tree().to_string();
+    assert!(tree.contains("starts_with"));
+    assert!(predicate_fn.find_case(true, &user()).is_none());
+
+    let predicate_fn = UserPredicate::new().age(predicates::gt(18));
+    let case = predicate_fn.find_case(true, &user()).unwrap();
+    assert_eq!(case.children().count(), 1);
+    assert!(predicate_fn.find_case(false, &user()).is_none());
+}
+
+#[test]
+fn test_field_predicate_nested_case() {
+    let predicate_fn =
+        UserPredicate::new().age(predicates::gt(18).and(predicates::lt(20)));
+
+    let case = predicate_fn.find_case(false, &user()).unwrap();
+    let age = case.children().next().unwrap();
+    assert_eq!(age.predicate().unwrap().to_string(), "age");
+    // 字段断言自己的用例树也被保留，能看到是哪个条件失败
+    let and = age.children().next().unwrap();
+    let failed: Vec<String> = and
+        .children()
+        .map(|child| child.predicate().unwrap().to_string())
+        .collect();
+    assert_eq!(failed, ["var < 20"]);
+}
+
+#[test]
+fn test_field_predicate_reflection() {
+    let predicate_fn = UserPredicate::new()
+        .name(predicates::str::is_empty().not())
+        .age(predicates::between(18, 65));
+
+    let children: Vec<String> =
+        predicate_fn.children().map(|child| child.name().to_owned()).collect();
+    assert_eq!(children, ["name", "age"]);
+    assert_eq!(predicate_fn.to_string(), "User { name, age }");
+    assert!(format!("{:?}", predicate_fn)
+        .starts_with("UserPredicate { name: Some("));
+}